@@ -6,7 +6,6 @@ use minecraft_launcher_lib::{
     AuthenticatorConfig, Authenticator, Account,
     init_logger,
 };
-use serde_json;
 use std::fs;
 
 #[tokio::main]
@@ -26,7 +25,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Check if we have a saved account
     let account_file = "account.json";
     let account = if let Ok(account_data) = fs::read_to_string(account_file) {
-        let mut account: Account = serde_json::from_str(&account_data)?;
+        let account: Account = serde_json::from_str(&account_data)?;
         
         println!("📱 Found saved account: {}", account.name);
         
@@ -157,9 +156,13 @@ pub async fn check_account_status(account_file: &str) -> Result<(), Box<dyn std:
     Ok(())
 }
 
-/// Logout (delete saved account)
-pub fn logout(account_file: &str) -> Result<(), Box<dyn std::error::Error>> {
+/// Logout (end the Microsoft session and delete saved account)
+pub async fn logout(authenticator: &Authenticator, account_file: &str) -> Result<(), Box<dyn std::error::Error>> {
     if std::path::Path::new(account_file).exists() {
+        let account_data = fs::read_to_string(account_file)?;
+        if let Ok(account) = serde_json::from_str::<Account>(&account_data) {
+            authenticator.logout(&account).await?;
+        }
         fs::remove_file(account_file)?;
         println!("🚪 Logged out - account file deleted");
     } else {
@@ -29,7 +29,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📁 Minecraft directory: {}", config.minecraft_dir.display());
 
     // Create launcher
-    let launcher = Launcher::new(config).await?;
+    let _launcher = Launcher::new(config).await?;
     println!("✅ Launcher initialized");
 
     // Set up version manager
@@ -95,7 +95,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 download_count += 1;
             }
             if let Some(classifiers) = &downloads.classifiers {
-                for (_, download_info) in classifiers {
+                for download_info in classifiers.values() {
                     total_size += download_info.size;
                     download_count += 1;
                 }
@@ -188,11 +188,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 fn print_progress(downloaded: u64, total: u64) {
-    let percentage = if total > 0 {
-        (downloaded * 100) / total
-    } else {
-        0
-    };
+    let percentage = (downloaded * 100).checked_div(total).unwrap_or(0);
 
     let bar_length = 30;
     let filled_length = (percentage * bar_length as u64) / 100;
@@ -272,7 +268,7 @@ pub async fn calculate_version_download_size(
                 total_size += artifact.size;
             }
             if let Some(classifiers) = &downloads.classifiers {
-                for (_, download_info) in classifiers {
+                for download_info in classifiers.values() {
                     total_size += download_info.size;
                 }
             }
@@ -297,10 +293,10 @@ pub async fn get_download_statistics(
 ) -> Result<Vec<(String, u64, usize)>, Box<dyn std::error::Error>> {
     let mut statistics = Vec::new();
 
-    for version_id in version_ids {
-        let version_entry = version_manager.find_version(version_id).await?;
-        let version_info = version_manager.fetch_version_info(&version_entry).await?;
-        
+    let version_infos = version_manager.fetch_version_infos(version_ids).await?;
+    for (version_id, version_info) in version_ids.iter().zip(version_infos) {
+        let version_info = version_info?;
+
         let total_size = calculate_version_download_size(&version_info).await;
         let file_count = version_info.libraries.len() + 1; // +1 for client jar
 
@@ -4,12 +4,11 @@
 //! like Forge, Fabric, Quilt, etc.
 
 use minecraft_launcher_lib::{
-    Launcher, LauncherConfig, AuthenticatorConfig, Account,
+    Launcher, LauncherConfig, Account,
     ModLoaderType, VersionManager,
     init_logger,
 };
 use std::path::PathBuf;
-use serde_json;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -43,6 +42,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("===================================");
     
     let minecraft_version = "1.21.4";
+    if !ModLoaderType::Fabric.supports_version(minecraft_version) {
+        println!("⚠️  Fabric does not support Minecraft {}", minecraft_version);
+    }
     println!("🔍 Checking available Fabric versions for {}...", minecraft_version);
     
     let fabric_versions = version_manager.get_mod_loader_versions(
@@ -54,8 +56,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let latest_fabric = &fabric_versions[0];
         println!("✅ Latest Fabric version: {}", latest_fabric.version);
         
-        let mut launch_config = launcher.create_launch_config(minecraft_version, &account).await?;
-        launch_config = launch_config.with_mod_loader(
+        let launch_config = launcher.create_launch_config(minecraft_version, &account).await?;
+        let _launch_config = launch_config.with_mod_loader(
             ModLoaderType::Fabric,
             latest_fabric.version.clone(),
         );
@@ -81,8 +83,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let latest_forge = &forge_versions[0];
         println!("✅ Latest Forge version: {}", latest_forge.version);
         
-        let mut launch_config = launcher.create_launch_config(minecraft_version, &account).await?;
-        launch_config = launch_config.with_mod_loader(
+        let launch_config = launcher.create_launch_config(minecraft_version, &account).await?;
+        let _launch_config = launch_config.with_mod_loader(
             ModLoaderType::Forge,
             latest_forge.version.clone(),
         );
@@ -102,8 +104,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let shader_packs_dir = launcher.get_config().minecraft_dir.join("shaderpacks");
     let saves_dir = launcher.get_config().minecraft_dir.join("saves");
 
-    let mut custom_launch_config = launcher.create_launch_config(minecraft_version, &account).await?;
-    custom_launch_config = custom_launch_config
+    let custom_launch_config = launcher.create_launch_config(minecraft_version, &account).await?;
+    let _custom_launch_config = custom_launch_config
         .with_mod_loader(ModLoaderType::Fabric, "0.16.10".to_string())
         .with_custom_dirs(
             Some(mods_dir.clone()),
@@ -168,7 +170,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 async fn load_account() -> Result<Account, Box<dyn std::error::Error>> {
     // In practice, this would load from a file or perform authentication
     // For this example, we'll create a dummy account
-    use minecraft_launcher_lib::{ProfileInfo, SkinInfo, CapeInfo};
+    use minecraft_launcher_lib::{AccountType, ProfileInfo, SkinInfo};
     use chrono::Utc;
 
     Ok(Account {
@@ -177,7 +179,7 @@ async fn load_account() -> Result<Account, Box<dyn std::error::Error>> {
         access_token: "example_token".to_string(),
         refresh_token: "example_refresh_token".to_string(),
         expires_at: Utc::now() + chrono::Duration::hours(1),
-        account_type: "msa".to_string(),
+        account_type: AccountType::Microsoft,
         profile: ProfileInfo {
             id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
             name: "ExamplePlayer".to_string(),
@@ -191,6 +193,8 @@ async fn load_account() -> Result<Account, Box<dyn std::error::Error>> {
             ],
             capes: vec![],
         },
+        xuid: None,
+        client_id: None,
     })
 }
 
@@ -218,70 +222,3 @@ pub async fn list_installed_mods(mods_dir: &PathBuf) -> Result<Vec<String>, Box<
     mods.sort();
     Ok(mods)
 }
-
-/// Check mod loader compatibility
-pub fn check_mod_compatibility(mod_loader: &ModLoaderType, minecraft_version: &str) -> bool {
-    // Simplified compatibility check
-    match mod_loader {
-        ModLoaderType::Forge => {
-            // Forge supports most versions from 1.2.5 onwards
-            true
-        }
-        ModLoaderType::Fabric => {
-            // Fabric supports versions from 1.14 onwards primarily
-            let version_parts: Vec<&str> = minecraft_version.split('.').collect();
-            if version_parts.len() >= 2 {
-                if let (Ok(major), Ok(minor)) = (version_parts[0].parse::<i32>(), version_parts[1].parse::<i32>()) {
-                    major > 1 || (major == 1 && minor >= 14)
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        }
-        ModLoaderType::Quilt => {
-            // Quilt is based on Fabric, similar compatibility
-            let version_parts: Vec<&str> = minecraft_version.split('.').collect();
-            if version_parts.len() >= 2 {
-                if let (Ok(major), Ok(minor)) = (version_parts[0].parse::<i32>(), version_parts[1].parse::<i32>()) {
-                    major > 1 || (major == 1 && minor >= 17)
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        }
-        ModLoaderType::NeoForge => {
-            // NeoForge is newer, supports 1.20.1+
-            let version_parts: Vec<&str> = minecraft_version.split('.').collect();
-            if version_parts.len() >= 3 {
-                if let (Ok(major), Ok(minor), Ok(patch)) = (
-                    version_parts[0].parse::<i32>(),
-                    version_parts[1].parse::<i32>(),
-                    version_parts[2].parse::<i32>()
-                ) {
-                    major > 1 || (major == 1 && minor > 20) || (major == 1 && minor == 20 && patch >= 1)
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        }
-        ModLoaderType::LegacyFabric => {
-            // Legacy Fabric for older versions
-            let version_parts: Vec<&str> = minecraft_version.split('.').collect();
-            if version_parts.len() >= 2 {
-                if let (Ok(major), Ok(minor)) = (version_parts[0].parse::<i32>(), version_parts[1].parse::<i32>()) {
-                    major == 1 && minor < 14
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        }
-    }
-}
@@ -4,6 +4,10 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use crate::error::{LauncherError, Result};
+use tokio_util::sync::CancellationToken;
+
+/// Endpoint for equipping (PUT) and hiding (DELETE) the active cape
+const ACTIVE_CAPE_URL: &str = "https://api.minecraftservices.com/minecraft/profile/capes/active";
 
 /// Microsoft account information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,10 +22,306 @@ pub struct Account {
     pub refresh_token: String,
     /// Token expiration time
     pub expires_at: DateTime<Utc>,
-    /// Account type (typically "msa" for Microsoft)
-    pub account_type: String,
+    /// Kind of account, which determines the `user_type` passed to the game
+    #[serde(default)]
+    pub account_type: AccountType,
     /// Additional profile information
     pub profile: ProfileInfo,
+    /// Xbox user ID, passed to the game as `${auth_xuid}`
+    #[serde(default)]
+    pub xuid: Option<String>,
+    /// Client ID passed to the game as `${clientid}`: the Azure application that signed in
+    #[serde(default)]
+    pub client_id: Option<String>,
+}
+
+/// Kind of account an [`Account`] was signed in with
+///
+/// Serialized as `"msa"`, `"offline"` and `"third_party"`; the older strings `"legacy"` and
+/// `"mojang"` are still accepted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AccountType {
+    /// Microsoft account
+    #[default]
+    #[serde(rename = "msa", alias = "")]
+    Microsoft,
+    /// Offline account without authentication
+    #[serde(rename = "offline", alias = "legacy")]
+    Offline,
+    /// Third-party authentication server (e.g. authlib-injector)
+    #[serde(rename = "third_party", alias = "mojang")]
+    ThirdParty,
+}
+
+impl AccountType {
+    /// Value for the game's `${user_type}` argument
+    pub fn user_type(&self) -> &'static str {
+        match self {
+            AccountType::Microsoft => "msa",
+            AccountType::Offline => "legacy",
+            AccountType::ThirdParty => "mojang",
+        }
+    }
+}
+
+impl std::fmt::Display for AccountType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AccountType::Microsoft => "Microsoft",
+            AccountType::Offline => "Offline",
+            AccountType::ThirdParty => "Third-party",
+        };
+        f.write_str(name)
+    }
+}
+
+/// How long before expiry a token counts as expiring soon by default
+pub const DEFAULT_TOKEN_REFRESH_BUFFER: chrono::Duration = chrono::Duration::minutes(5);
+
+/// Freshness of an account's access token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenStatus {
+    /// Usable and not close to expiry
+    Valid,
+    /// Still usable, but should be refreshed in the background
+    ExpiringSoon,
+    /// No longer usable; refresh before launching
+    Expired,
+}
+
+impl Account {
+    /// Token status using the default 5-minute refresh buffer
+    pub fn token_status(&self) -> TokenStatus {
+        self.token_status_with_buffer(DEFAULT_TOKEN_REFRESH_BUFFER)
+    }
+
+    /// Token status, treating tokens that expire within `buffer` as expiring soon
+    pub fn token_status_with_buffer(&self, buffer: chrono::Duration) -> TokenStatus {
+        let now = Utc::now();
+        if self.expires_at <= now {
+            TokenStatus::Expired
+        } else if self.expires_at <= now + buffer {
+            TokenStatus::ExpiringSoon
+        } else {
+            TokenStatus::Valid
+        }
+    }
+}
+
+/// Result of a sign-in or token refresh, for keeping a stored account list up to date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthOutcome {
+    /// The signed-in account
+    pub account: Account,
+    /// An existing account's tokens were refreshed rather than a new sign-in completed
+    pub was_refreshed: bool,
+    /// The Minecraft username differs from the one previously stored for this account
+    pub name_changed: bool,
+}
+
+/// What `Launcher::refresh_all_accounts` did with one stored account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AccountRefreshStatus {
+    /// The token isn't close to expiry, or the account has no Microsoft token to refresh
+    UpToDate,
+    /// The token was refreshed and the account store updated
+    Refreshed { name_changed: bool },
+    /// The account has no usable refresh token or Microsoft rejected it; the user must sign in again
+    ReauthRequired,
+    /// The refresh failed, e.g. offline or a service outage; retrying later may work
+    Failed { error: String },
+}
+
+/// Refresh result for one account, see [`AccountRefreshStatus`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountRefresh {
+    pub uuid: String,
+    pub name: String,
+    pub status: AccountRefreshStatus,
+}
+
+/// File name of the account store inside the minecraft directory
+pub const ACCOUNT_STORE_FILE: &str = "accounts.json";
+
+/// Signed-in accounts and which one is active, persisted as JSON
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountStore {
+    accounts: Vec<Account>,
+    /// UUID of the account picked with `set_active_account`
+    #[serde(default)]
+    active: Option<String>,
+    #[serde(skip)]
+    path: std::path::PathBuf,
+}
+
+impl AccountStore {
+    /// Load the store from `path`; a missing file gives an empty store, and a corrupt one is
+    /// moved to `accounts.json.corrupt` and replaced by an empty store
+    pub async fn load(path: std::path::PathBuf) -> Result<Self> {
+        let mut store = match tokio::fs::read(&path).await {
+            Ok(content) => match serde_json::from_slice::<AccountStore>(&content) {
+                Ok(store) => store,
+                Err(e) => {
+                    crate::utils::quarantine_corrupt_file(&path, &e.to_string());
+                    AccountStore::default()
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => AccountStore::default(),
+            Err(e) => {
+                return Err(LauncherError::file(format!("Failed to read account store {}: {}", path.display(), e)));
+            }
+        };
+        store.path = path;
+        Ok(store)
+    }
+
+    /// Write the store back to the file it was loaded from. It holds refresh tokens, so on Unix
+    /// only the current user can read it.
+    pub async fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| LauncherError::file(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+        let content = serde_json::to_vec_pretty(self)?;
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            crate::utils::write_atomic_private(&path, &content)
+                .map_err(|e| LauncherError::file(format!("Failed to write account store {}: {}", path.display(), e)))
+        })
+        .await
+        .map_err(|e| LauncherError::other(format!("Writing account store failed: {}", e)))?
+    }
+
+    /// All stored accounts
+    pub fn accounts(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    /// Stored account with the given UUID
+    pub fn get(&self, uuid: &str) -> Option<&Account> {
+        self.accounts.iter().find(|account| account.uuid == uuid)
+    }
+
+    /// Add an account, replacing a stored account with the same UUID
+    pub fn upsert(&mut self, account: Account) {
+        match self.accounts.iter_mut().find(|stored| stored.uuid == account.uuid) {
+            Some(stored) => *stored = account,
+            None => self.accounts.push(account),
+        }
+    }
+
+    /// Remove an account; it stops being active if it was
+    pub fn remove(&mut self, uuid: &str) -> Option<Account> {
+        let index = self.accounts.iter().position(|account| account.uuid == uuid)?;
+        if self.active.as_deref() == Some(uuid) {
+            self.active = None;
+        }
+        Some(self.accounts.remove(index))
+    }
+
+    /// The active account: the one picked with `set_active_account`, or the only stored
+    /// account when there is exactly one
+    pub fn active_account(&self) -> Option<&Account> {
+        match &self.active {
+            Some(uuid) => self.get(uuid),
+            None if self.accounts.len() == 1 => self.accounts.first(),
+            None => None,
+        }
+    }
+
+    /// Make a stored account the active one
+    pub fn set_active_account(&mut self, uuid: &str) -> Result<()> {
+        if self.get(uuid).is_none() {
+            return Err(LauncherError::config(format!("No stored account with UUID {}", uuid)));
+        }
+        self.active = Some(uuid.to_string());
+        Ok(())
+    }
+}
+
+/// Account lists of the official launcher, inside its `.minecraft` directory
+const VANILLA_ACCOUNT_FILES: [&str; 2] = ["launcher_accounts.json", "launcher_accounts_microsoft_store.json"];
+
+#[derive(Deserialize)]
+struct VanillaAccountsFile {
+    #[serde(default)]
+    accounts: std::collections::HashMap<String, VanillaAccountEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VanillaAccountEntry {
+    #[serde(rename = "type", default)]
+    account_type: String,
+    #[serde(default)]
+    username: String,
+    access_token: Option<String>,
+    access_token_expires_at: Option<DateTime<Utc>>,
+    minecraft_profile: Option<VanillaMinecraftProfile>,
+}
+
+#[derive(Deserialize)]
+struct VanillaMinecraftProfile {
+    id: String,
+    name: String,
+}
+
+/// Accounts found in an official launcher directory
+#[derive(Debug, Default)]
+pub(crate) struct VanillaAccounts {
+    /// Microsoft accounts with a readable, unexpired Minecraft access token
+    pub usable: Vec<Account>,
+    /// Names of accounts that have to sign in again
+    pub needing_sign_in: Vec<String>,
+}
+
+/// Read the accounts of an official launcher directory
+///
+/// Only the Minecraft access token can be carried over: the official launcher keeps refresh
+/// tokens encrypted, and recent versions encrypt the access token as well. Imported accounts
+/// therefore have no refresh token and must sign in again once the access token expires.
+pub(crate) fn read_vanilla_accounts(mc_dir: &std::path::Path) -> Result<VanillaAccounts> {
+    let mut found = VanillaAccounts::default();
+    for file_name in VANILLA_ACCOUNT_FILES {
+        let path = mc_dir.join(file_name);
+        if !path.exists() {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| LauncherError::file(format!("Failed to read {}: {}", path.display(), e)))?;
+        let file: VanillaAccountsFile = serde_json::from_str(&content)
+            .map_err(|e| LauncherError::json(format!("Failed to parse {}: {}", path.display(), e)))?;
+
+        for entry in file.accounts.into_values() {
+            let display_name = entry.minecraft_profile.as_ref().map(|profile| profile.name.clone()).unwrap_or(entry.username);
+            let usable = match (entry.account_type.as_str(), entry.access_token, entry.access_token_expires_at, entry.minecraft_profile) {
+                ("Xbox", Some(access_token), Some(expires_at), Some(profile)) if !access_token.is_empty() && expires_at > Utc::now() => {
+                    Some(Account {
+                        uuid: profile.id.clone(),
+                        name: profile.name.clone(),
+                        xuid: xuid_from_token(&access_token),
+                        access_token,
+                        refresh_token: String::new(),
+                        expires_at,
+                        account_type: AccountType::Microsoft,
+                        profile: ProfileInfo { id: profile.id, name: profile.name, skins: Vec::new(), capes: Vec::new() },
+                        client_id: None,
+                    })
+                }
+                _ => None,
+            };
+            match usable {
+                Some(account) if !found.usable.iter().any(|stored| stored.uuid == account.uuid) => found.usable.push(account),
+                Some(_) => {}
+                None if !found.needing_sign_in.contains(&display_name) => found.needing_sign_in.push(display_name),
+                None => {}
+            }
+        }
+    }
+    Ok(found)
 }
 
 /// Minecraft profile information
@@ -76,6 +376,10 @@ pub struct AuthenticatorConfig {
     pub user_agent: Option<String>,
     /// Timeout for authentication requests (seconds)
     pub timeout: u64,
+    /// How often a failed Xbox Live, XSTS, Minecraft or profile request is retried
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for each further attempt
+    pub retry_backoff: std::time::Duration,
 }
 
 impl Default for AuthenticatorConfig {
@@ -89,6 +393,8 @@ impl Default for AuthenticatorConfig {
             ],
             user_agent: Some(format!("MinecraftLauncher/{}", crate::VERSION)),
             timeout: 300,
+            max_retries: 2,
+            retry_backoff: std::time::Duration::from_millis(500),
         }
     }
 }
@@ -125,9 +431,17 @@ impl AuthenticatorConfig {
         self.timeout = timeout;
         self
     }
+
+    /// Set how often individual auth steps are retried and the initial backoff between attempts
+    pub fn with_retries(mut self, max_retries: u32, backoff: std::time::Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_backoff = backoff;
+        self
+    }
 }
 
 /// Microsoft authenticator for Minecraft
+#[derive(Clone)]
 pub struct Authenticator {
     config: AuthenticatorConfig,
     client: reqwest::Client,
@@ -181,24 +495,58 @@ impl Authenticator {
         // Step 4: Authenticate with Minecraft
         let minecraft_token = self.authenticate_minecraft(&xsts_token).await?;
         
-        // Step 5: Get profile information
-        let profile = self.get_minecraft_profile(&minecraft_token).await?;
-        
-        // Step 6: Check game ownership
-        self.check_game_ownership(&minecraft_token).await?;
+        // Step 5: Get profile information and check game ownership; both only need the token
+        let (profile, ownership) = tokio::join!(
+            self.get_minecraft_profile(&minecraft_token),
+            self.check_game_ownership(&minecraft_token),
+        );
+        // Accounts without the game have no profile either, so the ownership error is the useful one
+        ownership?;
+        let profile = profile?;
 
+        let xuid = xsts_token.xuid().or_else(|| xuid_from_token(&minecraft_token));
         Ok(Account {
             uuid: profile.id.clone(),
             name: profile.name.clone(),
             access_token: minecraft_token,
             refresh_token: token_response.refresh_token.unwrap_or_default(),
             expires_at: Utc::now() + chrono::Duration::seconds(token_response.expires_in as i64),
-            account_type: "msa".to_string(),
+            account_type: AccountType::Microsoft,
             profile,
+            xuid,
+            client_id: Some(self.config.client_id.clone()),
+        })
+    }
+
+    /// Complete the OAuth flow and compare the result against already stored accounts.
+    /// `name_changed` is set when an account with the same UUID had a different name.
+    pub async fn authenticate_with_code_outcome(&self, auth_code: String, known_accounts: &[Account]) -> Result<AuthOutcome> {
+        let account = self.authenticate_with_code(auth_code).await?;
+        let name_changed = known_accounts
+            .iter()
+            .any(|known| known.uuid == account.uuid && known.name != account.name);
+        Ok(AuthOutcome {
+            account,
+            was_refreshed: false,
+            name_changed,
+        })
+    }
+
+    /// Refresh an account and report whether its username changed
+    pub async fn refresh_account_outcome(&self, account: &Account) -> Result<AuthOutcome> {
+        let refreshed = self.refresh_account(account).await?;
+        let name_changed = refreshed.name != account.name;
+        Ok(AuthOutcome {
+            account: refreshed,
+            was_refreshed: true,
+            name_changed,
         })
     }
 
     /// Refresh an existing account's tokens
+    ///
+    /// Fails with [`AuthErrorKind::ReauthRequired`](crate::AuthErrorKind::ReauthRequired) when Microsoft rejects the
+    /// refresh token; the caller should prompt for a new login instead of retrying.
     pub async fn refresh_account(&self, account: &Account) -> Result<Account> {
         if account.refresh_token.is_empty() {
             return Err(LauncherError::auth("No refresh token available"));
@@ -213,26 +561,126 @@ impl Authenticator {
         let minecraft_token = self.authenticate_minecraft(&xsts_token).await?;
         let profile = self.get_minecraft_profile(&minecraft_token).await?;
 
+        let xuid = xsts_token.xuid().or_else(|| xuid_from_token(&minecraft_token));
         Ok(Account {
             uuid: profile.id.clone(),
             name: profile.name.clone(),
             access_token: minecraft_token,
             refresh_token: token_response.refresh_token.unwrap_or_else(|| account.refresh_token.clone()),
             expires_at: Utc::now() + chrono::Duration::seconds(token_response.expires_in as i64),
-            account_type: account.account_type.clone(),
+            account_type: account.account_type,
             profile,
+            xuid,
+            client_id: Some(self.config.client_id.clone()),
         })
     }
 
+    /// Visit Microsoft's logout page for this client (best effort)
+    ///
+    /// This revokes nothing: the request carries no browser cookies, so neither the refresh
+    /// token nor the Minecraft access token is invalidated. Signing out is local only; callers
+    /// must delete their stored copy of the account, see [`crate::Launcher::logout`].
+    pub async fn logout(&self, account: &Account) -> Result<()> {
+        if account.refresh_token.is_empty() {
+            return Ok(());
+        }
+
+        let logout_url = format!(
+            "https://login.live.com/oauth20_logout.srf?client_id={}&redirect_uri=https://login.live.com/oauth20_desktop.srf",
+            self.config.client_id
+        );
+
+        match self.client.get(&logout_url).send().await {
+            Ok(response) if response.status().is_success() => {
+                log::info!("Signed out Microsoft session for {}", account.name);
+            }
+            Ok(response) => {
+                log::warn!("Microsoft logout for {} returned status {}", account.name, response.status());
+            }
+            Err(e) => {
+                log::warn!("Microsoft logout request for {} failed: {}", account.name, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check whether Minecraft services still accept the account's access token.
+    ///
+    /// Returns `Ok(false)` when the session is rejected; network failures are returned as errors
+    /// so callers can tell "invalid" apart from "couldn't check".
+    pub async fn validate_session(&self, account: &Account) -> Result<bool> {
+        let response = self.client
+            .get("https://api.minecraftservices.com/minecraft/profile")
+            .bearer_auth(&account.access_token)
+            .send()
+            .await
+            .map_err(|e| LauncherError::from_auth_request("Session validation request failed", e))?;
+
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => Ok(false),
+            status => Err(LauncherError::auth(format!("Session validation failed with status {}", status))),
+        }
+    }
+
+    /// Equip one of the account's capes
+    ///
+    /// `cape_id` must be one of `account.profile.capes`. Returns the updated profile, which
+    /// callers should store back into the account.
+    pub async fn set_active_cape(&self, account: &Account, cape_id: &str) -> Result<ProfileInfo> {
+        if !account.profile.capes.iter().any(|cape| cape.id == cape_id) {
+            return Err(LauncherError::auth(format!("{} does not own cape {}", account.name, cape_id)));
+        }
+
+        let payload = serde_json::json!({ "capeId": cape_id });
+        let response = self
+            .send_with_retry("Cape change request failed", || {
+                self.client.put(ACTIVE_CAPE_URL).bearer_auth(&account.access_token).json(&payload)
+            })
+            .await?;
+
+        Self::parse_cape_response(response).await
+    }
+
+    /// Unequip the account's active cape
+    ///
+    /// Returns the updated profile, which callers should store back into the account.
+    pub async fn hide_cape(&self, account: &Account) -> Result<ProfileInfo> {
+        let response = self
+            .send_with_retry("Cape change request failed", || {
+                self.client.delete(ACTIVE_CAPE_URL).bearer_auth(&account.access_token)
+            })
+            .await?;
+
+        Self::parse_cape_response(response).await
+    }
+
+    async fn parse_cape_response(response: reqwest::Response) -> Result<ProfileInfo> {
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(LauncherError::auth(format!("Cape change failed with status {}: {}", status, error_text)));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| LauncherError::auth(format!("Failed to parse cape change response: {}", e)))
+    }
+
     /// Check if an account's token is still valid
     pub fn is_token_valid(&self, account: &Account) -> bool {
-        account.expires_at > Utc::now() + chrono::Duration::minutes(5) // 5-minute buffer
+        account.token_status() == TokenStatus::Valid
     }
 
     /// Start device code flow for authentication
     /// Note: Microsoft Live.com doesn't support standard device code flow, so we'll simulate it
     /// by generating a device code locally and using the standard authorization flow
-    pub async fn start_device_code_flow(&self) -> Result<DeviceCodeResponse> {
+    ///
+    /// Returns a [`DeviceCodeFlow`] handle; call [`DeviceCodeFlow::next_poll`] in a loop until it
+    /// completes or expires.
+    pub async fn start_device_code_flow(&self) -> Result<DeviceCodeFlow> {
         // Since Live.com doesn't support device code flow, we'll create a simulated response
         // that directs users to the standard OAuth flow
         use std::time::{SystemTime, UNIX_EPOCH};
@@ -257,18 +705,97 @@ impl Authenticator {
             self.config.client_id
         );
 
-        Ok(DeviceCodeResponse {
+        let response = DeviceCodeResponse {
             device_code,
             user_code,
             verification_uri,
             expires_in: 900, // 15 minutes
             interval: 5,     // Poll every 5 seconds
             message: Some("Please visit the URL and sign in with your Microsoft account".to_string()),
-        })
+        };
+
+        Ok(DeviceCodeFlow::new(self.clone(), response))
+    }
+
+    /// Start a browser login whose redirect is caught by a local HTTP server
+    ///
+    /// The server listens on the port of the configured `redirect_uri`, which must be an
+    /// `http://localhost` (or `127.0.0.1`) address. For `localhost` the server listens on both
+    /// `127.0.0.1` and `::1`, since browsers may resolve it to either. If the port is taken, an
+    /// ephemeral port is used instead and the redirect URI adjusted to match, which Azure apps
+    /// registered for `http://localhost` accept. Open [`LocalServerFlow::auth_url`] in a browser, then call
+    /// [`LocalServerFlow::wait_for_account`].
+    pub async fn start_local_server_flow(&self) -> Result<LocalServerFlow> {
+        let mut redirect = reqwest::Url::parse(&self.config.redirect_uri)
+            .map_err(|e| LauncherError::config(format!("Invalid redirect URI {}: {}", self.config.redirect_uri, e)))?;
+        if redirect.scheme() != "http" || !matches!(redirect.host_str(), Some("localhost" | "127.0.0.1")) {
+            return Err(LauncherError::config(format!(
+                "Redirect URI {} is not a local http address",
+                self.config.redirect_uri
+            )));
+        }
+
+        let port = redirect.port_or_known_default().unwrap_or(80);
+        let listener = match tokio::net::TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::warn!("Cannot listen on port {} for the sign-in redirect ({}), using a free port", port, e);
+                tokio::net::TcpListener::bind(("127.0.0.1", 0))
+                    .await
+                    .map_err(|e| LauncherError::auth(format!("Failed to start the sign-in redirect server: {}", e)))?
+            }
+        };
+        let bound_port = listener
+            .local_addr()
+            .map_err(|e| LauncherError::auth(format!("Failed to start the sign-in redirect server: {}", e)))?
+            .port();
+        if bound_port != port {
+            let _ = redirect.set_port(Some(bound_port));
+        }
+        let ipv6_listener = if redirect.host_str() == Some("localhost") {
+            tokio::net::TcpListener::bind(("::1", bound_port))
+                .await
+                .inspect_err(|e| log::debug!("Not listening on [::1]:{} for the sign-in redirect: {}", bound_port, e))
+                .ok()
+        } else {
+            None
+        };
+
+        let mut authenticator = self.clone();
+        authenticator.config.redirect_uri = redirect.to_string();
+        let state = uuid::Uuid::new_v4().simple().to_string();
+        let auth_url = reqwest::Url::parse_with_params(
+            "https://login.live.com/oauth20_authorize.srf",
+            [
+                ("client_id", self.config.client_id.as_str()),
+                ("response_type", "code"),
+                ("redirect_uri", authenticator.config.redirect_uri.as_str()),
+                ("scope", &self.config.scopes.join(" ")),
+                ("state", &state),
+                ("prompt", "select_account"),
+            ],
+        )
+        .map_err(|e| LauncherError::auth(format!("Failed to build the sign-in URL: {}", e)))?
+        .to_string();
+
+        Ok(LocalServerFlow { authenticator, listener, ipv6_listener, auth_url, state, cancel: None })
     }
 
-    /// Poll for device code completion
+    /// Poll once for device code completion.
+    ///
+    /// While the user hasn't finished signing in this fails with an error whose message contains
+    /// the OAuth code (`authorization_pending`, `slow_down`, `expired_token`). Prefer
+    /// [`DeviceCodeFlow::next_poll`], which handles those for you.
     pub async fn poll_device_code(&self, device_code: &str) -> Result<Account> {
+        match self.request_device_token(device_code).await? {
+            DeviceTokenPoll::Complete(token_response) => self.complete_authentication_with_token(token_response).await,
+            DeviceTokenPoll::Pending => Err(LauncherError::auth("authorization_pending")),
+            DeviceTokenPoll::SlowDown => Err(LauncherError::auth("slow_down")),
+            DeviceTokenPoll::Expired => Err(LauncherError::auth("expired_token")),
+        }
+    }
+
+    async fn request_device_token(&self, device_code: &str) -> Result<DeviceTokenPoll> {
         let params = [
             ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
             ("client_id", self.config.client_id.as_str()),
@@ -280,15 +807,34 @@ impl Authenticator {
             .form(&params)
             .send()
             .await
-            .map_err(|e| LauncherError::auth(format!("Device code poll request failed: {}", e)))?;
+            .map_err(|e| LauncherError::from_auth_request("Device code poll request failed", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            let error: OAuthErrorResponse = serde_json::from_str(&error_text).unwrap_or_default();
+            return match error.error.as_deref() {
+                Some("authorization_pending") => Ok(DeviceTokenPoll::Pending),
+                Some("slow_down") => Ok(DeviceTokenPoll::SlowDown),
+                Some("expired_token") => Ok(DeviceTokenPoll::Expired),
+                Some(code) => Err(LauncherError::auth(format!(
+                    "Device code authentication failed: {} {}",
+                    code,
+                    error.error_description.unwrap_or_default()
+                ))),
+                None => Err(LauncherError::auth(format!(
+                    "Device code poll failed with status {}: {}",
+                    status, error_text
+                ))),
+            };
+        }
 
         let token_response: TokenResponse = response
             .json()
             .await
             .map_err(|e| LauncherError::auth(format!("Failed to parse token response: {}", e)))?;
 
-        // If we get here, authentication was successful - continue with normal flow
-        self.complete_authentication_with_token(token_response).await
+        Ok(DeviceTokenPoll::Complete(token_response))
     }
 
     /// Complete authentication with a token response (shared by both flows)
@@ -302,20 +848,26 @@ impl Authenticator {
         // Step 4: Authenticate with Minecraft
         let minecraft_token = self.authenticate_minecraft(&xsts_token).await?;
         
-        // Step 5: Get profile information
-        let profile = self.get_minecraft_profile(&minecraft_token).await?;
-        
-        // Step 6: Check game ownership
-        self.check_game_ownership(&minecraft_token).await?;
+        // Step 5: Get profile information and check game ownership; both only need the token
+        let (profile, ownership) = tokio::join!(
+            self.get_minecraft_profile(&minecraft_token),
+            self.check_game_ownership(&minecraft_token),
+        );
+        // Accounts without the game have no profile either, so the ownership error is the useful one
+        ownership?;
+        let profile = profile?;
 
+        let xuid = xsts_token.xuid().or_else(|| xuid_from_token(&minecraft_token));
         Ok(Account {
             uuid: profile.id.clone(),
             name: profile.name.clone(),
             access_token: minecraft_token,
             refresh_token: token_response.refresh_token.unwrap_or_default(),
             expires_at: Utc::now() + chrono::Duration::seconds(token_response.expires_in as i64),
-            account_type: "msa".to_string(),
+            account_type: AccountType::Microsoft,
             profile,
+            xuid,
+            client_id: Some(self.config.client_id.clone()),
         })
     }
 
@@ -335,7 +887,7 @@ impl Authenticator {
             .form(&params)
             .send()
             .await
-            .map_err(|e| LauncherError::auth(format!("Token exchange request failed: {}", e)))?;
+            .map_err(|e| LauncherError::from_auth_request("Token exchange request failed", e))?;
 
         let token_response: TokenResponse = response
             .json()
@@ -358,7 +910,13 @@ impl Authenticator {
             .form(&params)
             .send()
             .await
-            .map_err(|e| LauncherError::auth(format!("Token refresh request failed: {}", e)))?;
+            .map_err(|e| LauncherError::from_auth_request("Token refresh request failed", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(classify_refresh_error(status, &error_text));
+        }
 
         let token_response: TokenResponse = response
             .json()
@@ -368,6 +926,38 @@ impl Authenticator {
         Ok(token_response)
     }
 
+    /// Send a request built by `build`, retrying connection failures, timeouts, 429 and 5xx responses.
+    /// Only used for steps that are safe to repeat; single-use codes are never resent.
+    async fn send_with_retry<F>(&self, step: &str, build: F) -> Result<reqwest::Response>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            let retryable = match build().send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    if !(status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS)
+                        || attempt >= self.config.max_retries
+                    {
+                        return Ok(response);
+                    }
+                    format!("status {}", status)
+                }
+                Err(e) if (e.is_timeout() || e.is_connect()) && attempt < self.config.max_retries => e.to_string(),
+                Err(e) => return Err(LauncherError::from_auth_request(step, e)),
+            };
+
+            let delay = self.config.retry_backoff * 2u32.saturating_pow(attempt);
+            attempt += 1;
+            log::warn!(
+                "{} ({}), retrying in {:?} (attempt {}/{})",
+                step, retryable, delay, attempt, self.config.max_retries
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
     async fn authenticate_xbox_live(&self, access_token: &str) -> Result<String> {
         let payload = serde_json::json!({
             "Properties": {
@@ -379,12 +969,11 @@ impl Authenticator {
             "TokenType": "JWT"
         });
 
-        let response = self.client
-            .post("https://user.auth.xboxlive.com/user/authenticate")
-            .json(&payload)
-            .send()
-            .await
-            .map_err(|e| LauncherError::auth(format!("Xbox Live authentication failed: {}", e)))?;
+        let response = self
+            .send_with_retry("Xbox Live authentication failed", || {
+                self.client.post("https://user.auth.xboxlive.com/user/authenticate").json(&payload)
+            })
+            .await?;
 
         let xbox_response: XboxLiveResponse = response
             .json()
@@ -404,12 +993,11 @@ impl Authenticator {
             "TokenType": "JWT"
         });
 
-        let response = self.client
-            .post("https://xsts.auth.xboxlive.com/xsts/authorize")
-            .json(&payload)
-            .send()
-            .await
-            .map_err(|e| LauncherError::auth(format!("XSTS authentication failed: {}", e)))?;
+        let response = self
+            .send_with_retry("XSTS authentication failed", || {
+                self.client.post("https://xsts.auth.xboxlive.com/xsts/authorize").json(&payload)
+            })
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -431,12 +1019,11 @@ impl Authenticator {
             "identityToken": format!("XBL3.0 x={};{}", user_hash, xsts_response.token)
         });
 
-        let response = self.client
-            .post("https://api.minecraftservices.com/authentication/login_with_xbox")
-            .json(&payload)
-            .send()
-            .await
-            .map_err(|e| LauncherError::auth(format!("Minecraft authentication failed: {}", e)))?;
+        let response = self
+            .send_with_retry("Minecraft authentication failed", || {
+                self.client.post("https://api.minecraftservices.com/authentication/login_with_xbox").json(&payload)
+            })
+            .await?;
 
         let minecraft_response: MinecraftAuthResponse = response
             .json()
@@ -447,12 +1034,11 @@ impl Authenticator {
     }
 
     async fn get_minecraft_profile(&self, access_token: &str) -> Result<ProfileInfo> {
-        let response = self.client
-            .get("https://api.minecraftservices.com/minecraft/profile")
-            .bearer_auth(access_token)
-            .send()
-            .await
-            .map_err(|e| LauncherError::auth(format!("Profile request failed: {}", e)))?;
+        let response = self
+            .send_with_retry("Profile request failed", || {
+                self.client.get("https://api.minecraftservices.com/minecraft/profile").bearer_auth(access_token)
+            })
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -469,12 +1055,11 @@ impl Authenticator {
     }
 
     async fn check_game_ownership(&self, access_token: &str) -> Result<()> {
-        let response = self.client
-            .get("https://api.minecraftservices.com/entitlements/mcstore")
-            .bearer_auth(access_token)
-            .send()
-            .await
-            .map_err(|e| LauncherError::auth(format!("Ownership check failed: {}", e)))?;
+        let response = self
+            .send_with_retry("Ownership check failed", || {
+                self.client.get("https://api.minecraftservices.com/entitlements/mcstore").bearer_auth(access_token)
+            })
+            .await?;
 
         let ownership: OwnershipResponse = response
             .json()
@@ -489,8 +1074,284 @@ impl Authenticator {
     }
 }
 
+/// Longest interval the device code poller backs off to
+const MAX_DEVICE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How long a connection to the sign-in redirect server may take to send its request
+const REDIRECT_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Result of one [`DeviceCodeFlow::next_poll`]
+#[derive(Debug)]
+pub enum DeviceCodePoll {
+    /// The user hasn't finished signing in yet; poll again
+    Pending,
+    /// Sign-in finished and the account is ready
+    Complete(Box<Account>),
+    /// The device code expired; start a new flow
+    Expired,
+}
+
+/// Outcome of a single token request with a device code
+enum DeviceTokenPoll {
+    Pending,
+    SlowDown,
+    Expired,
+    Complete(TokenResponse),
+}
+
+/// An in-progress device code login that paces its own polling
+pub struct DeviceCodeFlow {
+    authenticator: Authenticator,
+    response: DeviceCodeResponse,
+    interval: std::time::Duration,
+    expires_at: tokio::time::Instant,
+    cancel: Option<CancellationToken>,
+}
+
+impl DeviceCodeFlow {
+    fn new(authenticator: Authenticator, response: DeviceCodeResponse) -> Self {
+        Self {
+            interval: std::time::Duration::from_secs(response.interval.max(1)),
+            expires_at: tokio::time::Instant::now() + std::time::Duration::from_secs(response.expires_in),
+            authenticator,
+            response,
+            cancel: None,
+        }
+    }
+
+    /// Stop polling as soon as `cancel` is cancelled, e.g. when the user closes the login dialog;
+    /// `next_poll` then fails with [`AuthErrorKind::Cancelled`](crate::AuthErrorKind::Cancelled)
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// The code and URL to show to the user
+    pub fn response(&self) -> &DeviceCodeResponse {
+        &self.response
+    }
+
+    /// Wait the current interval, then poll once.
+    /// `slow_down` responses double the interval (up to a minute) and report [`DeviceCodePoll::Pending`].
+    pub async fn next_poll(&mut self) -> Result<DeviceCodePoll> {
+        match self.cancel.clone() {
+            Some(cancel) => tokio::select! {
+                _ = cancel.cancelled() => Err(LauncherError::auth_cancelled("Device code sign-in was cancelled")),
+                result = self.poll_once() => result,
+            },
+            None => self.poll_once().await,
+        }
+    }
+
+    async fn poll_once(&mut self) -> Result<DeviceCodePoll> {
+        if tokio::time::Instant::now() >= self.expires_at {
+            return Ok(DeviceCodePoll::Expired);
+        }
+
+        tokio::time::sleep(self.interval).await;
+
+        match self.authenticator.request_device_token(&self.response.device_code).await? {
+            DeviceTokenPoll::Pending => Ok(DeviceCodePoll::Pending),
+            DeviceTokenPoll::SlowDown => {
+                self.interval = (self.interval * 2).min(MAX_DEVICE_POLL_INTERVAL);
+                log::debug!("Device code poll asked to slow down, interval is now {:?}", self.interval);
+                Ok(DeviceCodePoll::Pending)
+            }
+            DeviceTokenPoll::Expired => Ok(DeviceCodePoll::Expired),
+            DeviceTokenPoll::Complete(token_response) => {
+                let account = self.authenticator.complete_authentication_with_token(token_response).await?;
+                Ok(DeviceCodePoll::Complete(Box::new(account)))
+            }
+        }
+    }
+}
+
+/// A browser login waiting for its redirect on a local port, see
+/// [`Authenticator::start_local_server_flow`]
+pub struct LocalServerFlow {
+    authenticator: Authenticator,
+    listener: tokio::net::TcpListener,
+    /// Second listener on `[::1]` when the redirect host is `localhost`
+    ipv6_listener: Option<tokio::net::TcpListener>,
+    auth_url: String,
+    state: String,
+    cancel: Option<CancellationToken>,
+}
+
+impl LocalServerFlow {
+    /// The URL to open in the user's browser
+    pub fn auth_url(&self) -> &str {
+        &self.auth_url
+    }
+
+    /// The redirect URI actually in use, which has a different port than configured when that
+    /// port was taken
+    pub fn redirect_uri(&self) -> &str {
+        &self.authenticator.config.redirect_uri
+    }
+
+    /// Give up as soon as `cancel` is cancelled, e.g. when the user closes the login dialog;
+    /// `wait_for_account` then fails with [`AuthErrorKind::Cancelled`](crate::AuthErrorKind::Cancelled)
+    /// and the redirect port is released
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Wait for the browser to be redirected back, then complete the login.
+    /// Requests for other paths (favicons and the like) are answered with 404 and ignored.
+    /// Connections are read in parallel, so one the browser opens and leaves idle doesn't hold
+    /// up the redirect.
+    pub async fn wait_for_account(mut self) -> Result<Account> {
+        // Dropping the flow's future also drops its listener and any request in flight
+        match self.cancel.take() {
+            Some(cancel) => tokio::select! {
+                _ = cancel.cancelled() => Err(LauncherError::auth_cancelled("Browser sign-in was cancelled")),
+                result = self.receive_account() => result,
+            },
+            None => self.receive_account().await,
+        }
+    }
+
+    async fn receive_account(self) -> Result<Account> {
+        use futures::StreamExt;
+
+        let callback_path = reqwest::Url::parse(self.redirect_uri())
+            .map(|url| url.path().to_string())
+            .unwrap_or_else(|_| "/".to_string());
+
+        let mut requests = futures::stream::FuturesUnordered::new();
+        loop {
+            let accept = async {
+                match &self.ipv6_listener {
+                    Some(ipv6_listener) => tokio::select! {
+                        accepted = self.listener.accept() => accepted,
+                        accepted = ipv6_listener.accept() => accepted,
+                    },
+                    None => self.listener.accept().await,
+                }
+            };
+            let (mut stream, target) = tokio::select! {
+                accepted = accept => {
+                    let (stream, _) = accepted.map_err(|e| LauncherError::auth(format!("Sign-in redirect server failed: {}", e)))?;
+                    requests.push(read_request_within(stream));
+                    continue;
+                }
+                Some(request) = requests.next() => request,
+            };
+            let Some(target) = target else {
+                continue;
+            };
+            let Ok(url) = reqwest::Url::parse(&format!("http://localhost{}", target)) else {
+                continue;
+            };
+            if url.path() != callback_path {
+                respond_html(&mut stream, "404 Not Found", "Not found").await;
+                continue;
+            }
+
+            let params: std::collections::HashMap<String, String> = url.query_pairs().into_owned().collect();
+            if params.get("state") != Some(&self.state) {
+                respond_html(&mut stream, "400 Bad Request", "This sign-in link is out of date, please try again.").await;
+                continue;
+            }
+            if let Some(code) = params.get("code") {
+                respond_html(&mut stream, "200 OK", "Signed in. You can close this window and return to the launcher.").await;
+                return self.authenticator.authenticate_with_code(code.clone()).await;
+            }
+
+            respond_html(&mut stream, "200 OK", "Sign-in was not completed. You can close this window.").await;
+            return Err(LauncherError::auth(format!(
+                "Sign-in failed: {} {}",
+                params.get("error").map(String::as_str).unwrap_or("no authorization code"),
+                params.get("error_description").map(String::as_str).unwrap_or_default()
+            ).trim_end().to_string()));
+        }
+    }
+}
+
+/// Read the request target from `stream`, giving up after [`REDIRECT_REQUEST_TIMEOUT`]
+async fn read_request_within(mut stream: tokio::net::TcpStream) -> (tokio::net::TcpStream, Option<String>) {
+    let target = tokio::time::timeout(REDIRECT_REQUEST_TIMEOUT, read_request_target(&mut stream))
+        .await
+        .ok()
+        .flatten();
+    (stream, target)
+}
+
+/// Request target (`/path?query`) of an HTTP request, read from its request line
+async fn read_request_target(stream: &mut tokio::net::TcpStream) -> Option<String> {
+    use tokio::io::AsyncReadExt;
+
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") && request.len() < 16 * 1024 {
+        let read = stream.read(&mut buffer).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request.lines().next()?.split_whitespace();
+    match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(target)) => Some(target.to_string()),
+        _ => None,
+    }
+}
+
+/// Answer the browser with a minimal HTML page
+async fn respond_html(stream: &mut tokio::net::TcpStream, status: &str, message: &str) {
+    use tokio::io::AsyncWriteExt;
+
+    let body = format!("<!DOCTYPE html><html><body><p>{}</p></body></html>", message);
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// OAuth error codes meaning the refresh token is dead and retrying will not help
+const REAUTH_ERROR_CODES: &[&str] = &["invalid_grant", "interaction_required", "consent_required"];
+
+/// AADSTS codes for revoked consent, password changes and expired or revoked grants
+const REAUTH_AADSTS_CODES: &[&str] = &["AADSTS50173", "AADSTS65001", "AADSTS70000", "AADSTS70008", "AADSTS700082"];
+
+/// Turn a failed refresh response into an error, flagging cases that need a fresh login
+fn classify_refresh_error(status: reqwest::StatusCode, body: &str) -> LauncherError {
+    let error: OAuthErrorResponse = serde_json::from_str(body).unwrap_or_default();
+    let description = error.error_description.unwrap_or_default();
+
+    let reauth_required = error.error.as_deref().is_some_and(|code| REAUTH_ERROR_CODES.contains(&code))
+        || REAUTH_AADSTS_CODES.iter().any(|code| description.contains(code));
+
+    let message = format!(
+        "Token refresh failed with status {}: {} {}",
+        status,
+        error.error.as_deref().unwrap_or("unknown_error"),
+        description
+    );
+
+    if reauth_required {
+        LauncherError::reauth_required(message.trim_end())
+    } else {
+        LauncherError::auth(message.trim_end())
+    }
+}
+
 // Response structures for API calls
 
+#[derive(Debug, Default, Deserialize)]
+struct OAuthErrorResponse {
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct TokenResponse {
     access_token: String,
@@ -502,7 +1363,7 @@ struct TokenResponse {
     refresh_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct DeviceCodeResponse {
     pub device_code: String,
     pub user_code: String,
@@ -535,6 +1396,27 @@ struct DisplayClaims {
 #[derive(Debug, Deserialize)]
 struct UserInfo {
     uhs: String,
+    /// Only present for some relying parties
+    #[serde(default)]
+    xid: Option<String>,
+}
+
+impl XstsResponse {
+    fn xuid(&self) -> Option<String> {
+        self.display_claims.xui.first().and_then(|user| user.xid.clone())
+    }
+}
+
+/// Read the `xuid` claim from a Minecraft access token (a JWT)
+fn xuid_from_token(token: &str) -> Option<String> {
+    use base64::Engine;
+
+    let payload = token.split('.').nth(1)?;
+    let payload = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&payload).ok()?;
+    claims.get("xuid").and_then(|xuid| xuid.as_str()).map(str::to_string)
 }
 
 #[derive(Debug, Deserialize)]
@@ -554,3 +1436,148 @@ struct OwnershipItem {
 }
 
 // Add urlencoding dependency
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    async fn start_flow(redirect_uri: String) -> LocalServerFlow {
+        let config = AuthenticatorConfig::new("test-client".to_string()).with_redirect_uri(redirect_uri);
+        Authenticator::new(config).unwrap().start_local_server_flow().await.unwrap()
+    }
+
+    fn query_param(url: &str, name: &str) -> String {
+        let url = reqwest::Url::parse(url).unwrap();
+        url.query_pairs().find(|(key, _)| key == name).unwrap().1.into_owned()
+    }
+
+    fn redirect_port(flow: &LocalServerFlow) -> u16 {
+        reqwest::Url::parse(flow.redirect_uri()).unwrap().port().unwrap()
+    }
+
+    async fn send_callback(port: u16, target: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn local_server_flow_moves_off_a_taken_port() {
+        let occupied = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let taken_port = occupied.local_addr().unwrap().port();
+
+        let flow = start_flow(format!("http://localhost:{}/callback", taken_port)).await;
+        let port = redirect_port(&flow);
+        assert_ne!(port, taken_port);
+        assert_eq!(flow.redirect_uri(), format!("http://localhost:{}/callback", port));
+        assert_eq!(query_param(flow.auth_url(), "redirect_uri"), flow.redirect_uri());
+    }
+
+    #[tokio::test]
+    async fn local_server_flow_rejects_a_wrong_state() {
+        let flow = start_flow("http://127.0.0.1:0/callback".to_string()).await;
+        let port = redirect_port(&flow);
+        let state = query_param(flow.auth_url(), "state");
+        let login = tokio::spawn(flow.wait_for_account());
+
+        let response = send_callback(port, "/callback?state=forged&code=stolen").await;
+        assert!(response.starts_with("HTTP/1.1 400"), "{}", response);
+        assert!(!login.is_finished());
+
+        let response = send_callback(port, &format!("/callback?state={}&error=access_denied", state)).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        let error = login.await.unwrap().unwrap_err();
+        assert!(error.to_string().contains("access_denied"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn idle_connection_does_not_block_the_redirect() {
+        let flow = start_flow("http://127.0.0.1:0/callback".to_string()).await;
+        let port = redirect_port(&flow);
+        let state = query_param(flow.auth_url(), "state");
+        let login = tokio::spawn(flow.wait_for_account());
+
+        // Browsers open spare connections and may never send anything on them
+        let _idle = tokio::net::TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let target = format!("/callback?state={}&error=access_denied", state);
+        let callback = send_callback(port, &target);
+        let response = tokio::time::timeout(std::time::Duration::from_secs(2), callback).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        let error = login.await.unwrap().unwrap_err();
+        assert!(error.to_string().contains("access_denied"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn cancelled_local_server_flow_releases_its_port() {
+        let cancel = CancellationToken::new();
+        let flow = start_flow("http://127.0.0.1:0/callback".to_string()).await.with_cancellation(cancel.clone());
+        let port = redirect_port(&flow);
+        let login = tokio::spawn(flow.wait_for_account());
+
+        cancel.cancel();
+        let error = login.await.unwrap().unwrap_err();
+        assert!(error.is_auth_cancelled(), "{}", error);
+        assert!(tokio::net::TcpListener::bind(("127.0.0.1", port)).await.is_ok());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn account_store_is_only_readable_by_its_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(ACCOUNT_STORE_FILE);
+        // Written world-readable by an older version
+        std::fs::write(&path, b"{\"accounts\": []}").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        // Left over from a crash mid-save
+        std::fs::write(dir.path().join("accounts.json.tmp"), b"").unwrap();
+
+        let store = AccountStore::load(path.clone()).await.unwrap();
+        store.save().await.unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn reads_vanilla_launcher_accounts() {
+        let dir = tempfile::tempdir().unwrap();
+        let token = "eyJhbGciOiJIUzI1NiJ9.eyJ4dWlkIjogIjI1MzU0MDAwMDAwMDAwMDEifQ.c2ln";
+        let accounts = serde_json::json!({
+            "accounts": {
+                "1": {
+                    "type": "Xbox", "username": "steve@example.com", "accessToken": token,
+                    "accessTokenExpiresAt": "2999-01-01T00:00:00Z",
+                    "minecraftProfile": {"id": "069a79f444e94726a5befca90e38aaf5", "name": "Steve"}
+                },
+                "2": {
+                    "type": "Xbox", "username": "alex@example.com", "accessToken": token,
+                    "accessTokenExpiresAt": "2001-01-01T00:00:00Z",
+                    "minecraftProfile": {"id": "ec561538f3fd461daff5086b22154bce", "name": "Alex"}
+                },
+                "3": {"type": "Mojang", "username": "legacy@example.com"}
+            },
+            "mojangClientToken": "abc"
+        });
+        std::fs::write(dir.path().join(VANILLA_ACCOUNT_FILES[0]), accounts.to_string()).unwrap();
+        // The Microsoft Store launcher keeps its own copy of the same account
+        std::fs::write(dir.path().join(VANILLA_ACCOUNT_FILES[1]), accounts.to_string()).unwrap();
+
+        let found = read_vanilla_accounts(dir.path()).unwrap();
+        assert_eq!(found.usable.len(), 1);
+        let steve = &found.usable[0];
+        assert_eq!(steve.name, "Steve");
+        assert_eq!(steve.uuid, "069a79f444e94726a5befca90e38aaf5");
+        assert_eq!(steve.account_type, AccountType::Microsoft);
+        assert_eq!(steve.xuid.as_deref(), Some("2535400000000001"));
+        assert!(steve.refresh_token.is_empty());
+
+        let mut needing_sign_in = found.needing_sign_in.clone();
+        needing_sign_in.sort();
+        assert_eq!(needing_sign_in, ["Alex", "legacy@example.com"]);
+    }
+}
@@ -2,9 +2,11 @@
 
 use std::path::PathBuf;
 use std::collections::HashMap;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use crate::auth::Account;
-use crate::version::ModLoaderType;
+use crate::instance::InstanceMetadata;
+use crate::version::{ModLoaderType, VersionSource};
 
 /// Main launcher configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +15,9 @@ pub struct LauncherConfig {
     pub minecraft_dir: PathBuf,
     /// Java executable path (optional, will auto-detect if None)
     pub java_path: Option<PathBuf>,
+    /// Java executable to use per major Java version, checked before `java_path` and auto-detection
+    #[serde(default)]
+    pub java_pins: HashMap<u32, PathBuf>,
     /// JVM arguments
     pub jvm_args: Vec<String>,
     /// Game arguments
@@ -20,7 +25,7 @@ pub struct LauncherConfig {
     /// Memory allocation (in MB)
     pub memory_min: u32,
     pub memory_max: u32,
-    /// Download timeout in seconds
+    /// Download timeout in seconds (how long a connection may sit idle, not a cap on file size)
     pub download_timeout: u64,
     /// Number of concurrent downloads
     pub concurrent_downloads: usize,
@@ -28,6 +33,139 @@ pub struct LauncherConfig {
     pub env_vars: HashMap<String, String>,
     /// Whether to enable debug logging
     pub debug: bool,
+    /// What to do about conflicting JVM flags (duplicate heap sizes, several garbage collectors)
+    #[serde(default)]
+    pub jvm_arg_validation: JvmArgValidation,
+    /// Base URLs of Mojang's download hosts (mirrors, local mock servers)
+    #[serde(default)]
+    pub endpoints: MojangEndpoints,
+    /// Architecture whose native classifiers are preferred (e.g. "arm64" or "x86_64"); by
+    /// default the architecture of the Java runtime the game launches with
+    #[serde(default)]
+    pub preferred_natives_arch: Option<String>,
+    /// How strictly downloads and files already on disk are checked against their hashes
+    #[serde(default)]
+    pub download_verification: DownloadVerification,
+    /// Split large downloads into parallel range requests; `None` downloads every file in one request
+    #[serde(default)]
+    pub multipart_downloads: Option<MultipartDownloadConfig>,
+    /// Launcher name passed to the game as `${launcher_name}`
+    #[serde(default = "default_launcher_name")]
+    pub launcher_name: String,
+    /// Launcher version passed to the game as `${launcher_version}`
+    #[serde(default = "default_launcher_version")]
+    pub launcher_version: String,
+}
+
+fn default_launcher_name() -> String {
+    env!("CARGO_PKG_NAME").to_string()
+}
+
+fn default_launcher_version() -> String {
+    crate::VERSION.to_string()
+}
+
+/// Base URLs used in place of Mojang's hosts
+///
+/// URLs on the default hosts, including those inside version JSONs and asset indexes, are
+/// rewritten to the configured bases before they are requested.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MojangEndpoints {
+    /// Version manifests and version JSONs (`piston-meta.mojang.com`, `launchermeta.mojang.com`)
+    pub meta: String,
+    /// Asset objects (`resources.download.minecraft.net`)
+    pub resources: String,
+    /// Libraries (`libraries.minecraft.net`)
+    pub libraries: String,
+    /// Client jars, mappings and logging configs (`piston-data.mojang.com`, `launcher.mojang.com`)
+    pub data: String,
+}
+
+const DEFAULT_META_HOSTS: [&str; 2] = ["https://piston-meta.mojang.com", "https://launchermeta.mojang.com"];
+pub(crate) const DEFAULT_RESOURCES_HOST: &str = "https://resources.download.minecraft.net";
+const DEFAULT_LIBRARIES_HOST: &str = "https://libraries.minecraft.net";
+/// Both serve the same files under the same paths
+pub(crate) const DEFAULT_DATA_HOSTS: [&str; 2] = ["https://piston-data.mojang.com", "https://launcher.mojang.com"];
+
+impl Default for MojangEndpoints {
+    fn default() -> Self {
+        Self {
+            meta: DEFAULT_META_HOSTS[0].to_string(),
+            resources: DEFAULT_RESOURCES_HOST.to_string(),
+            libraries: DEFAULT_LIBRARIES_HOST.to_string(),
+            data: DEFAULT_DATA_HOSTS[0].to_string(),
+        }
+    }
+}
+
+impl MojangEndpoints {
+    /// Move a URL on one of Mojang's default hosts to the configured base; other URLs are
+    /// returned unchanged
+    pub fn rewrite(&self, url: &str) -> String {
+        let hosts = DEFAULT_META_HOSTS
+            .iter()
+            .map(|host| (*host, &self.meta))
+            .chain(DEFAULT_DATA_HOSTS.iter().map(|host| (*host, &self.data)))
+            .chain([(DEFAULT_RESOURCES_HOST, &self.resources), (DEFAULT_LIBRARIES_HOST, &self.libraries)]);
+        for (host, base) in hosts {
+            if let Some(path) = url.strip_prefix(host).filter(|path| path.is_empty() || path.starts_with('/')) {
+                return format!("{}{}", base.trim_end_matches('/'), path);
+            }
+        }
+        url.to_string()
+    }
+}
+
+/// How files are checked against the hashes their sources publish
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadVerification {
+    /// Hash nothing: files already present are kept and downloads are trusted as received.
+    /// Only for trusted mirrors or very slow devices.
+    Skip,
+    /// Hash downloads and present files that have a known hash; present files without one are kept
+    #[default]
+    Standard,
+    /// Like `Standard`, but present files without a known hash are sanity-checked instead of
+    /// trusted: empty files and jars without a zip header are downloaded again. Native libraries
+    /// are also re-extracted on every launch.
+    Strict,
+}
+
+/// Settings for downloading single large files over several connections
+///
+/// Only used for files whose size is known from their manifest, on servers that advertise
+/// byte range support; everything else gets a normal download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MultipartDownloadConfig {
+    /// Smallest file size, in bytes, that is split into parts
+    pub min_size: u64,
+    /// Number of parts fetched in parallel
+    pub connections: usize,
+}
+
+impl Default for MultipartDownloadConfig {
+    fn default() -> Self {
+        Self {
+            min_size: 32 * 1024 * 1024, // 32 MiB
+            connections: 4,
+        }
+    }
+}
+
+/// How conflicting JVM arguments are handled before launch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JvmArgValidation {
+    /// Don't check
+    Off,
+    /// Log a warning and launch anyway
+    #[default]
+    Warn,
+    /// Refuse to launch
+    Error,
 }
 
 impl Default for LauncherConfig {
@@ -37,6 +175,7 @@ impl Default for LauncherConfig {
                 .unwrap_or_else(|| PathBuf::from("."))
                 .join(".minecraft"),
             java_path: None,
+            java_pins: HashMap::new(),
             jvm_args: vec![
                 "-XX:+UnlockExperimentalVMOptions".to_string(),
                 "-XX:+UseG1GC".to_string(),
@@ -52,6 +191,13 @@ impl Default for LauncherConfig {
             concurrent_downloads: 8,
             env_vars: HashMap::new(),
             debug: false,
+            jvm_arg_validation: JvmArgValidation::default(),
+            endpoints: MojangEndpoints::default(),
+            download_verification: DownloadVerification::default(),
+            multipart_downloads: None,
+            preferred_natives_arch: None,
+            launcher_name: default_launcher_name(),
+            launcher_version: default_launcher_version(),
         }
     }
 }
@@ -71,6 +217,25 @@ impl LauncherConfig {
         self
     }
 
+    /// Use `java_path` for every version that needs Java `major_version`
+    pub fn pin_java(mut self, major_version: u32, java_path: PathBuf) -> Self {
+        self.java_pins.insert(major_version, java_path);
+        self
+    }
+
+    /// Use other base URLs for Mojang's hosts
+    pub fn with_endpoints(mut self, endpoints: MojangEndpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Identify as this launcher to the game instead of as this library
+    pub fn with_launcher_identity(mut self, name: impl Into<String>, version: impl Into<String>) -> Self {
+        self.launcher_name = name.into();
+        self.launcher_version = version.into();
+        self
+    }
+
     /// Set memory allocation
     pub fn with_memory(mut self, min_mb: u32, max_mb: u32) -> Self {
         self.memory_min = min_mb;
@@ -96,6 +261,12 @@ impl LauncherConfig {
         self
     }
 
+    /// Choose whether conflicting JVM arguments are ignored, logged or rejected
+    pub fn with_jvm_arg_validation(mut self, validation: JvmArgValidation) -> Self {
+        self.jvm_arg_validation = validation;
+        self
+    }
+
     /// Set download configuration
     pub fn with_download_config(mut self, timeout: u64, concurrent: usize) -> Self {
         self.download_timeout = timeout;
@@ -103,11 +274,96 @@ impl LauncherConfig {
         self
     }
 
+    /// Prefer native libraries built for `arch` ("arm64", "x86_64", ...) when a library offers several
+    pub fn with_preferred_natives_arch(mut self, arch: impl Into<String>) -> Self {
+        self.preferred_natives_arch = Some(arch.into());
+        self
+    }
+
+    /// Trade download safety for speed or the other way round, see [`DownloadVerification`]
+    pub fn with_download_verification(mut self, verification: DownloadVerification) -> Self {
+        self.download_verification = verification;
+        self
+    }
+
+    /// Download large files over several connections, for servers that support range requests
+    pub fn with_multipart_downloads(mut self, multipart: MultipartDownloadConfig) -> Self {
+        self.multipart_downloads = Some(multipart);
+        self
+    }
+
     /// Add environment variable
     pub fn with_env_var(mut self, key: String, value: String) -> Self {
         self.env_vars.insert(key, value);
         self
     }
+
+    /// Copy of this configuration with paths under `minecraft_dir` stored relative to it,
+    /// so it can be moved to another machine or launcher root
+    pub fn to_portable(&self) -> Self {
+        let root = self.minecraft_dir.to_string_lossy().into_owned();
+        let portable = |value: &String| {
+            if root.is_empty() {
+                value.clone()
+            } else {
+                value.replace(&root, LAUNCHER_ROOT_PLACEHOLDER)
+            }
+        };
+
+        let mut config = self.clone();
+        config.minecraft_dir = PathBuf::new();
+        let portable_path = |path: &PathBuf| {
+            path.strip_prefix(&self.minecraft_dir)
+                .map(|relative| relative.to_path_buf())
+                .unwrap_or_else(|_| path.clone())
+        };
+        config.java_path = self.java_path.as_ref().map(portable_path);
+        config.java_pins = self.java_pins.iter().map(|(major, path)| (*major, portable_path(path))).collect();
+        config.jvm_args = self.jvm_args.iter().map(portable).collect();
+        config.game_args = self.game_args.iter().map(portable).collect();
+        config.env_vars = self.env_vars.iter().map(|(key, value)| (key.clone(), portable(value))).collect();
+        config
+    }
+
+    /// Resolve a configuration produced by [`LauncherConfig::to_portable`] against `minecraft_dir`
+    pub fn resolve_portable(mut self, minecraft_dir: PathBuf) -> Self {
+        let root = minecraft_dir.to_string_lossy().into_owned();
+        let resolve = |value: &String| value.replace(LAUNCHER_ROOT_PLACEHOLDER, &root);
+
+        let resolve_path = |path: PathBuf| {
+            if path.is_relative() {
+                minecraft_dir.join(path)
+            } else {
+                path
+            }
+        };
+        self.java_path = self.java_path.map(resolve_path);
+        self.java_pins = self.java_pins.into_iter().map(|(major, path)| (major, resolve_path(path))).collect();
+        self.jvm_args = self.jvm_args.iter().map(resolve).collect();
+        self.game_args = self.game_args.iter().map(resolve).collect();
+        self.env_vars = self.env_vars.iter().map(|(key, value)| (key.clone(), resolve(value))).collect();
+        self.minecraft_dir = minecraft_dir;
+        self
+    }
+}
+
+/// Stands in for the launcher root inside arguments and environment values of a portable config
+const LAUNCHER_ROOT_PLACEHOLDER: &str = "${launcher_root}";
+
+/// Format version written by `Launcher::export_config`
+pub const CONFIG_EXPORT_VERSION: u32 = 1;
+
+/// A launcher setup saved to a single file by `Launcher::export_config`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigExport {
+    /// File format version, see [`CONFIG_EXPORT_VERSION`]
+    pub format_version: u32,
+    /// When the export was written
+    pub exported_at: DateTime<Utc>,
+    /// Launcher configuration with portable paths
+    pub config: LauncherConfig,
+    /// Metadata of every instance (instance files themselves are not included)
+    pub instances: Vec<InstanceMetadata>,
 }
 
 /// Launch configuration for a specific Minecraft instance
@@ -121,26 +377,145 @@ pub struct LaunchConfig {
     pub account: Account,
     /// Mod loader configuration
     pub mod_loader: Option<ModLoaderConfig>,
-    /// Custom mods directory
+    /// Shared mods directory, linked into the instance as `mods/`
     pub mods_dir: Option<PathBuf>,
-    /// Resource packs directory
+    /// Shared resource packs directory, linked into the instance as `resourcepacks/`
     pub resource_packs_dir: Option<PathBuf>,
-    /// Shader packs directory
+    /// Shared shader packs directory, linked into the instance as `shaderpacks/`
     pub shader_packs_dir: Option<PathBuf>,
-    /// World saves directory
+    /// Shared world saves directory, linked into the instance as `saves/`
     pub saves_dir: Option<PathBuf>,
-    /// Custom game directory (overrides instance-based directory)
+    /// Custom game directory, used instead of `instances/<instance_name>` for everything the launch writes
     pub custom_game_dir: Option<PathBuf>,
     /// Window configuration
     pub window_config: WindowConfig,
     /// Whether to download missing assets
     pub download_assets: bool,
+    /// Which assets to fetch when `download_assets` is enabled
+    #[serde(default)]
+    pub asset_download_mode: AssetDownloadMode,
     /// Whether to download missing libraries
     pub download_libraries: bool,
     /// Additional JVM arguments for this launch
     pub additional_jvm_args: Vec<String>,
     /// Additional game arguments for this launch
     pub additional_game_args: Vec<String>,
+    /// Capture game stdout/stderr to a rotating log file under `logs/launcher/`
+    pub log_capture: Option<LogCaptureConfig>,
+    /// Shell command run (and waited for) before the game starts
+    pub pre_launch_command: Option<String>,
+    /// Shell command run after the game exits
+    pub post_exit_command: Option<String>,
+    /// Directory of config files copied into the instance's `config/` when not already present
+    pub config_seed_dir: Option<PathBuf>,
+    /// Where to load the version JSON from; defaults to looking up `version` in Mojang's manifest
+    pub version_source: Option<VersionSource>,
+    /// Main class to launch instead of the version's `mainClass` (tweakers, launchwrappers)
+    pub main_class_override: Option<String>,
+    /// Assets directory (containing `indexes/` and `objects/`) shared instead of the instance's `assets/`
+    pub assets_dir: Option<PathBuf>,
+    /// Game language (e.g. `de_de`) written to `options.txt` when the instance has none set yet
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Start the game detached so it outlives the launcher, see `Launcher::reattach_instance`
+    #[serde(default)]
+    pub detached: bool,
+    /// Whether to pass `-XstartOnFirstThread` on macOS; `None` adds it for LWJGL 3 versions
+    #[serde(default)]
+    pub start_on_first_thread: Option<bool>,
+    /// Point the game's telemetry endpoint at a host that never resolves
+    #[serde(default)]
+    pub disable_telemetry: bool,
+    /// Extract native libraries even when the natives directory is recorded as up to date
+    #[serde(default)]
+    pub force_native_extraction: bool,
+    /// Skip the automatic Log4Shell mitigation for versions that ship a vulnerable log4j
+    #[serde(default)]
+    pub disable_log4shell_mitigation: bool,
+    /// Console output fragments that mark the game window as ready, see
+    /// `MinecraftProcess::wait_for_window_ready`; `None` uses the built-in patterns
+    #[serde(default)]
+    pub window_ready_patterns: Option<Vec<String>>,
+}
+
+/// A problem `Launcher::validate_launch_config` found that doesn't prevent launching
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LaunchWarning {
+    /// The version could not be fetched, so the game would start offline from cached files
+    Offline { version: String },
+    /// The mod loader is not known to support this Minecraft version
+    UnsupportedModLoader { loader: ModLoaderType, minecraft_version: String },
+    /// The maximum heap is small enough that the game is likely to run out of memory
+    LowMemory { memory_max_mb: u32 },
+    /// Conflicting JVM arguments (see `LauncherConfig::jvm_arg_validation`)
+    JvmArgConflict { message: String },
+    /// Mojang marks the version as predating its security patches; `log4j_mitigated` tells
+    /// whether the game is protected against Log4Shell anyway
+    NonCompliantVersion { version: String, log4j_mitigated: bool },
+}
+
+impl std::fmt::Display for LaunchWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LaunchWarning::Offline { version } => write!(f, "{} would be launched offline from cached files", version),
+            LaunchWarning::UnsupportedModLoader { loader, minecraft_version } => {
+                write!(f, "{} may not support Minecraft {}", loader, minecraft_version)
+            }
+            LaunchWarning::LowMemory { memory_max_mb } => {
+                write!(f, "Only {} MB of memory is allowed, the game may run out", memory_max_mb)
+            }
+            LaunchWarning::JvmArgConflict { message } => write!(f, "{}", message),
+            LaunchWarning::NonCompliantVersion { version, log4j_mitigated: true } => {
+                write!(f, "{} predates Mojang's security patches; the Log4Shell mitigation is applied", version)
+            }
+            LaunchWarning::NonCompliantVersion { version, log4j_mitigated: false } => {
+                write!(f, "{} predates Mojang's security patches and runs without the Log4Shell mitigation", version)
+            }
+        }
+    }
+}
+
+/// Which asset objects to download
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetDownloadMode {
+    /// The asset index and every object in it
+    #[default]
+    All,
+    /// Only the asset index, no objects
+    IndexOnly,
+    /// The asset index and objects whose names start with one of these prefixes (e.g. `minecraft/sounds/`)
+    Only(Vec<String>),
+}
+
+impl AssetDownloadMode {
+    /// Whether an object from the asset index should be downloaded
+    pub fn includes(&self, asset_name: &str) -> bool {
+        match self {
+            AssetDownloadMode::All => true,
+            AssetDownloadMode::IndexOnly => false,
+            AssetDownloadMode::Only(prefixes) => prefixes.iter().any(|prefix| asset_name.starts_with(prefix.as_str())),
+        }
+    }
+}
+
+/// Configuration for capturing game output to a per-launch log file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogCaptureConfig {
+    /// Maximum size of a single log file in bytes before it is rotated
+    pub max_file_size: u64,
+    /// Maximum number of rotated files to keep per launch
+    pub max_files: usize,
+}
+
+impl Default for LogCaptureConfig {
+    fn default() -> Self {
+        Self {
+            max_file_size: 10 * 1024 * 1024, // 10MB
+            max_files: 5,
+        }
+    }
 }
 
 /// Mod loader configuration
@@ -163,6 +538,10 @@ pub struct WindowConfig {
     pub height: u32,
     /// Whether to start in fullscreen
     pub fullscreen: bool,
+    /// Also write the size and fullscreen setting into `options.txt` when it doesn't set them yet,
+    /// for versions that ignore `--width`/`--height`
+    #[serde(default)]
+    pub persist_to_options: bool,
 }
 
 impl Default for WindowConfig {
@@ -171,6 +550,7 @@ impl Default for WindowConfig {
             width: 1280,
             height: 720,
             fullscreen: false,
+            persist_to_options: false,
         }
     }
 }
@@ -190,9 +570,24 @@ impl LaunchConfig {
             custom_game_dir: None,
             window_config: WindowConfig::default(),
             download_assets: true,
+            asset_download_mode: AssetDownloadMode::default(),
             download_libraries: true,
             additional_jvm_args: Vec::new(),
             additional_game_args: Vec::new(),
+            log_capture: None,
+            pre_launch_command: None,
+            post_exit_command: None,
+            config_seed_dir: None,
+            version_source: None,
+            main_class_override: None,
+            assets_dir: None,
+            language: None,
+            detached: false,
+            start_on_first_thread: None,
+            disable_telemetry: false,
+            force_native_extraction: false,
+            disable_log4shell_mitigation: false,
+            window_ready_patterns: None,
         }
     }
 
@@ -206,7 +601,7 @@ impl LaunchConfig {
         self
     }
 
-    /// Set custom directories
+    /// Share directories between instances; each one is linked into the instance in place of its own
     pub fn with_custom_dirs(
         mut self,
         mods: Option<PathBuf>,
@@ -227,10 +622,18 @@ impl LaunchConfig {
             width,
             height,
             fullscreen,
+            persist_to_options: self.window_config.persist_to_options,
         };
         self
     }
 
+    /// Seed the window size and fullscreen setting into `options.txt` on first launch.
+    /// An `options.txt` that already sets them is left untouched.
+    pub fn persist_window_to_options(mut self) -> Self {
+        self.window_config.persist_to_options = true;
+        self
+    }
+
     /// Disable asset/library downloads
     pub fn without_downloads(mut self) -> Self {
         self.download_assets = false;
@@ -238,10 +641,113 @@ impl LaunchConfig {
         self
     }
 
+    /// Limit which assets are downloaded, e.g. only the index for CI setups
+    pub fn with_asset_download_mode(mut self, mode: AssetDownloadMode) -> Self {
+        self.asset_download_mode = mode;
+        self
+    }
+
     /// Add additional arguments
     pub fn with_additional_args(mut self, jvm_args: Vec<String>, game_args: Vec<String>) -> Self {
         self.additional_jvm_args.extend(jvm_args);
         self.additional_game_args.extend(game_args);
         self
     }
+
+    /// Capture game stdout/stderr to a rotating log file
+    pub fn with_log_capture(mut self, max_file_size: u64, max_files: usize) -> Self {
+        self.log_capture = Some(LogCaptureConfig {
+            max_file_size,
+            max_files,
+        });
+        self
+    }
+
+    /// Run a shell command before launching; a non-zero exit code aborts the launch
+    pub fn with_pre_launch_command(mut self, command: String) -> Self {
+        self.pre_launch_command = Some(command);
+        self
+    }
+
+    /// Run a shell command after the game exits
+    pub fn with_post_exit_command(mut self, command: String) -> Self {
+        self.post_exit_command = Some(command);
+        self
+    }
+
+    /// Load the version JSON from a custom URL or file instead of Mojang's manifest
+    pub fn with_version_source(mut self, source: VersionSource) -> Self {
+        self.version_source = Some(source);
+        self
+    }
+
+    /// Launch a different main class than the one in the version JSON
+    pub fn with_main_class_override(mut self, main_class: String) -> Self {
+        self.main_class_override = Some(main_class);
+        self
+    }
+
+    /// Download assets to and load them from a directory outside the instance, e.g. one shared by several instances
+    pub fn with_assets_dir(mut self, dir: PathBuf) -> Self {
+        self.assets_dir = Some(dir);
+        self
+    }
+
+    /// Start the game detached from the launcher: it keeps running when the launcher exits, its
+    /// console output goes to the launch log (or `logs/launcher/detached.log`), and a later
+    /// launcher can pick it up again with `Launcher::reattach_instance`
+    pub fn with_detached(mut self) -> Self {
+        self.detached = true;
+        self
+    }
+
+    /// Force `-XstartOnFirstThread` on or off on macOS instead of deciding from the LWJGL version
+    pub fn with_start_on_first_thread(mut self, enabled: bool) -> Self {
+        self.start_on_first_thread = Some(enabled);
+        self
+    }
+
+    /// Send the game's telemetry to a null host instead of Mojang's servers
+    pub fn disable_telemetry(mut self) -> Self {
+        self.disable_telemetry = true;
+        self
+    }
+
+    /// Re-extract native libraries on this launch, e.g. after editing the natives directory by hand
+    pub fn force_native_extraction(mut self) -> Self {
+        self.force_native_extraction = true;
+        self
+    }
+
+    /// Launch vulnerable versions without disabling log4j lookups or swapping their logging config
+    pub fn without_log4shell_mitigation(mut self) -> Self {
+        self.disable_log4shell_mitigation = true;
+        self
+    }
+
+    /// Detect the game window from these console output fragments instead of the built-in ones
+    pub fn with_window_ready_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.window_ready_patterns = Some(patterns);
+        self
+    }
+
+    /// Run the game in `dir` instead of the launcher's `instances/<instance_name>` directory
+    pub fn with_custom_game_dir(mut self, dir: PathBuf) -> Self {
+        self.custom_game_dir = Some(dir);
+        self
+    }
+
+    /// Seed the instance's `config/` from a directory; files the user already has are kept
+    pub fn with_config_seed_dir(mut self, dir: PathBuf) -> Self {
+        self.config_seed_dir = Some(dir);
+        self
+    }
+
+    /// Preselect the game language (`de_de`, `pt-BR`, ...) in `options.txt`. Only applied when the
+    /// instance has no language yet, so a language the player picked in game is kept. The game
+    /// has no launch argument for this, so `options.txt` is the only place it is set.
+    pub fn with_language(mut self, code: String) -> Self {
+        self.language = Some(code);
+        self
+    }
 }
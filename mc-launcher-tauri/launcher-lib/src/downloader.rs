@@ -1,15 +1,197 @@
 //! File downloader with progress tracking and verification
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use futures::StreamExt;
+use serde::{Deserialize, Serialize};
 use tokio::io::AsyncWriteExt;
+use tokio::sync::Notify;
+use crate::config::{DownloadVerification, MojangEndpoints, MultipartDownloadConfig};
 use crate::error::{LauncherError, Result};
 
+/// Longest response body excerpt included in HTTP status errors
+const ERROR_BODY_SNIPPET_CHARS: usize = 300;
+
+/// Number of times a download is attempted before a hash mismatch is reported
+const HASH_MISMATCH_ATTEMPTS: usize = 2;
+
+/// Number of times a download with a per-call timeout is attempted before giving up
+const TIMEOUT_ATTEMPTS: usize = 3;
+
+/// Connection timeout; the configured download timeout applies to idle reads instead of whole files
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Expected digest of a download, as a lowercase or uppercase hex string
+///
+/// Mojang publishes SHA1, Azul SHA-256 and Modrinth SHA-512; the downloader verifies whichever
+/// the source provides.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "algorithm", content = "value", rename_all = "lowercase")]
+pub enum FileHash {
+    Sha1(String),
+    Sha256(String),
+    Sha512(String),
+}
+
+impl FileHash {
+    /// Name of the hash algorithm, for messages
+    pub fn algorithm(&self) -> &'static str {
+        match self {
+            FileHash::Sha1(_) => "SHA1",
+            FileHash::Sha256(_) => "SHA-256",
+            FileHash::Sha512(_) => "SHA-512",
+        }
+    }
+
+    /// The expected hex digest
+    pub fn value(&self) -> &str {
+        match self {
+            FileHash::Sha1(value) | FileHash::Sha256(value) | FileHash::Sha512(value) => value,
+        }
+    }
+
+    /// Hex digest of `content` with this hash's algorithm
+    fn digest(&self, content: &[u8]) -> String {
+        use sha2::Digest;
+
+        match self {
+            FileHash::Sha1(_) => format!("{:x}", sha1::Sha1::digest(content)),
+            FileHash::Sha256(_) => format!("{:x}", sha2::Sha256::digest(content)),
+            FileHash::Sha512(_) => format!("{:x}", sha2::Sha512::digest(content)),
+        }
+    }
+
+    /// Hex digest of the file at `path`, read in a streaming fashion
+    fn digest_file(&self, path: &std::path::Path) -> std::io::Result<String> {
+        use sha2::Digest;
+
+        let mut file = std::fs::File::open(path)?;
+        Ok(match self {
+            FileHash::Sha1(_) => {
+                let mut hasher = sha1::Sha1::new();
+                std::io::copy(&mut file, &mut hasher)?;
+                format!("{:x}", hasher.finalize())
+            }
+            FileHash::Sha256(_) => {
+                let mut hasher = sha2::Sha256::new();
+                std::io::copy(&mut file, &mut hasher)?;
+                format!("{:x}", hasher.finalize())
+            }
+            FileHash::Sha512(_) => {
+                let mut hasher = sha2::Sha512::new();
+                std::io::copy(&mut file, &mut hasher)?;
+                format!("{:x}", hasher.finalize())
+            }
+        })
+    }
+
+    /// Whether `digest` (as produced by this algorithm) is the expected value
+    fn matches(&self, digest: &str) -> bool {
+        digest.eq_ignore_ascii_case(self.value())
+    }
+}
+
+/// Point-in-time view of a [`ProgressTracker`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ProgressSnapshot {
+    pub bytes_done: u64,
+    /// Sum of the sizes reported so far; grows as responses arrive
+    pub bytes_total: u64,
+    pub files_done: u64,
+    pub files_total: u64,
+}
+
+impl ProgressSnapshot {
+    /// Fraction of files completed, or `None` before any files are queued
+    pub fn fraction(&self) -> Option<f64> {
+        (self.files_total > 0).then(|| self.files_done as f64 / self.files_total as f64)
+    }
+}
+
+#[derive(Debug, Default)]
+struct ProgressCounters {
+    bytes_done: AtomicU64,
+    bytes_total: AtomicU64,
+    files_done: AtomicU64,
+    files_total: AtomicU64,
+    notify: Notify,
+}
+
+/// Shared download progress, updated lock-free from concurrent downloads.
+/// Clones share the same counters; poll with `snapshot` or wait for updates with `changed`.
+#[derive(Debug, Clone, Default)]
+pub struct ProgressTracker {
+    counters: Arc<ProgressCounters>,
+}
+
+impl ProgressTracker {
+    /// Create a tracker with all counters at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `count` more files
+    pub fn add_files_total(&self, count: u64) {
+        self.counters.files_total.fetch_add(count, Ordering::Relaxed);
+        self.counters.notify.notify_waiters();
+    }
+
+    /// Mark one queued file as finished
+    pub fn file_done(&self) {
+        self.counters.files_done.fetch_add(1, Ordering::Relaxed);
+        self.counters.notify.notify_waiters();
+    }
+
+    /// Add the size of a download that has started
+    pub fn add_bytes_total(&self, bytes: u64) {
+        self.counters.bytes_total.fetch_add(bytes, Ordering::Relaxed);
+        self.counters.notify.notify_waiters();
+    }
+
+    /// Record received bytes
+    pub fn add_bytes_done(&self, bytes: u64) {
+        self.counters.bytes_done.fetch_add(bytes, Ordering::Relaxed);
+        self.counters.notify.notify_waiters();
+    }
+
+    /// Current counter values
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        ProgressSnapshot {
+            bytes_done: self.counters.bytes_done.load(Ordering::Relaxed),
+            bytes_total: self.counters.bytes_total.load(Ordering::Relaxed),
+            files_done: self.counters.files_done.load(Ordering::Relaxed),
+            files_total: self.counters.files_total.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Wait for the next update and return the counters after it.
+    /// Bursts of updates may be coalesced into one wake-up.
+    pub async fn changed(&self) -> ProgressSnapshot {
+        self.counters.notify.notified().await;
+        self.snapshot()
+    }
+
+    /// Set every counter back to zero
+    pub fn reset(&self) {
+        self.counters.bytes_done.store(0, Ordering::Relaxed);
+        self.counters.bytes_total.store(0, Ordering::Relaxed);
+        self.counters.files_done.store(0, Ordering::Relaxed);
+        self.counters.files_total.store(0, Ordering::Relaxed);
+        self.counters.notify.notify_waiters();
+    }
+}
+
 /// File downloader with concurrent download support
 pub struct Downloader {
     client: reqwest::Client,
     concurrent_downloads: usize,
     timeout: u64,
+    progress: Option<ProgressTracker>,
+    endpoints: MojangEndpoints,
+    multipart: Option<MultipartDownloadConfig>,
+    verification: DownloadVerification,
 }
 
 impl Downloader {
@@ -17,7 +199,8 @@ impl Downloader {
     pub fn new(concurrent_downloads: usize, timeout: u64) -> Result<Self> {
         let client = reqwest::Client::builder()
             .user_agent(format!("MinecraftLauncher/{}", crate::VERSION))
-            .timeout(std::time::Duration::from_secs(timeout))
+            .connect_timeout(CONNECT_TIMEOUT)
+            .read_timeout(Duration::from_secs(timeout))
             .build()
             .map_err(|e| LauncherError::download(format!("Failed to create HTTP client: {}", e)))?;
 
@@ -25,9 +208,51 @@ impl Downloader {
             client,
             concurrent_downloads,
             timeout,
+            progress: None,
+            endpoints: MojangEndpoints::default(),
+            multipart: None,
+            verification: DownloadVerification::default(),
         })
     }
 
+    /// Report file and byte counts of every download to `tracker`
+    pub fn with_progress_tracker(mut self, tracker: ProgressTracker) -> Self {
+        self.progress = Some(tracker);
+        self
+    }
+
+    /// Send requests for Mojang's hosts to other base URLs
+    pub fn with_endpoints(mut self, endpoints: MojangEndpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Fetch files whose known size is at least `multipart.min_size` bytes as parallel range requests.
+    /// `None` turns multi-part downloads off.
+    pub fn with_multipart_downloads(mut self, multipart: Option<MultipartDownloadConfig>) -> Self {
+        self.multipart = multipart;
+        self
+    }
+
+    /// Choose how downloads and files already on disk are checked, see [`DownloadVerification`]
+    pub fn with_verification(mut self, verification: DownloadVerification) -> Self {
+        if verification == DownloadVerification::Skip {
+            log::warn!("Download verification is off; corrupt or tampered files will not be detected");
+        }
+        self.verification = verification;
+        self
+    }
+
+    /// The tracker downloads report to, if any
+    pub fn progress_tracker(&self) -> Option<&ProgressTracker> {
+        self.progress.as_ref()
+    }
+
+    /// The HTTP client downloads go through, for API requests that should share its settings
+    pub(crate) fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
     /// Download a single file
     pub async fn download_file(
         &self,
@@ -35,15 +260,44 @@ impl Downloader {
         destination: &PathBuf,
         expected_hash: Option<&str>,
     ) -> Result<()> {
+        self.download_file_with_timeout(url, destination, expected_hash, None).await
+    }
+
+    /// Download a single file, giving each attempt at most `timeout` to finish.
+    /// Timed-out attempts are retried; without a timeout only the client's idle timeout applies.
+    pub async fn download_file_with_timeout(
+        &self,
+        url: &str,
+        destination: &PathBuf,
+        expected_hash: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        let expected_hash = expected_hash.map(|hash| FileHash::Sha1(hash.to_string()));
+        self.download_file_verified(url, destination, expected_hash.as_ref(), None, timeout).await
+    }
+
+    /// Download a single file and verify it against a hash of any supported algorithm
+    /// (see `download_file_with_timeout` for `timeout`).
+    /// `expected_size` comes from the manifest and decides whether the file is fetched in parts.
+    pub async fn download_file_verified(
+        &self,
+        url: &str,
+        destination: &PathBuf,
+        expected_hash: Option<&FileHash>,
+        expected_size: Option<u64>,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        if self.verification == DownloadVerification::Skip && destination.exists() {
+            log::debug!("File {} already exists, verification is off", destination.display());
+            return Ok(());
+        }
+        let expected_hash = self.hash_to_verify(expected_hash);
+
         // Check if file already exists and is valid
         if let Some(hash) = expected_hash {
-            if destination.exists() {
-                if let Ok(existing_hash) = self.calculate_sha1(destination).await {
-                    if existing_hash == hash {
-                        log::debug!("File {} already exists with correct hash", destination.display());
-                        return Ok(());
-                    }
-                }
+            if self.file_matches(destination, hash).await {
+                log::debug!("File {} already exists with correct hash", destination.display());
+                return Ok(());
             }
         }
 
@@ -56,106 +310,378 @@ impl Downloader {
 
         log::debug!("Downloading {} to {}", url, destination.display());
 
-        // Download the file
-        let response = self.client
+        let temp_path = destination.with_extension("tmp");
+        for attempt in 1..=HASH_MISMATCH_ATTEMPTS {
+            self.fetch_to_file(url, &temp_path, expected_size, timeout).await?;
+
+            // Report error pages as such instead of as a confusing hash mismatch
+            if destination.extension().is_some_and(|extension| extension == "jar") {
+                if let Ok(Some(hint)) = crate::utils::non_jar_content(&temp_path) {
+                    let _ = tokio::fs::remove_file(&temp_path).await;
+                    if attempt == HASH_MISMATCH_ATTEMPTS {
+                        return Err(LauncherError::validation(format!(
+                            "Downloaded content for {} from {} is not a valid jar ({})",
+                            destination.display(),
+                            url,
+                            hint
+                        )));
+                    }
+                    log::warn!("Download of {} from {} is not a valid jar ({}), retrying", destination.display(), url, hint);
+                    continue;
+                }
+            }
+
+            // Verify hash if provided
+            let Some(expected_hash) = expected_hash else {
+                break;
+            };
+            let actual_hash = self.calculate_hash(&temp_path, expected_hash).await?;
+            if expected_hash.matches(&actual_hash) {
+                break;
+            }
+
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            if attempt == HASH_MISMATCH_ATTEMPTS {
+                return Err(LauncherError::validation(format!(
+                    "{} mismatch for {}: expected {}, got {}",
+                    expected_hash.algorithm(),
+                    destination.display(),
+                    expected_hash.value(),
+                    actual_hash
+                )));
+            }
+            // A stale CDN cache or captive portal page usually doesn't repeat
+            log::warn!(
+                "{} mismatch for {} (expected {}, got {}), retrying download",
+                expected_hash.algorithm(),
+                destination.display(),
+                expected_hash.value(),
+                actual_hash
+            );
+        }
+
+        // Move temporary file to final destination
+        tokio::fs::rename(&temp_path, destination)
+            .await
+            .map_err(|e| LauncherError::file(format!("Failed to move file to final destination: {}", e)))?;
+
+        log::debug!("Successfully downloaded {}", destination.display());
+        Ok(())
+    }
+
+    /// Download a file from the first URL in `urls` that works, e.g. when old URLs have moved.
+    /// Only 403/404 answers and transient failures (see [`LauncherError::is_transient`]) move on
+    /// to the next URL; any other error, such as a hash mismatch, is returned right away.
+    pub async fn download_file_with_fallbacks(
+        &self,
+        urls: &[String],
+        destination: &PathBuf,
+        expected_hash: Option<&str>,
+        expected_size: Option<u64>,
+    ) -> Result<()> {
+        let expected_hash = expected_hash.map(|hash| FileHash::Sha1(hash.to_string()));
+        let mut last_error = LauncherError::download(format!("No URLs to download {}", destination.display()));
+        for url in urls {
+            match self.download_file_verified(url, destination, expected_hash.as_ref(), expected_size, None).await {
+                Ok(()) => return Ok(()),
+                Err(e) if e.is_transient() || matches!(e.http_status_code(), Some(403 | 404)) => {
+                    log::warn!("Download from {} failed, trying next host: {}", url, e);
+                    last_error = e;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Download `url` into `path`, split into range requests when multi-part downloads are
+    /// enabled, `expected_size` is large enough and the server supports them.
+    ///
+    /// Files without a known size are never probed, so small downloads cost no extra request.
+    async fn fetch_to_file(&self, url: &str, path: &PathBuf, expected_size: Option<u64>, timeout: Option<Duration>) -> Result<()> {
+        let ranged = self.multipart.filter(|multipart| expected_size.is_some_and(|size| size >= multipart.min_size));
+        if let (Some(multipart), Some(expected_size)) = (ranged, expected_size) {
+            match self.ranged_size(url).await {
+                Some(size) if size == expected_size => {
+                    return self.download_in_parts(url, path, size, multipart.connections, timeout).await;
+                }
+                Some(size) => log::warn!(
+                    "{} is {} bytes instead of the expected {}, downloading it in one piece",
+                    url, size, expected_size
+                ),
+                None => log::debug!("{} does not support range requests, downloading it in one piece", url),
+            }
+        }
+
+        match timeout {
+            Some(timeout) => self.download_to_file_within(url, path, timeout).await,
+            None => self.download_to_file(url, path).await,
+        }
+    }
+
+    /// Size of `url` if its server accepts byte range requests for it
+    async fn ranged_size(&self, url: &str) -> Option<u64> {
+        use reqwest::header::{ACCEPT_RANGES, CONTENT_LENGTH};
+
+        let response = self.client.head(self.endpoints.rewrite(url)).send().await.ok()?;
+        let headers = response.headers();
+        if !response.status().is_success() || headers.get(ACCEPT_RANGES).is_none_or(|value| value != "bytes") {
+            return None;
+        }
+        headers.get(CONTENT_LENGTH)?.to_str().ok()?.parse().ok()
+    }
+
+    /// Fetch `url` as `connections` parallel range requests and join the parts into `path`.
+    ///
+    /// Parts are kept next to `path` until they have been joined, so a failed download resumes
+    /// where each part stopped on the next attempt. Each part is named after its byte range;
+    /// parts of any other range, e.g. from a different size or connection count, are removed.
+    async fn download_in_parts(
+        &self,
+        url: &str,
+        path: &Path,
+        size: u64,
+        connections: usize,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        let connections = connections.max(1) as u64;
+        let part_size = size.div_ceil(connections);
+        let parts: Vec<(PathBuf, u64, u64)> = (0..connections)
+            .map(|index| (index * part_size, ((index + 1) * part_size).min(size)))
+            .filter(|(start, end)| start < end)
+            .map(|(start, end)| (part_path(path, start, end), start, end))
+            .collect();
+        log::debug!("Downloading {} in {} parts", url, parts.len());
+
+        let current: Vec<&PathBuf> = parts.iter().map(|(part_path, _, _)| part_path).collect();
+        remove_stale_parts(path, &current).await;
+
+        if let Some(progress) = &self.progress {
+            progress.add_bytes_total(size);
+        }
+
+        let results = futures::future::join_all(parts.iter().map(|(part_path, start, end)| async move {
+            // Bytes left over from an earlier attempt count as done
+            let resumed = tokio::fs::metadata(part_path).await.map(|metadata| metadata.len()).unwrap_or(0);
+            if let Some(progress) = &self.progress {
+                progress.add_bytes_done(resumed.min(end - start));
+            }
+
+            let Some(timeout) = timeout else {
+                return self.download_part(url, part_path, *start, *end).await;
+            };
+            for attempt in 1..=TIMEOUT_ATTEMPTS {
+                match tokio::time::timeout(timeout, self.download_part(url, part_path, *start, *end)).await {
+                    Ok(result) => return result,
+                    Err(_) => log::warn!(
+                        "Download of bytes {}-{} of {} timed out after {:?} (attempt {}/{})",
+                        start, end, url, timeout, attempt, TIMEOUT_ATTEMPTS
+                    ),
+                }
+            }
+            Err(LauncherError::timeout(format!(
+                "Download of bytes {}-{} of {} timed out {} times",
+                start, end, url, TIMEOUT_ATTEMPTS
+            )))
+        }))
+        .await;
+        results.into_iter().collect::<Result<Vec<()>>>()?;
+
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+            let mut file = std::fs::File::create(&path)?;
+            for (part_path, _, _) in &parts {
+                std::io::copy(&mut std::fs::File::open(part_path)?, &mut file)?;
+            }
+            file.sync_all()?;
+            for (part_path, _, _) in &parts {
+                let _ = std::fs::remove_file(part_path);
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| LauncherError::other(format!("Joining download parts failed: {}", e)))?
+        .map_err(|e| LauncherError::file(format!("Failed to join download parts: {}", e)))
+    }
+
+    /// Fetch bytes `start..end` of `url` into `part_path`, continuing after whatever the file
+    /// already holds
+    async fn download_part(&self, url: &str, part_path: &Path, start: u64, end: u64) -> Result<()> {
+        let mut have = tokio::fs::metadata(part_path).await.map(|metadata| metadata.len()).unwrap_or(0);
+        if have > end - start {
+            // Longer than its range, so it can't be a prefix of it
+            let _ = tokio::fs::remove_file(part_path).await;
+            have = 0;
+        }
+        if have == end - start {
+            return Ok(());
+        }
+
+        let url = &self.endpoints.rewrite(url);
+        let response = self
+            .client
             .get(url)
+            .header(reqwest::header::RANGE, format!("bytes={}-{}", start + have, end - 1))
             .send()
             .await
-            .map_err(|e| LauncherError::download(format!("Failed to start download from {}: {}", url, e)))?;
-
-        if !response.status().is_success() {
-            return Err(LauncherError::download(format!(
-                "HTTP error {} when downloading from {}",
-                response.status(),
-                url
-            )));
+            .map_err(|e| LauncherError::from_request(format!("Failed to start download from {}", url), e))?;
+        if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+            if !response.status().is_success() {
+                return Err(http_status_error(response, format!("Failed to download from {}", url)).await);
+            }
+            return Err(LauncherError::download(format!("{} ignored the range request for a download part", url)));
         }
 
-        // Stream the response to a temporary file
-        let temp_path = destination.with_extension("tmp");
-        let mut file = tokio::fs::File::create(&temp_path)
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(part_path)
             .await
-            .map_err(|e| LauncherError::file(format!("Failed to create temporary file {}: {}", temp_path.display(), e)))?;
+            .map_err(|e| LauncherError::file(format!("Failed to open download part {}: {}", part_path.display(), e)))?;
 
         let mut stream = response.bytes_stream();
         while let Some(chunk) = stream.next().await {
             let chunk = chunk
-                .map_err(|e| LauncherError::download(format!("Failed to read chunk: {}", e)))?;
-            
+                .map_err(|e| LauncherError::from_request(format!("Failed to read chunk from {}", url), e))?;
             file.write_all(&chunk)
                 .await
                 .map_err(|e| LauncherError::file(format!("Failed to write chunk: {}", e)))?;
+
+            if let Some(progress) = &self.progress {
+                progress.add_bytes_done(chunk.len() as u64);
+            }
         }
 
         file.flush()
             .await
             .map_err(|e| LauncherError::file(format!("Failed to flush file: {}", e)))?;
 
-        drop(file);
+        Ok(())
+    }
 
-        // Verify hash if provided
-        if let Some(expected_hash) = expected_hash {
-            let actual_hash = self.calculate_sha1(&temp_path).await?;
-            if actual_hash != expected_hash {
-                let _ = tokio::fs::remove_file(&temp_path).await;
-                return Err(LauncherError::validation(format!(
-                    "Hash mismatch for {}: expected {}, got {}",
-                    destination.display(),
-                    expected_hash,
-                    actual_hash
-                )));
+    /// Like `download_to_file`, but retry attempts that take longer than `timeout`
+    async fn download_to_file_within(&self, url: &str, path: &PathBuf, timeout: Duration) -> Result<()> {
+        for attempt in 1..=TIMEOUT_ATTEMPTS {
+            match tokio::time::timeout(timeout, self.download_to_file(url, path)).await {
+                Ok(result) => return result,
+                Err(_) => log::warn!(
+                    "Download of {} timed out after {:?} (attempt {}/{})",
+                    url, timeout, attempt, TIMEOUT_ATTEMPTS
+                ),
             }
         }
 
-        // Move temporary file to final destination
-        tokio::fs::rename(&temp_path, destination)
+        let _ = tokio::fs::remove_file(path).await;
+        Err(LauncherError::timeout(format!(
+            "Download of {} timed out {} times",
+            url, TIMEOUT_ATTEMPTS
+        )))
+    }
+
+    /// Stream a URL into `path`, overwriting any existing file
+    async fn download_to_file(&self, url: &str, path: &PathBuf) -> Result<()> {
+        let url = &self.endpoints.rewrite(url);
+        let response = self.client
+            .get(url)
+            .send()
             .await
-            .map_err(|e| LauncherError::file(format!("Failed to move file to final destination: {}", e)))?;
+            .map_err(|e| LauncherError::from_request(format!("Failed to start download from {}", url), e))?;
+
+        if !response.status().is_success() {
+            return Err(http_status_error(response, format!("Failed to download from {}", url)).await);
+        }
+
+        if let (Some(progress), Some(size)) = (&self.progress, response.content_length()) {
+            progress.add_bytes_total(size);
+        }
+
+        let mut file = tokio::fs::File::create(path)
+            .await
+            .map_err(|e| LauncherError::file(format!("Failed to create temporary file {}: {}", path.display(), e)))?;
+
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk
+                .map_err(|e| LauncherError::from_request(format!("Failed to read chunk from {}", url), e))?;
+            
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| LauncherError::file(format!("Failed to write chunk: {}", e)))?;
+
+            if let Some(progress) = &self.progress {
+                progress.add_bytes_done(chunk.len() as u64);
+            }
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| LauncherError::file(format!("Failed to flush file: {}", e)))?;
 
-        log::debug!("Successfully downloaded {}", destination.display());
         Ok(())
     }
 
-    /// Download multiple files concurrently
-    pub async fn download_files(&self, downloads: Vec<(String, PathBuf, String)>) -> Result<()> {
+    /// Download multiple files concurrently.
+    /// Entries without a SHA1 are downloaded unverified and kept if already present.
+    pub async fn download_files(&self, downloads: Vec<(String, PathBuf, Option<String>)>) -> Result<()> {
+        self.download_files_with_timeout(downloads, None).await
+    }
+
+    /// Download multiple files concurrently with a per-file timeout (see `download_file_with_timeout`)
+    pub async fn download_files_with_timeout(
+        &self,
+        downloads: Vec<(String, PathBuf, Option<String>)>,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        let downloads = downloads
+            .into_iter()
+            .map(|(url, path, hash)| (url, path, hash.map(FileHash::Sha1)))
+            .collect();
+        self.download_files_verified(downloads, timeout).await
+    }
+
+    /// Download multiple files concurrently, each verified against a hash of any supported algorithm
+    pub async fn download_files_verified(
+        &self,
+        downloads: Vec<(String, PathBuf, Option<FileHash>)>,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
         if downloads.is_empty() {
             return Ok(());
         }
 
         log::info!("Starting download of {} files", downloads.len());
-
-        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(self.concurrent_downloads));
-        let mut tasks = Vec::new();
-
-        for (url, path, hash) in downloads {
-            let semaphore = semaphore.clone();
-            let downloader = self.clone();
-            
-            let task = tokio::spawn(async move {
-                let _permit = semaphore.acquire().await.unwrap();
-                downloader.download_file(&url, &path, Some(&hash)).await
-            });
-            
-            tasks.push(task);
+        if let Some(progress) = &self.progress {
+            progress.add_files_total(downloads.len() as u64);
         }
 
-        // Wait for all downloads to complete
-        let mut failed_downloads = Vec::new();
-        for (i, task) in tasks.into_iter().enumerate() {
-            match task.await {
-                Ok(Ok(())) => {
-                    log::debug!("Download {} completed successfully", i);
-                }
-                Ok(Err(e)) => {
-                    log::error!("Download {} failed: {}", i, e);
-                    failed_downloads.push(e);
-                }
-                Err(e) => {
-                    log::error!("Download task {} panicked: {}", i, e);
-                    failed_downloads.push(LauncherError::download(format!("Task panicked: {}", e)));
+        // Only `concurrent_downloads` futures exist at a time; the rest stay as plain tuples
+        let failed_downloads: Vec<LauncherError> = futures::stream::iter(downloads)
+            .map(|(url, path, hash)| async move {
+                let keep_existing = match self.verification {
+                    DownloadVerification::Skip => true,
+                    DownloadVerification::Standard => hash.is_none(),
+                    DownloadVerification::Strict => hash.is_none() && hashless_file_looks_intact(&path),
+                };
+                let result = if keep_existing && path.exists() {
+                    log::debug!("File {} already exists, not hashing it", path.display());
+                    Ok(())
+                } else {
+                    if hash.is_none() {
+                        log::warn!("No hash known for {}, downloading without verification", url);
+                    }
+                    self.download_file_verified(&url, &path, hash.as_ref(), None, timeout).await.inspect_err(|e| {
+                        log::error!("Download of {} failed: {}", url, e);
+                    })
+                };
+                if let Some(progress) = &self.progress {
+                    progress.file_done();
                 }
-            }
-        }
+                result
+            })
+            .buffer_unordered(self.concurrent_downloads.max(1))
+            .filter_map(|result| async move { result.err() })
+            .collect()
+            .await;
 
         if !failed_downloads.is_empty() {
             return Err(LauncherError::download(format!(
@@ -169,19 +695,86 @@ impl Downloader {
         Ok(())
     }
 
-    /// Calculate SHA1 hash of a file
-    async fn calculate_sha1(&self, file_path: &PathBuf) -> Result<String> {
-        use sha1::{Sha1, Digest};
-        
+    /// Ask the server for the size of `url` without downloading it. Fails if the file doesn't exist.
+    pub(crate) async fn remote_size(&self, url: &str) -> Result<Option<u64>> {
+        let url = &self.endpoints.rewrite(url);
+        let response = self
+            .client
+            .head(url)
+            .send()
+            .await
+            .map_err(|e| LauncherError::from_request(format!("Failed to reach {}", url), e))?;
+        if !response.status().is_success() {
+            return Err(LauncherError::http_status(
+                response.status().as_u16(),
+                format!("{} is not available", url),
+            ));
+        }
+        Ok(response.content_length())
+    }
+
+    /// Whether `path` exists and its SHA1 matches `expected_hash`; only existence is checked
+    /// while verification is off
+    pub(crate) async fn file_matches_sha1(&self, path: &PathBuf, expected_hash: &str) -> bool {
+        if self.verification == DownloadVerification::Skip {
+            return path.exists();
+        }
+        self.file_matches(path, &FileHash::Sha1(expected_hash.to_string())).await
+    }
+
+    /// `expected_hash`, unless verification is off
+    fn hash_to_verify<'a>(&self, expected_hash: Option<&'a FileHash>) -> Option<&'a FileHash> {
+        expected_hash.filter(|_| self.verification != DownloadVerification::Skip)
+    }
+
+    /// Whether `path` exists and matches `expected_hash`
+    pub async fn file_matches(&self, path: &PathBuf, expected_hash: &FileHash) -> bool {
+        path.exists()
+            && self
+                .calculate_hash(path, expected_hash)
+                .await
+                .is_ok_and(|hash| expected_hash.matches(&hash))
+    }
+
+    /// Check files against their hashes and return those that are missing or don't match.
+    /// Files without a hash only have to exist.
+    ///
+    /// Up to `concurrent_downloads` files are hashed at once on blocking threads. Every checked
+    /// file is queued and marked done on the progress tracker.
+    pub async fn verify_files(&self, files: Vec<(PathBuf, Option<FileHash>)>) -> Vec<PathBuf> {
+        if let Some(progress) = &self.progress {
+            progress.add_files_total(files.len() as u64);
+        }
+
+        futures::stream::iter(files)
+            .map(|(path, hash)| async move {
+                let intact = match hash {
+                    Some(hash) => {
+                        let file = path.clone();
+                        tokio::task::spawn_blocking(move || hash.digest_file(&file).is_ok_and(|digest| hash.matches(&digest)))
+                            .await
+                            .unwrap_or(false)
+                    }
+                    None => path.exists(),
+                };
+                if let Some(progress) = &self.progress {
+                    progress.file_done();
+                }
+                (!intact).then_some(path)
+            })
+            .buffer_unordered(self.concurrent_downloads.max(1))
+            .filter_map(|broken| async move { broken })
+            .collect()
+            .await
+    }
+
+    /// Hash a file with the algorithm of `hash`
+    async fn calculate_hash(&self, file_path: &PathBuf, hash: &FileHash) -> Result<String> {
         let content = tokio::fs::read(file_path)
             .await
             .map_err(|e| LauncherError::file(format!("Failed to read file for hashing: {}", e)))?;
 
-        let mut hasher = Sha1::new();
-        hasher.update(&content);
-        let result = hasher.finalize();
-        
-        Ok(format!("{:x}", result))
+        Ok(hash.digest(&content))
     }
 
     /// Get download progress information
@@ -196,14 +789,13 @@ impl Downloader {
         F: Fn(u64, u64) + Send + Sync + 'static,
     {
         // Check if file already exists and is valid
-        if let Some(hash) = expected_hash {
-            if destination.exists() {
-                if let Ok(existing_hash) = self.calculate_sha1(destination).await {
-                    if existing_hash == hash {
-                        log::debug!("File {} already exists with correct hash", destination.display());
-                        return Ok(());
-                    }
-                }
+        let expected_hash = expected_hash
+            .map(|hash| FileHash::Sha1(hash.to_string()))
+            .filter(|_| self.verification != DownloadVerification::Skip);
+        if let Some(hash) = &expected_hash {
+            if self.file_matches(destination, hash).await {
+                log::debug!("File {} already exists with correct hash", destination.display());
+                return Ok(());
             }
         }
 
@@ -217,18 +809,15 @@ impl Downloader {
         log::debug!("Downloading {} to {}", url, destination.display());
 
         // Start the download
+        let url = &self.endpoints.rewrite(url);
         let response = self.client
             .get(url)
             .send()
             .await
-            .map_err(|e| LauncherError::download(format!("Failed to start download from {}: {}", url, e)))?;
+            .map_err(|e| LauncherError::from_request(format!("Failed to start download from {}", url), e))?;
 
         if !response.status().is_success() {
-            return Err(LauncherError::download(format!(
-                "HTTP error {} when downloading from {}",
-                response.status(),
-                url
-            )));
+            return Err(http_status_error(response, format!("Failed to download from {}", url)).await);
         }
 
         let total_size = response.content_length().unwrap_or(0);
@@ -242,7 +831,7 @@ impl Downloader {
         
         while let Some(chunk) = stream.next().await {
             let chunk = chunk
-                .map_err(|e| LauncherError::download(format!("Failed to read chunk: {}", e)))?;
+                .map_err(|e| LauncherError::from_request(format!("Failed to read chunk from {}", url), e))?;
             
             file.write_all(&chunk)
                 .await
@@ -259,14 +848,15 @@ impl Downloader {
         drop(file);
 
         // Verify hash if provided
-        if let Some(expected_hash) = expected_hash {
-            let actual_hash = self.calculate_sha1(&temp_path).await?;
-            if actual_hash != expected_hash {
+        if let Some(expected_hash) = &expected_hash {
+            let actual_hash = self.calculate_hash(&temp_path, expected_hash).await?;
+            if !expected_hash.matches(&actual_hash) {
                 let _ = tokio::fs::remove_file(&temp_path).await;
                 return Err(LauncherError::validation(format!(
-                    "Hash mismatch for {}: expected {}, got {}",
+                    "{} mismatch for {}: expected {}, got {}",
+                    expected_hash.algorithm(),
                     destination.display(),
-                    expected_hash,
+                    expected_hash.value(),
                     actual_hash
                 )));
             }
@@ -290,6 +880,229 @@ impl Clone for Downloader {
             client: self.client.clone(),
             concurrent_downloads: self.concurrent_downloads,
             timeout: self.timeout,
+            progress: self.progress.clone(),
+            endpoints: self.endpoints.clone(),
+            multipart: self.multipart,
+            verification: self.verification,
+        }
+    }
+}
+
+/// Cheap sanity check for a present file that has no hash to compare against: it must not be
+/// empty, and a jar must start with a zip header
+fn hashless_file_looks_intact(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    if metadata.len() == 0 {
+        log::warn!("{} is empty, downloading it again", path.display());
+        return false;
+    }
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("jar")) {
+        if let Ok(Some(hint)) = crate::utils::non_jar_content(path) {
+            log::warn!("{} is not a valid jar ({}), downloading it again", path.display(), hint);
+            return false;
         }
     }
+    true
+}
+
+/// Status error for a failed response, with a whitespace-collapsed excerpt of its body
+/// (captive portals, CDN and rate-limit pages usually explain the failure there)
+/// Where bytes `start..end` of the download into `path` are kept until the parts are joined
+fn part_path(path: &Path, start: u64, end: u64) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    path.with_file_name(format!("{}.{}-{}.part", name, start, end))
+}
+
+/// Remove parts of the download into `path` other than `current`
+async fn remove_stale_parts(path: &Path, current: &[&PathBuf]) {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return;
+    };
+    let prefix = format!("{}.", name.to_string_lossy());
+    let Ok(mut entries) = tokio::fs::read_dir(parent).await else {
+        return;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let entry_name = entry.file_name().to_string_lossy().into_owned();
+        let entry_path = entry.path();
+        if entry_name.starts_with(&prefix) && entry_name.ends_with(".part") && !current.contains(&&entry_path) {
+            log::debug!("Removing stale download part {}", entry_path.display());
+            let _ = tokio::fs::remove_file(&entry_path).await;
+        }
+    }
+}
+
+async fn http_status_error(response: reqwest::Response, context: String) -> LauncherError {
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    if body.is_empty() {
+        return LauncherError::http_status(status, context);
+    }
+
+    let mut snippet: String = body.chars().take(ERROR_BODY_SNIPPET_CHARS).collect();
+    if snippet.len() < body.len() {
+        snippet.push_str("...");
+    }
+    LauncherError::http_status(status, format!("{} (response: {})", context, snippet))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Nothing listens here, so any download attempt fails
+    const UNREACHABLE_URL: &str = "http://127.0.0.1:9/file";
+    /// SHA1 of `b"hello"`
+    const HELLO_SHA1: &str = "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d";
+
+    async fn keeps_file(verification: DownloadVerification, name: &str, content: &[u8], hash: Option<&str>) -> bool {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        let downloader = Downloader::new(2, 5).unwrap().with_verification(verification);
+        let downloads = vec![(UNREACHABLE_URL.to_string(), path, hash.map(str::to_string))];
+        downloader.download_files(downloads).await.is_ok()
+    }
+
+    #[tokio::test]
+    async fn skip_keeps_present_files_unhashed() {
+        let mode = DownloadVerification::Skip;
+        assert!(keeps_file(mode, "a.txt", b"hello", Some(HELLO_SHA1)).await);
+        assert!(keeps_file(mode, "a.txt", b"changed", Some(HELLO_SHA1)).await);
+        assert!(keeps_file(mode, "a.jar", b"<html>", None).await);
+    }
+
+    #[tokio::test]
+    async fn standard_hashes_only_files_with_a_known_hash() {
+        let mode = DownloadVerification::Standard;
+        assert!(keeps_file(mode, "a.txt", b"hello", Some(HELLO_SHA1)).await);
+        assert!(!keeps_file(mode, "a.txt", b"changed", Some(HELLO_SHA1)).await);
+        assert!(keeps_file(mode, "a.jar", b"<html>", None).await);
+    }
+
+    #[tokio::test]
+    async fn strict_sniffs_files_without_a_hash() {
+        let mode = DownloadVerification::Strict;
+        assert!(keeps_file(mode, "a.txt", b"hello", Some(HELLO_SHA1)).await);
+        assert!(!keeps_file(mode, "a.txt", b"changed", Some(HELLO_SHA1)).await);
+        assert!(keeps_file(mode, "a.txt", b"hello", None).await);
+        assert!(keeps_file(mode, "a.jar", b"PK\x03\x04rest", None).await);
+        assert!(!keeps_file(mode, "a.jar", b"<html>", None).await);
+        assert!(!keeps_file(mode, "a.txt", b"", None).await);
+    }
+
+    /// Serve `body` over HTTP on a local port, honouring `Range` headers when `ranges` is set.
+    /// Returns the file URL and the `METHOD range` line of every request received.
+    async fn serve(body: Vec<u8>, ranges: bool) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/file", listener.local_addr().unwrap());
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let body = body.clone();
+                let seen = seen.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buffer = [0; 1024];
+                    while !request.ends_with(b"\r\n\r\n") {
+                        match stream.read(&mut buffer).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(read) => request.extend_from_slice(&buffer[..read]),
+                        }
+                    }
+                    let request = String::from_utf8_lossy(&request).to_lowercase();
+                    let method = request.split_whitespace().next().unwrap_or_default().to_uppercase();
+                    let range = request
+                        .lines()
+                        .find_map(|line| line.strip_prefix("range: bytes="))
+                        .filter(|_| ranges)
+                        .and_then(|range| range.trim().split_once('-'))
+                        .map(|(start, end)| (start.parse::<usize>().unwrap(), end.parse::<usize>().unwrap() + 1));
+                    seen.lock().unwrap().push(format!("{} {:?}", method, range));
+
+                    let (status, content) = match range {
+                        Some((start, end)) => ("206 Partial Content", &body[start..end]),
+                        None => ("200 OK", &body[..]),
+                    };
+                    let accept_ranges = if ranges { "Accept-Ranges: bytes\r\n" } else { "" };
+                    let head = format!(
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+                        status,
+                        content.len(),
+                        accept_ranges
+                    );
+                    let _ = stream.write_all(head.as_bytes()).await;
+                    if method != "HEAD" {
+                        let _ = stream.write_all(content).await;
+                    }
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+        (url, requests)
+    }
+
+    fn multipart_downloader() -> Downloader {
+        Downloader::new(2, 5)
+            .unwrap()
+            .with_multipart_downloads(Some(MultipartDownloadConfig { min_size: 100, connections: 4 }))
+    }
+
+    #[tokio::test]
+    async fn large_files_download_in_ranges() {
+        let body: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        let hash = FileHash::Sha1(FileHash::Sha1(String::new()).digest(&body));
+        let (url, requests) = serve(body.clone(), true).await;
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("file.bin");
+        // Left over from an attempt with two connections
+        let stale = part_path(&destination.with_extension("tmp"), 0, 500);
+        std::fs::write(&stale, vec![0xff; 500]).unwrap();
+
+        multipart_downloader()
+            .download_file_verified(&url, &destination, Some(&hash), Some(1000), None)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&destination).unwrap(), body);
+        assert!(!stale.exists());
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.iter().filter(|request| request.starts_with("HEAD")).count(), 1);
+        assert_eq!(requests.iter().filter(|request| request.starts_with("GET Some")).count(), 4);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn servers_without_range_support_get_a_single_request() {
+        let body = vec![7; 1000];
+        let (url, requests) = serve(body.clone(), false).await;
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("file.bin");
+
+        multipart_downloader()
+            .download_file_verified(&url, &destination, None, Some(1000), None)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&destination).unwrap(), body);
+        assert_eq!(*requests.lock().unwrap(), vec!["HEAD None".to_string(), "GET None".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn small_and_unsized_files_are_not_probed() {
+        let (url, requests) = serve(vec![7; 1000], true).await;
+        let dir = tempfile::tempdir().unwrap();
+        let downloader = multipart_downloader();
+
+        downloader.download_file_verified(&url, &dir.path().join("small.bin"), None, Some(50), None).await.unwrap();
+        downloader.download_file_verified(&url, &dir.path().join("unsized.bin"), None, None, None).await.unwrap();
+
+        assert_eq!(*requests.lock().unwrap(), vec!["GET None".to_string(), "GET None".to_string()]);
+    }
 }
@@ -8,8 +8,8 @@ pub type Result<T> = std::result::Result<T, LauncherError>;
 /// Main error type for launcher operations
 #[derive(Debug, Error)]
 pub enum LauncherError {
-    #[error("Authentication error: {0}")]
-    Auth(String),
+    #[error("Authentication error: {message}")]
+    Auth { kind: AuthErrorKind, message: String },
     #[error("Version not found: {0}")]
     VersionNotFound(String),
     #[error("Download error: {0}")]
@@ -25,7 +25,13 @@ pub enum LauncherError {
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
     #[error("HTTP error: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(reqwest::Error),
+    #[error("Network unreachable: {0}")]
+    NetworkUnreachable(String),
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+    #[error("HTTP status {status}: {message}")]
+    HttpStatus { status: u16, message: String },
     #[error("JSON serialization error: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("ZIP archive error: {0}")]
@@ -34,10 +40,65 @@ pub enum LauncherError {
     Java(String),
 }
 
+impl From<reqwest::Error> for LauncherError {
+    /// Timeouts, connection failures, HTTP statuses and undecodable bodies get their own
+    /// variants; other transfer failures become download errors. Only errors building the
+    /// request or client stay `Http`.
+    fn from(error: reqwest::Error) -> Self {
+        let context = error.url().map(|url| url.to_string()).unwrap_or_else(|| "request".to_string());
+        if let Some(classified) = Self::classify_request(&context, &error) {
+            classified
+        } else if error.is_body() || error.is_request() || error.is_redirect() {
+            Self::Download(format!("{}: {}", context, error))
+        } else {
+            Self::Http(error)
+        }
+    }
+}
+
+/// Category of an authentication failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthErrorKind {
+    /// Any authentication failure not covered by a more specific kind
+    Other,
+    /// The refresh token was rejected (password change, revoked consent); the user must sign in again
+    ReauthRequired,
+    /// The sign-in was cancelled through its cancellation token
+    Cancelled,
+}
+
 impl LauncherError {
     /// Create a new authentication error
     pub fn auth<S: Into<String>>(msg: S) -> Self {
-        Self::Auth(msg.into())
+        Self::Auth { kind: AuthErrorKind::Other, message: msg.into() }
+    }
+
+    /// Create an authentication error that requires a fresh interactive login
+    pub fn reauth_required<S: Into<String>>(msg: S) -> Self {
+        Self::Auth { kind: AuthErrorKind::ReauthRequired, message: msg.into() }
+    }
+
+    /// Create the error a cancelled sign-in flow ends with
+    pub fn auth_cancelled<S: Into<String>>(msg: S) -> Self {
+        Self::Auth { kind: AuthErrorKind::Cancelled, message: msg.into() }
+    }
+
+    /// Get the authentication error kind, if this is an authentication error
+    pub fn auth_kind(&self) -> Option<AuthErrorKind> {
+        match self {
+            Self::Auth { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Check whether the user has to sign in again instead of retrying a refresh
+    pub fn is_reauth_required(&self) -> bool {
+        self.auth_kind() == Some(AuthErrorKind::ReauthRequired)
+    }
+
+    /// Check whether a sign-in flow stopped because it was cancelled
+    pub fn is_auth_cancelled(&self) -> bool {
+        self.auth_kind() == Some(AuthErrorKind::Cancelled)
     }
 
     /// Create a new version not found error
@@ -50,6 +111,73 @@ impl LauncherError {
         Self::Download(msg.into())
     }
 
+    /// Create an error for a request that got a non-success HTTP status
+    pub fn http_status<S: Into<String>>(status: u16, msg: S) -> Self {
+        Self::HttpStatus { status, message: msg.into() }
+    }
+
+    /// Create an error for a request that took too long
+    pub fn timeout<S: Into<String>>(msg: S) -> Self {
+        Self::Timeout(msg.into())
+    }
+
+    /// Classify a failed request: connection and DNS failures, timeouts and HTTP statuses get
+    /// their own variants, anything else becomes a download error
+    pub fn from_request<S: Into<String>>(context: S, error: reqwest::Error) -> Self {
+        let context = context.into();
+        Self::classify_request(&context, &error)
+            .unwrap_or_else(|| Self::Download(format!("{}: {}", context, error)))
+    }
+
+    /// Like `from_request`, but unclassified failures become authentication errors
+    pub(crate) fn from_auth_request<S: Into<String>>(context: S, error: reqwest::Error) -> Self {
+        let context = context.into();
+        Self::classify_request(&context, &error)
+            .unwrap_or_else(|| Self::auth(format!("{}: {}", context, error)))
+    }
+
+    fn classify_request(context: &str, error: &reqwest::Error) -> Option<Self> {
+        if error.is_timeout() {
+            Some(Self::Timeout(format!("{}: {}", context, error)))
+        } else if error.is_connect() {
+            // DNS resolution failures surface as connect errors too
+            Some(Self::NetworkUnreachable(format!("{}: {}", context, error)))
+        } else if let Some(status) = error.status() {
+            Some(Self::http_status(status.as_u16(), context))
+        } else if error.is_decode() {
+            Some(Self::Json(format!("{}: {}", context, error)))
+        } else {
+            None
+        }
+    }
+
+    /// Check whether the network or host could not be reached at all, e.g. to offer offline mode
+    pub fn is_network_unreachable(&self) -> bool {
+        matches!(self, Self::NetworkUnreachable(_))
+    }
+
+    /// Check whether a request timed out
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::Timeout(_))
+    }
+
+    /// Check whether retrying later could succeed: timeouts, unreachable hosts, 429 and 5xx
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Timeout(_) | Self::NetworkUnreachable(_) => true,
+            Self::HttpStatus { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Get the HTTP status code, if a server answered with an error status
+    pub fn http_status_code(&self) -> Option<u16> {
+        match self {
+            Self::HttpStatus { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
     /// Create a new file error
     pub fn file<S: Into<String>>(msg: S) -> Self {
         Self::File(msg.into())
@@ -96,10 +224,7 @@ impl LauncherError {
     }
 
     pub fn zip<S: Into<String>>(msg: S) -> Self {
-        LauncherError::Zip(zip::result::ZipError::Io(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            msg.into(),
-        )))
+        LauncherError::Zip(zip::result::ZipError::Io(std::io::Error::other(msg.into())))
     }
 
     pub fn java<S: Into<String>>(msg: S) -> Self {
@@ -0,0 +1,1182 @@
+//! Instance file management (world backups and other per-instance helpers)
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::config::ModLoaderConfig;
+use crate::error::{LauncherError, Result};
+
+/// File name of the per-instance metadata stored in the instance directory
+const METADATA_FILE: &str = "instance.json";
+
+/// Persistent metadata describing an instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceMetadata {
+    /// Instance name (also the directory name)
+    pub name: String,
+    /// Minecraft version the instance runs
+    pub version: String,
+    /// Mod loader installed into the instance
+    pub mod_loader: Option<ModLoaderConfig>,
+    /// When the instance was created
+    pub created_at: DateTime<Utc>,
+    /// When the instance was last launched
+    pub last_played: Option<DateTime<Utc>>,
+    /// JVM arguments for every launch of this instance, after the launcher-wide ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jvm_args: Vec<String>,
+    /// Classpath resolved at the last launch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classpath_cache: Option<ClasspathCache>,
+}
+
+/// A resolved classpath and the fingerprint of the inputs it was built from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClasspathCache {
+    /// Version, mod loader, platform and the size and modification time of the cached version JSON
+    pub fingerprint: String,
+    /// Classpath entries in launch order
+    pub entries: Vec<PathBuf>,
+}
+
+impl InstanceMetadata {
+    /// Create metadata for a new instance
+    pub fn new(name: String, version: String) -> Self {
+        Self {
+            name,
+            version,
+            mod_loader: None,
+            created_at: Utc::now(),
+            last_played: None,
+            jvm_args: Vec::new(),
+            classpath_cache: None,
+        }
+    }
+}
+
+/// A zipped backup of a single world save
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldBackup {
+    /// Path to the backup archive
+    pub path: PathBuf,
+    /// Name of the world directory stored in the archive
+    pub world_name: String,
+    /// When the backup was created
+    pub created_at: DateTime<Utc>,
+    /// Archive size in bytes
+    pub size: u64,
+}
+
+/// A screenshot taken in an instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreenshotInfo {
+    /// Path to the image file
+    pub path: PathBuf,
+    /// File name (e.g. `2024-05-01_18.30.12.png`)
+    pub file_name: String,
+    /// When the screenshot was taken
+    pub taken_at: DateTime<Utc>,
+    /// Image width in pixels, if the PNG header could be read
+    pub width: Option<u32>,
+    /// Image height in pixels, if the PNG header could be read
+    pub height: Option<u32>,
+    /// File size in bytes
+    pub size: u64,
+}
+
+/// Disk usage of an instance, in bytes, broken down by top-level directory
+///
+/// Directories linked in from a shared store and hard-linked files also take up space for
+/// other instances; they count towards `shared` rather than `unique`. The per-directory
+/// fields include both.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstanceDiskUsage {
+    /// Everything the instance uses, `unique + shared`
+    pub total: u64,
+    /// Files only this instance uses, i.e. what deleting it frees
+    pub unique: u64,
+    /// Files in linked shared directories and hard-linked files
+    pub shared: u64,
+    /// `libraries/`
+    pub libraries: u64,
+    /// `assets/`
+    pub assets: u64,
+    /// `versions/` (client jars and natives)
+    pub versions: u64,
+    /// `mods/`
+    pub mods: u64,
+    /// `saves/`
+    pub saves: u64,
+    /// `backups/`
+    pub backups: u64,
+    /// Everything else (config, logs, screenshots, packs, ...)
+    pub other: u64,
+}
+
+/// Files removed by a cleanup and the space they took
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CleanupReport {
+    /// Number of files deleted
+    pub files_removed: u64,
+    /// Combined size of the deleted files in bytes
+    pub bytes_removed: u64,
+}
+
+impl std::ops::AddAssign for CleanupReport {
+    fn add_assign(&mut self, other: Self) {
+        self.files_removed += other.files_removed;
+        self.bytes_removed += other.bytes_removed;
+    }
+}
+
+/// Instance directories where downloads write `.tmp` files
+const TEMP_FILE_DIRS: [&str; 4] = ["libraries", "assets", "versions", "backups"];
+
+/// Delete `.tmp` files left behind by interrupted downloads and writes in an instance
+pub(crate) fn clean_instance_temp_files(instance_dir: &Path) -> Result<CleanupReport> {
+    if !instance_dir.is_dir() {
+        return Err(LauncherError::file(format!("Instance not found: {}", instance_dir.display())));
+    }
+
+    // Top level only holds `instance.json.tmp`; saves and config are left alone
+    let mut report = remove_files_in(instance_dir, false, &is_temp_file)?;
+    for dir in TEMP_FILE_DIRS {
+        let dir = instance_dir.join(dir);
+        if dir.is_dir() {
+            report += remove_files_in(&dir, true, &is_temp_file)?;
+        }
+    }
+    Ok(report)
+}
+
+/// Delete `.tmp` and `.partN` files directly under `dir` and, if `recursive`, in its subdirectories
+pub(crate) fn clean_temp_files_in(dir: &Path, recursive: bool) -> Result<CleanupReport> {
+    if !dir.is_dir() {
+        return Ok(CleanupReport::default());
+    }
+    remove_files_in(dir, recursive, &is_temp_file)
+}
+
+/// Downloaded files an instance's current version still uses. Libraries and assets are relative
+/// to the directory they are downloaded to, so references from several instances can be merged
+/// and checked against a directory they share.
+#[derive(Debug, Clone)]
+pub(crate) struct ReferencedDownloads {
+    /// Versions whose `versions/<id>` directories are kept
+    pub version_ids: Vec<String>,
+    /// Library jars, including natives for every platform
+    pub libraries: std::collections::HashSet<PathBuf>,
+    /// Asset indexes and objects; `None` keeps every asset (the index couldn't be read)
+    pub assets: Option<std::collections::HashSet<PathBuf>>,
+}
+
+impl ReferencedDownloads {
+    /// No references, the starting point for merging several instances' references
+    pub fn empty() -> Self {
+        Self {
+            version_ids: Vec::new(),
+            libraries: std::collections::HashSet::new(),
+            assets: Some(std::collections::HashSet::new()),
+        }
+    }
+
+    /// Add everything `other` references
+    pub fn merge(&mut self, other: &ReferencedDownloads) {
+        self.version_ids.extend(other.version_ids.iter().cloned());
+        self.libraries.extend(other.libraries.iter().cloned());
+        match (&mut self.assets, &other.assets) {
+            (Some(assets), Some(other)) => assets.extend(other.iter().cloned()),
+            _ => self.assets = None,
+        }
+    }
+}
+
+/// Delete (or with `dry_run`, only count) libraries, assets and other versions' directories
+/// an instance no longer references. `libraries/` and `assets/` are skipped when they resolve
+/// to one of the `shared` directories; those are pruned with [`prune_shared_downloads`].
+pub(crate) fn prune_downloads(
+    instance_dir: &Path,
+    referenced: &ReferencedDownloads,
+    shared: &std::collections::HashSet<PathBuf>,
+    dry_run: bool,
+) -> Result<CleanupReport> {
+    let mut report = CleanupReport::default();
+    let is_shared = |dir: &Path| std::fs::canonicalize(dir).is_ok_and(|dir| shared.contains(&dir));
+
+    let libraries_dir = instance_dir.join("libraries");
+    if libraries_dir.is_dir() && !is_shared(&libraries_dir) {
+        report += prune_libraries(&libraries_dir, &referenced.libraries, dry_run)?;
+    }
+
+    let versions_dir = instance_dir.join("versions");
+    if versions_dir.is_dir() {
+        for entry in std::fs::read_dir(&versions_dir)? {
+            let path = entry?.path();
+            if path.is_dir() && path.file_name().is_some_and(|name| !referenced.version_ids.iter().any(|id| name == id.as_str())) {
+                report += prune_files_in(&path, dry_run, &|_| false)?;
+                if !dry_run {
+                    remove_if_empty(&path);
+                }
+            }
+        }
+    }
+
+    let assets_dir = instance_dir.join("assets");
+    if assets_dir.is_dir() && !is_shared(&assets_dir) {
+        report += prune_assets(&assets_dir, referenced.assets.as_ref(), dry_run)?;
+    }
+
+    Ok(report)
+}
+
+/// Prune libraries and assets directories used by several instances, keeping everything
+/// any of them references (`referenced` merged over all instances)
+pub(crate) fn prune_shared_downloads(
+    libraries_dirs: &[PathBuf],
+    assets_dirs: &[PathBuf],
+    referenced: &ReferencedDownloads,
+    dry_run: bool,
+) -> Result<CleanupReport> {
+    let mut report = CleanupReport::default();
+    for dir in libraries_dirs.iter().filter(|dir| dir.is_dir()) {
+        report += prune_libraries(dir, &referenced.libraries, dry_run)?;
+    }
+    for dir in assets_dirs.iter().filter(|dir| dir.is_dir()) {
+        report += prune_assets(dir, referenced.assets.as_ref(), dry_run)?;
+    }
+    Ok(report)
+}
+
+fn prune_libraries(dir: &Path, keep: &std::collections::HashSet<PathBuf>, dry_run: bool) -> Result<CleanupReport> {
+    prune_files_in(dir, dry_run, &|path| path.strip_prefix(dir).is_ok_and(|relative| keep.contains(relative)))
+}
+
+/// Prune `indexes/` and `objects/`; `None` keeps everything
+fn prune_assets(dir: &Path, keep: Option<&std::collections::HashSet<PathBuf>>, dry_run: bool) -> Result<CleanupReport> {
+    let mut report = CleanupReport::default();
+    let Some(keep) = keep else {
+        return Ok(report);
+    };
+    for subdir in ["indexes", "objects"] {
+        let subdir = dir.join(subdir);
+        if subdir.is_dir() {
+            report += prune_files_in(&subdir, dry_run, &|path| {
+                path.strip_prefix(dir).is_ok_and(|relative| keep.contains(relative))
+            })?;
+        }
+    }
+    Ok(report)
+}
+
+/// Remove files under `dir` that `keep` rejects, then any directories left empty
+fn prune_files_in(dir: &Path, dry_run: bool, keep: &dyn Fn(&Path) -> bool) -> Result<CleanupReport> {
+    let mut report = CleanupReport::default();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let metadata = std::fs::symlink_metadata(&path)?;
+
+        if metadata.is_dir() {
+            report += prune_files_in(&path, dry_run, keep)?;
+            if !dry_run {
+                remove_if_empty(&path);
+            }
+        } else if !keep(&path) {
+            if !dry_run {
+                std::fs::remove_file(&path)
+                    .map_err(|e| LauncherError::file(format!("Failed to remove {}: {}", path.display(), e)))?;
+            }
+            report.files_removed += 1;
+            report.bytes_removed += metadata.len();
+        }
+    }
+    Ok(report)
+}
+
+fn remove_if_empty(dir: &Path) {
+    if std::fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_none()) {
+        let _ = std::fs::remove_dir(dir);
+    }
+}
+
+/// `.tmp` files and the `.partN` pieces of multi-part downloads
+fn is_temp_file(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| {
+        ext == "tmp" || ext.strip_prefix("part").is_some_and(|index| !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()))
+    })
+}
+
+/// Remove matching files, never following symlinked directories
+fn remove_files_in(dir: &Path, recursive: bool, matches: &dyn Fn(&Path) -> bool) -> Result<CleanupReport> {
+    let mut report = CleanupReport::default();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let metadata = std::fs::symlink_metadata(&path)?;
+
+        if metadata.is_dir() {
+            if recursive {
+                report += remove_files_in(&path, recursive, matches)?;
+            }
+        } else if matches(&path) {
+            std::fs::remove_file(&path)
+                .map_err(|e| LauncherError::file(format!("Failed to remove {}: {}", path.display(), e)))?;
+            report.files_removed += 1;
+            report.bytes_removed += metadata.len();
+        }
+    }
+    Ok(report)
+}
+
+/// Walk an instance directory and total file sizes per top-level directory
+pub(crate) fn disk_usage(instance_dir: &Path) -> Result<InstanceDiskUsage> {
+    let mut usage = InstanceDiskUsage::default();
+    if !instance_dir.is_dir() {
+        return Err(LauncherError::file(format!("Instance not found: {}", instance_dir.display())));
+    }
+
+    for entry in std::fs::read_dir(instance_dir)? {
+        let entry = entry?;
+        let (unique, shared) = path_usage(&entry.path(), false)?;
+        let size = unique + shared;
+        usage.total += size;
+        usage.unique += unique;
+        usage.shared += shared;
+
+        let bucket = match entry.file_name().to_str() {
+            Some("libraries") => &mut usage.libraries,
+            Some("assets") => &mut usage.assets,
+            Some("versions") => &mut usage.versions,
+            Some("mods") => &mut usage.mods,
+            Some("saves") => &mut usage.saves,
+            Some("backups") => &mut usage.backups,
+            _ => &mut usage.other,
+        };
+        *bucket += size;
+    }
+
+    Ok(usage)
+}
+
+/// Bytes under `path` as `(unique, shared)`. Symlinks and junctions are followed once, and
+/// everything behind them counts as shared; links inside them are not followed again.
+fn path_usage(path: &Path, in_shared_dir: bool) -> Result<(u64, u64)> {
+    let metadata = std::fs::symlink_metadata(path)?;
+    if metadata.file_type().is_symlink() {
+        if in_shared_dir {
+            return Ok((0, 0));
+        }
+        return match std::fs::metadata(path) {
+            Ok(target) if target.is_dir() => {
+                let (unique, shared) = dir_usage(path, true)?;
+                Ok((0, unique + shared))
+            }
+            Ok(target) => Ok((0, target.len())),
+            // Dangling link
+            Err(_) => Ok((0, 0)),
+        };
+    }
+    if metadata.is_dir() {
+        return dir_usage(path, in_shared_dir);
+    }
+
+    if in_shared_dir || is_hard_linked(&metadata) {
+        Ok((0, metadata.len()))
+    } else {
+        Ok((metadata.len(), 0))
+    }
+}
+
+fn dir_usage(path: &Path, in_shared_dir: bool) -> Result<(u64, u64)> {
+    let (mut unique, mut shared) = (0, 0);
+    for entry in std::fs::read_dir(path)? {
+        let (entry_unique, entry_shared) = path_usage(&entry?.path(), in_shared_dir)?;
+        unique += entry_unique;
+        shared += entry_shared;
+    }
+    Ok((unique, shared))
+}
+
+/// Whether other paths point at the same file data
+#[cfg(unix)]
+fn is_hard_linked(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    metadata.nlink() > 1
+}
+
+#[cfg(not(unix))]
+fn is_hard_linked(_metadata: &std::fs::Metadata) -> bool {
+    false
+}
+
+/// Read `instance.json` from an instance directory, if it exists
+pub(crate) fn read_metadata(instance_dir: &Path) -> Result<Option<InstanceMetadata>> {
+    let path = instance_dir.join(METADATA_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| LauncherError::file(format!("Failed to read instance metadata: {}", e)))?;
+    match serde_json::from_str(&content) {
+        Ok(metadata) => Ok(Some(metadata)),
+        Err(e) => {
+            // Treated like a missing file so the next launch rebuilds it
+            crate::utils::quarantine_corrupt_file(&path, &e.to_string());
+            Ok(None)
+        }
+    }
+}
+
+/// Read the metadata of every instance under `instances_dir` that has an `instance.json`
+pub(crate) fn list_metadata(instances_dir: &Path) -> Result<Vec<InstanceMetadata>> {
+    if !instances_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut instances = Vec::new();
+    for entry in std::fs::read_dir(instances_dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if let Some(metadata) = read_metadata(&path)? {
+                instances.push(metadata);
+            }
+        }
+    }
+    instances.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(instances)
+}
+
+/// Write `instance.json` into an instance directory
+pub(crate) fn write_metadata(instance_dir: &Path, metadata: &InstanceMetadata) -> Result<()> {
+    let content = serde_json::to_string_pretty(metadata)?;
+    crate::utils::write_atomic(&instance_dir.join(METADATA_FILE), content.as_bytes())
+        .map_err(|e| LauncherError::file(format!("Failed to write instance metadata: {}", e)))
+}
+
+/// Reject names that would escape the directory they are joined onto
+pub(crate) fn validate_entry_name(name: &str) -> Result<()> {
+    // `:` rules out Windows drive prefixes (`C:evil`) as well as alternate data streams
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\', ':', '\0']) {
+        return Err(LauncherError::validation(format!("Invalid name: {}", name)));
+    }
+    Ok(())
+}
+
+/// Zip `saves/<world_name>` into a timestamped archive inside `backups_dir`
+pub(crate) fn backup_world(saves_dir: &Path, backups_dir: &Path, world_name: &str) -> Result<PathBuf> {
+    validate_entry_name(world_name)?;
+
+    let world_dir = saves_dir.join(world_name);
+    if !world_dir.is_dir() {
+        return Err(LauncherError::file(format!("World not found: {}", world_dir.display())));
+    }
+
+    std::fs::create_dir_all(backups_dir)
+        .map_err(|e| LauncherError::file(format!("Failed to create backups directory: {}", e)))?;
+
+    let backup_name = format!("{}_{}.zip", world_name, Utc::now().format("%Y-%m-%d_%H-%M-%S"));
+    let backup_path = backups_dir.join(backup_name);
+    let temp_path = backup_path.with_extension("tmp");
+
+    let file = std::fs::File::create(&temp_path)
+        .map_err(|e| LauncherError::file(format!("Failed to create backup file: {}", e)))?;
+    let mut writer = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let result = add_directory_to_zip(&mut writer, &world_dir, Path::new(world_name), options)
+        .and_then(|_| writer.finish().map(|_| ()).map_err(LauncherError::from));
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    std::fs::rename(&temp_path, &backup_path)
+        .map_err(|e| LauncherError::file(format!("Failed to finalize backup: {}", e)))?;
+
+    log::info!("Backed up world {} to {}", world_name, backup_path.display());
+    Ok(backup_path)
+}
+
+fn add_directory_to_zip<W: Write + std::io::Seek>(
+    writer: &mut zip::ZipWriter<W>,
+    dir: &Path,
+    prefix: &Path,
+    options: zip::write::SimpleFileOptions,
+) -> Result<()> {
+    let entry_name = |path: &Path| path.to_string_lossy().replace('\\', "/");
+    writer.add_directory(entry_name(prefix), options)?;
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = prefix.join(entry.file_name());
+
+        if path.is_dir() {
+            add_directory_to_zip(writer, &path, &name, options)?;
+        } else {
+            // The game holds session.lock open while running; it is recreated on load anyway
+            if entry.file_name() == "session.lock" {
+                continue;
+            }
+            writer.start_file(entry_name(&name), options)?;
+            let mut file = std::fs::File::open(&path)?;
+            std::io::copy(&mut file, writer)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the world directory name stored at the root of a backup archive
+fn backup_world_name<R: Read + std::io::Seek>(archive: &mut zip::ZipArchive<R>) -> Result<String> {
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i)?;
+        if let Some(path) = entry.enclosed_name() {
+            if let Some(std::path::Component::Normal(first)) = path.components().next() {
+                return Ok(first.to_string_lossy().to_string());
+            }
+        }
+    }
+    Err(LauncherError::validation("Backup archive does not contain a world"))
+}
+
+/// Restore a backup archive into `saves_dir`, replacing the existing world of the same name.
+/// Returns the restored world name.
+pub(crate) fn restore_world(saves_dir: &Path, backup_path: &Path) -> Result<String> {
+    let file = std::fs::File::open(backup_path)
+        .map_err(|e| LauncherError::file(format!("Failed to open backup {}: {}", backup_path.display(), e)))?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    let world_name = backup_world_name(&mut archive)?;
+    validate_entry_name(&world_name)?;
+
+    // Extract next to the target first so a failed restore leaves the current world untouched
+    let staging_dir = saves_dir.join(format!(".restore-{}", world_name));
+    if staging_dir.exists() {
+        std::fs::remove_dir_all(&staging_dir)?;
+    }
+    std::fs::create_dir_all(&staging_dir)?;
+    if let Err(e) = archive.extract(&staging_dir) {
+        let _ = std::fs::remove_dir_all(&staging_dir);
+        return Err(e.into());
+    }
+
+    let world_dir = saves_dir.join(&world_name);
+    if world_dir.exists() {
+        std::fs::remove_dir_all(&world_dir)
+            .map_err(|e| LauncherError::file(format!("Failed to remove existing world: {}", e)))?;
+    }
+    std::fs::rename(staging_dir.join(&world_name), &world_dir)
+        .map_err(|e| LauncherError::file(format!("Failed to move restored world into place: {}", e)))?;
+    let _ = std::fs::remove_dir_all(&staging_dir);
+
+    log::info!("Restored world {} from {}", world_name, backup_path.display());
+    Ok(world_name)
+}
+
+/// List world backups in `backups_dir`, newest first
+pub(crate) fn list_backups(backups_dir: &Path) -> Result<Vec<WorldBackup>> {
+    let mut backups = Vec::new();
+    if !backups_dir.exists() {
+        return Ok(backups);
+    }
+
+    for entry in std::fs::read_dir(backups_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("zip") {
+            continue;
+        }
+
+        let world_name = match std::fs::File::open(&path)
+            .map_err(LauncherError::from)
+            .and_then(|file| Ok(zip::ZipArchive::new(file)?))
+            .and_then(|mut archive| backup_world_name(&mut archive))
+        {
+            Ok(name) => name,
+            Err(e) => {
+                log::warn!("Skipping unreadable backup {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        let metadata = std::fs::metadata(&path)?;
+        let created_at = metadata
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+
+        backups.push(WorldBackup {
+            path,
+            world_name,
+            created_at,
+            size: metadata.len(),
+        });
+    }
+
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    Ok(backups)
+}
+
+/// Read width and height from a PNG's IHDR chunk without decoding the image
+fn read_png_dimensions(path: &Path) -> Option<(u32, u32)> {
+    const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    let mut header = [0u8; 24];
+    std::fs::File::open(path).ok()?.read_exact(&mut header).ok()?;
+    if &header[..8] != PNG_SIGNATURE || &header[12..16] != b"IHDR" {
+        return None;
+    }
+
+    let width = u32::from_be_bytes(header[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(header[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// Parse the `YYYY-MM-DD_HH.MM.SS` timestamp Minecraft uses for screenshot names
+fn parse_screenshot_time(file_name: &str) -> Option<DateTime<Utc>> {
+    let stem = file_name.strip_suffix(".png")?;
+    // Duplicates taken in the same second get a `_N` suffix
+    let timestamp = stem.get(..19)?;
+    chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d_%H.%M.%S")
+        .ok()
+        .and_then(|time| time.and_local_timezone(chrono::Local).single())
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// List PNG screenshots in `screenshots_dir`, newest first
+pub(crate) fn list_screenshots(screenshots_dir: &Path) -> Result<Vec<ScreenshotInfo>> {
+    let mut screenshots = Vec::new();
+    if !screenshots_dir.exists() {
+        return Ok(screenshots);
+    }
+
+    for entry in std::fs::read_dir(screenshots_dir)? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some("png") {
+            continue;
+        }
+
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let metadata = std::fs::metadata(&path)?;
+        let taken_at = parse_screenshot_time(&file_name)
+            .or_else(|| metadata.modified().ok().map(DateTime::<Utc>::from))
+            .unwrap_or_else(Utc::now);
+        let dimensions = read_png_dimensions(&path);
+
+        screenshots.push(ScreenshotInfo {
+            path,
+            file_name,
+            taken_at,
+            width: dimensions.map(|(width, _)| width),
+            height: dimensions.map(|(_, height)| height),
+            size: metadata.len(),
+        });
+    }
+
+    screenshots.sort_by_key(|screenshot| std::cmp::Reverse(screenshot.taken_at));
+    Ok(screenshots)
+}
+
+/// Read the raw bytes of a screenshot by file name
+pub(crate) fn read_screenshot(screenshots_dir: &Path, file_name: &str) -> Result<Vec<u8>> {
+    validate_entry_name(file_name)?;
+    let path = screenshots_dir.join(file_name);
+    std::fs::read(&path)
+        .map_err(|e| LauncherError::file(format!("Failed to read screenshot {}: {}", path.display(), e)))
+}
+
+/// Worlds whose `session.lock` is currently held, i.e. open in a running game.
+///
+/// The game only locks a world while it is loaded, not on the title screen. Locks taken on
+/// another machine over a network share may not be visible, so this complements the
+/// launcher's own process tracking rather than replacing it.
+pub(crate) fn locked_worlds(saves_dir: &Path) -> Result<Vec<String>> {
+    if !saves_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut locked = Vec::new();
+    for entry in std::fs::read_dir(saves_dir)? {
+        let entry = entry?;
+        let lock_path = entry.path().join("session.lock");
+        let Ok(file) = std::fs::OpenOptions::new().read(true).write(true).open(&lock_path) else {
+            continue;
+        };
+        if session_lock_held(&file) {
+            locked.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    Ok(locked)
+}
+
+/// Whether another process holds a lock on `session.lock`.
+///
+/// Java's `FileChannel.tryLock` takes a POSIX record lock (`fcntl`), which `flock`-based
+/// locking such as `File::try_lock` does not see on Linux, so ask `fcntl` which lock would
+/// conflict without taking one.
+#[cfg(unix)]
+fn session_lock_held(file: &std::fs::File) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    // SAFETY: `flock` is plain data, all zeroes is a valid value (start 0, length 0 = whole file)
+    let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+    lock.l_type = libc::F_WRLCK as _;
+    lock.l_whence = libc::SEEK_SET as _;
+    // SAFETY: the descriptor is open for the duration of the call and `lock` outlives it
+    let result = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETLK, &mut lock) };
+    result != -1 && libc::c_int::from(lock.l_type) != libc::F_UNLCK
+}
+
+/// Whether another process holds a lock on `session.lock`; Java's `tryLock` uses `LockFileEx`
+/// on Windows, the same lock `File::try_lock` takes
+#[cfg(not(unix))]
+fn session_lock_held(file: &std::fs::File) -> bool {
+    matches!(file.try_lock(), Err(std::fs::TryLockError::WouldBlock))
+}
+
+/// How a shared directory was made available inside an instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SharedDirLink {
+    /// Symbolic link to the shared directory
+    Symlink,
+    /// NTFS directory junction (Windows without symlink permission)
+    #[cfg_attr(not(windows), allow(dead_code))]
+    Junction,
+}
+
+/// Make `link` inside an instance point at the shared directory `target`, creating `target` if needed.
+/// An existing link is replaced and an empty directory removed; a directory with files in it is
+/// left alone and reported as an error so nothing the player put there is lost. There is no copy
+/// fallback: if neither a symlink nor a junction can be created, this fails.
+pub(crate) fn link_shared_dir(target: &Path, link: &Path) -> Result<SharedDirLink> {
+    std::fs::create_dir_all(target)
+        .map_err(|e| LauncherError::file(format!("Failed to create {}: {}", target.display(), e)))?;
+
+    if let Ok(metadata) = std::fs::symlink_metadata(link) {
+        if metadata.file_type().is_symlink() || is_junction(link) {
+            let points_at_target = std::fs::read_link(link)
+                .is_ok_and(|current| without_verbatim_prefix(&current) == without_verbatim_prefix(target));
+            if points_at_target {
+                return Ok(if metadata.file_type().is_symlink() { SharedDirLink::Symlink } else { SharedDirLink::Junction });
+            }
+            remove_link(link)?;
+        } else if metadata.is_dir() {
+            if std::fs::read_dir(link)?.next().is_some() {
+                return Err(LauncherError::file(format!(
+                    "{} already contains files; move them into {} to share it",
+                    link.display(),
+                    target.display()
+                )));
+            }
+            std::fs::remove_dir(link)?;
+        } else {
+            return Err(LauncherError::file(format!("{} exists and is not a directory", link.display())));
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(target, link)
+            .map_err(|e| LauncherError::file(format!("Failed to link {}: {}", link.display(), e)))?;
+        Ok(SharedDirLink::Symlink)
+    }
+
+    #[cfg(windows)]
+    {
+        // Symlinks need Developer Mode or admin rights; junctions don't
+        if std::os::windows::fs::symlink_dir(target, link).is_ok() {
+            return Ok(SharedDirLink::Symlink);
+        }
+        let junction = std::process::Command::new("cmd")
+            .arg("/C")
+            .arg("mklink")
+            .arg("/J")
+            .arg(link)
+            .arg(target)
+            .output()
+            .map_err(|e| LauncherError::file(format!("Failed to link {}: {}", link.display(), e)))?;
+        if !junction.status.success() {
+            return Err(LauncherError::file(format!(
+                "Failed to link {} to {}: no symlink permission and creating a junction failed: {}",
+                link.display(),
+                target.display(),
+                String::from_utf8_lossy(&junction.stderr).trim()
+            )));
+        }
+        Ok(SharedDirLink::Junction)
+    }
+}
+
+/// `read_link` reports junction targets in their verbatim `\\?\C:\...` form
+fn without_verbatim_prefix(path: &Path) -> PathBuf {
+    match path.to_string_lossy().strip_prefix(r"\\?\") {
+        Some(rest) => PathBuf::from(rest),
+        None => path.to_path_buf(),
+    }
+}
+
+#[cfg(windows)]
+fn is_junction(path: &Path) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+    std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT != 0)
+}
+
+#[cfg(not(windows))]
+fn is_junction(_path: &Path) -> bool {
+    false
+}
+
+/// Remove a symlink or junction without touching what it points to
+fn remove_link(link: &Path) -> Result<()> {
+    // Directory symlinks and junctions on Windows are removed like directories
+    std::fs::remove_file(link)
+        .or_else(|_| std::fs::remove_dir(link))
+        .map_err(|e| LauncherError::file(format!("Failed to remove link {}: {}", link.display(), e)))
+}
+
+/// Add `key:value` lines to the instance's `options.txt` for keys it doesn't have yet, creating
+/// the file if needed. Values the player (or the game) already set are never changed.
+/// Returns the number of lines added.
+pub(crate) fn seed_options(instance_dir: &Path, entries: &[(&str, String)]) -> Result<usize> {
+    let path = instance_dir.join("options.txt");
+    let mut content = if path.exists() {
+        std::fs::read_to_string(&path)
+            .map_err(|e| LauncherError::file(format!("Failed to read options.txt: {}", e)))?
+    } else {
+        String::new()
+    };
+
+    let missing: Vec<&(&str, String)> = entries
+        .iter()
+        .filter(|(key, _)| !content.lines().any(|line| line.split_once(':').is_some_and(|(k, _)| k == *key)))
+        .collect();
+    if missing.is_empty() {
+        return Ok(0);
+    }
+
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    for (key, value) in &missing {
+        content.push_str(&format!("{}:{}\n", key, value));
+    }
+    std::fs::write(&path, content)
+        .map_err(|e| LauncherError::file(format!("Failed to write options.txt: {}", e)))?;
+    Ok(missing.len())
+}
+
+/// Set `lang:` in the instance's `options.txt` unless it already has one.
+/// Versions before 1.11 (snapshot 16w32a) expect codes like `de_DE`, later ones `de_de`.
+/// Returns whether the file was changed.
+pub(crate) fn seed_language(instance_dir: &Path, code: &str, lowercase_codes: bool) -> Result<bool> {
+    let code = code.replace('-', "_");
+    let code = match code.split_once('_') {
+        Some((language, region)) if !lowercase_codes => {
+            format!("{}_{}", language.to_lowercase(), region.to_uppercase())
+        }
+        _ => code.to_lowercase(),
+    };
+
+    Ok(seed_options(instance_dir, &[("lang", code)])? > 0)
+}
+
+/// Recursively copy files from `source` into `destination`, skipping files that already exist.
+/// Returns the number of files copied.
+pub(crate) fn seed_directory(source: &Path, destination: &Path) -> Result<usize> {
+    if !source.is_dir() {
+        return Err(LauncherError::file(format!("Seed directory not found: {}", source.display())));
+    }
+
+    std::fs::create_dir_all(destination)?;
+
+    let mut copied = 0;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let source_path = entry.path();
+        let destination_path = destination.join(entry.file_name());
+
+        if source_path.is_dir() {
+            copied += seed_directory(&source_path, &destination_path)?;
+        } else if !destination_path.exists() {
+            std::fs::copy(&source_path, &destination_path).map_err(|e| {
+                LauncherError::file(format!("Failed to copy {}: {}", source_path.display(), e))
+            })?;
+            copied += 1;
+        }
+    }
+
+    Ok(copied)
+}
+
+/// Profile list of the official launcher, inside its `.minecraft` directory
+const VANILLA_PROFILES_FILE: &str = "launcher_profiles.json";
+
+/// What [`Launcher::import_vanilla_profiles`](crate::Launcher::import_vanilla_profiles) brought over
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VanillaImportReport {
+    /// Instances created, by name
+    pub instances: Vec<String>,
+    /// Profiles and versions left out because the instance already exists or the version could not be resolved
+    pub skipped: Vec<String>,
+    /// Accounts imported with a still usable access token, by name
+    pub accounts: Vec<String>,
+    /// Accounts whose tokens could not be imported; these have to sign in again
+    pub accounts_needing_sign_in: Vec<String>,
+}
+
+/// A profile from the official launcher's `launcher_profiles.json`
+#[derive(Debug, Clone)]
+pub(crate) struct VanillaProfile {
+    /// Display name of the profile
+    pub name: String,
+    /// Version ID, or `latest-release` / `latest-snapshot`
+    pub version: String,
+    /// Game directory holding the profile's saves and options, if the profile sets one
+    pub game_dir: Option<PathBuf>,
+    /// JVM arguments set on the profile
+    pub jvm_args: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct VanillaProfilesFile {
+    #[serde(default)]
+    profiles: std::collections::HashMap<String, VanillaProfileEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VanillaProfileEntry {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type", default)]
+    profile_type: String,
+    last_version_id: Option<String>,
+    game_dir: Option<PathBuf>,
+    java_args: Option<String>,
+}
+
+/// Read the profiles of an official launcher directory; none if it has no `launcher_profiles.json`
+pub(crate) fn read_vanilla_profiles(mc_dir: &Path) -> Result<Vec<VanillaProfile>> {
+    let path = mc_dir.join(VANILLA_PROFILES_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| LauncherError::file(format!("Failed to read {}: {}", path.display(), e)))?;
+    let file: VanillaProfilesFile = serde_json::from_str(&content)
+        .map_err(|e| LauncherError::json(format!("Failed to parse {}: {}", path.display(), e)))?;
+
+    let mut profiles: Vec<VanillaProfile> = file
+        .profiles
+        .into_values()
+        .filter_map(|entry| {
+            // The built-in "latest" profiles have no name and may omit the version
+            let version = entry.last_version_id.or_else(|| {
+                matches!(entry.profile_type.as_str(), "latest-release" | "latest-snapshot").then(|| entry.profile_type.clone())
+            })?;
+            let name = match entry.name.trim() {
+                "" if version == "latest-release" => "Latest release".to_string(),
+                "" if version == "latest-snapshot" => "Latest snapshot".to_string(),
+                "" => version.clone(),
+                name => name.to_string(),
+            };
+            Some(VanillaProfile {
+                name,
+                version,
+                game_dir: entry.game_dir,
+                jvm_args: entry.java_args.map(|args| args.split_whitespace().map(str::to_string).collect()).unwrap_or_default(),
+            })
+        })
+        .collect();
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+/// IDs of the versions installed in a `.minecraft` directory, i.e. those with a version JSON
+pub(crate) fn installed_vanilla_versions(mc_dir: &Path) -> Result<Vec<String>> {
+    let versions_dir = mc_dir.join("versions");
+    if !versions_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut versions = Vec::new();
+    for entry in std::fs::read_dir(&versions_dir)? {
+        let entry = entry?;
+        let id = entry.file_name().to_string_lossy().into_owned();
+        if entry.path().join(format!("{}.json", id)).is_file() {
+            versions.push(id);
+        }
+    }
+    versions.sort();
+    Ok(versions)
+}
+
+/// Turn a profile name into a name that can be used as an instance directory
+pub(crate) fn sanitize_instance_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    let sanitized = sanitized.trim().trim_end_matches('.');
+    if sanitized.is_empty() {
+        "imported".to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+/// Copy what an instance needs from an official launcher directory: the version folder and,
+/// when a game directory is given, its saves and `options.txt`. Existing files are kept.
+///
+/// The version JSON is left out: the instance's copy is the resolved profile, which the caller
+/// writes after resolving the official launcher's (possibly inheriting) JSON.
+pub(crate) fn copy_vanilla_game_data(mc_dir: &Path, version_id: &str, game_dir: Option<&Path>, instance_dir: &Path) -> Result<()> {
+    let version_dir = mc_dir.join("versions").join(version_id);
+    if version_dir.is_dir() {
+        let destination = instance_dir.join("versions").join(version_id);
+        std::fs::create_dir_all(&destination)?;
+        let version_json = format!("{}.json", version_id);
+        for entry in std::fs::read_dir(&version_dir)? {
+            let entry = entry?;
+            let (source_path, destination_path) = (entry.path(), destination.join(entry.file_name()));
+            if source_path.is_dir() {
+                seed_directory(&source_path, &destination_path)?;
+            } else if entry.file_name() != version_json.as_str() && !destination_path.exists() {
+                std::fs::copy(&source_path, &destination_path)
+                    .map_err(|e| LauncherError::file(format!("Failed to copy {}: {}", source_path.display(), e)))?;
+            }
+        }
+    }
+
+    let Some(game_dir) = game_dir else {
+        return Ok(());
+    };
+    let saves_dir = game_dir.join("saves");
+    if saves_dir.is_dir() {
+        seed_directory(&saves_dir, &instance_dir.join("saves"))?;
+    }
+    let options = game_dir.join("options.txt");
+    let instance_options = instance_dir.join("options.txt");
+    if options.is_file() && !instance_options.exists() {
+        std::fs::copy(&options, &instance_options)
+            .map_err(|e| LauncherError::file(format!("Failed to copy {}: {}", options.display(), e)))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_vanilla_launcher_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(VANILLA_PROFILES_FILE),
+            r#"{
+                "profiles": {
+                    "a1b2": {"name": "", "type": "latest-release", "lastVersionId": "latest-release"},
+                    "c3d4": {"name": "Modded", "type": "custom", "lastVersionId": "1.20.1-forge-47.2.0",
+                             "gameDir": "/games/modded", "javaArgs": "-Xmx4G  -XX:+UseG1GC"},
+                    "e5f6": {"type": "latest-snapshot"},
+                    "g7h8": {"name": "Broken", "type": "custom"}
+                },
+                "settings": {"enableSnapshots": true},
+                "version": 3
+            }"#,
+        )
+        .unwrap();
+
+        let profiles = read_vanilla_profiles(dir.path()).unwrap();
+        let names: Vec<&str> = profiles.iter().map(|profile| profile.name.as_str()).collect();
+        assert_eq!(names, ["Latest release", "Latest snapshot", "Modded"]);
+
+        let modded = &profiles[2];
+        assert_eq!(modded.version, "1.20.1-forge-47.2.0");
+        assert_eq!(modded.game_dir.as_deref(), Some(Path::new("/games/modded")));
+        assert_eq!(modded.jvm_args, ["-Xmx4G", "-XX:+UseG1GC"]);
+        assert_eq!(profiles[0].version, "latest-release");
+        assert_eq!(profiles[0].game_dir, None);
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(read_vanilla_profiles(empty.path()).unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shared_dirs_are_symlinked() {
+        let dir = tempfile::tempdir().unwrap();
+        let shared = dir.path().join("shared").join("resourcepacks");
+        let other = dir.path().join("shared").join("other");
+        let link = dir.path().join("instance").join("resourcepacks");
+        std::fs::create_dir_all(&link).unwrap();
+
+        // An empty directory is replaced, and linking again keeps the link
+        assert_eq!(link_shared_dir(&shared, &link).unwrap(), SharedDirLink::Symlink);
+        assert_eq!(link_shared_dir(&shared, &link).unwrap(), SharedDirLink::Symlink);
+        assert_eq!(std::fs::read_link(&link).unwrap(), shared);
+
+        // A link to another directory is moved without touching either directory
+        std::fs::write(shared.join("pack.zip"), b"pack").unwrap();
+        link_shared_dir(&other, &link).unwrap();
+        assert_eq!(std::fs::read_link(&link).unwrap(), other);
+        assert!(shared.join("pack.zip").exists());
+
+        // A real directory with files in it is never replaced
+        let populated = dir.path().join("instance").join("shaderpacks");
+        std::fs::create_dir_all(&populated).unwrap();
+        std::fs::write(populated.join("shader.zip"), b"shader").unwrap();
+        assert!(link_shared_dir(&shared, &populated).is_err());
+        assert!(populated.join("shader.zip").exists());
+    }
+
+    #[test]
+    fn junction_targets_compare_without_verbatim_prefix() {
+        assert_eq!(without_verbatim_prefix(Path::new(r"\\?\C:\shared\mods")), PathBuf::from(r"C:\shared\mods"));
+        assert_eq!(without_verbatim_prefix(Path::new("/shared/mods")), PathBuf::from("/shared/mods"));
+    }
+
+    #[test]
+    fn temp_file_cleanup_keeps_complete_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("cache");
+        std::fs::create_dir_all(cache.join("versions")).unwrap();
+        std::fs::write(cache.join("version_manifest_v2.json"), b"{}").unwrap();
+        std::fs::write(cache.join("versions").join("1.20.1.json"), b"{}").unwrap();
+        std::fs::write(cache.join("version_manifest_v2.tmp"), b"{").unwrap();
+        std::fs::write(cache.join("client.part0"), b"12").unwrap();
+        std::fs::write(cache.join("notes.partial"), b"keep").unwrap();
+
+        let report = clean_temp_files_in(&cache, true).unwrap();
+        assert_eq!(report, CleanupReport { files_removed: 2, bytes_removed: 3 });
+        assert!(cache.join("version_manifest_v2.json").exists());
+        assert!(cache.join("versions").join("1.20.1.json").exists());
+        assert!(cache.join("notes.partial").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn disk_usage_separates_shared_and_unique_bytes() {
+        let root = tempfile::tempdir().unwrap();
+        let instance_dir = root.path().join("instance");
+        let shared_saves = root.path().join("shared-saves");
+        std::fs::create_dir_all(instance_dir.join("mods")).unwrap();
+        std::fs::create_dir_all(instance_dir.join("libraries")).unwrap();
+        std::fs::create_dir_all(&shared_saves).unwrap();
+
+        std::fs::write(instance_dir.join("mods").join("mod.jar"), [0u8; 100]).unwrap();
+        std::fs::write(root.path().join("store.jar"), [0u8; 40]).unwrap();
+        std::fs::hard_link(root.path().join("store.jar"), instance_dir.join("libraries").join("lib.jar")).unwrap();
+        std::fs::write(shared_saves.join("level.dat"), [0u8; 7]).unwrap();
+        std::os::unix::fs::symlink(&shared_saves, instance_dir.join("saves")).unwrap();
+
+        let usage = disk_usage(&instance_dir).unwrap();
+        assert_eq!(usage.unique, 100);
+        assert_eq!(usage.shared, 47);
+        assert_eq!(usage.total, 147);
+        assert_eq!(usage.saves, 7);
+        assert_eq!(usage.libraries, 40);
+    }
+}
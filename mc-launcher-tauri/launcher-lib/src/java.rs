@@ -1,6 +1,8 @@
 //! This module handles the downloading and management of Java runtimes.
 
+use crate::downloader::{Downloader, FileHash, ProgressTracker};
 use crate::error::LauncherError;
+use crate::version::release_series;
 use flate2::read::GzDecoder;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -10,6 +12,14 @@ use zip::ZipArchive;
 // Azul Zulu API URL (better Java 8 support than Adoptium)
 const AZUL_API_URL: &str = "https://api.azul.com/metadata/v1/zulu/packages";
 
+/// Written into every runtime directory `download_java_runtime` extracts. The runtime directory
+/// is usually shared with the official launcher and may hold JDKs the user installed, so only
+/// directories carrying this marker are ever deleted.
+const MANAGED_RUNTIME_MARKER: &str = ".mc-launcher-runtime.json";
+
+/// Idle timeout in seconds for runtime downloads made by a `JavaManager` created with `new`
+const JAVA_DOWNLOAD_TIMEOUT: u64 = 60;
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ZuluPackage {
     name: String,
@@ -17,20 +27,42 @@ struct ZuluPackage {
     sha256_hash: Option<String>,
 }
 
+/// Contents of [`MANAGED_RUNTIME_MARKER`]
+#[derive(Debug, Serialize, Deserialize)]
+struct ManagedRuntime {
+    major_version: u32,
+    /// File name of the archive the runtime was extracted from, next to the runtime directory
+    archive: String,
+}
+
 /// Manages Java runtimes for Minecraft.
-#[derive(Debug)]
 pub struct JavaManager {
     runtime_dir: PathBuf,
-    client: reqwest::Client,
+    downloader: Downloader,
 }
 
 impl JavaManager {
-    /// Creates a new `JavaManager`.
-    pub fn new(runtime_dir: PathBuf) -> Self {
-        Self {
-            runtime_dir,
-            client: reqwest::Client::new(),
-        }
+    /// Creates a new `JavaManager` with its own downloader.
+    pub fn new(runtime_dir: PathBuf) -> Result<Self, LauncherError> {
+        let downloader = Downloader::new(1, JAVA_DOWNLOAD_TIMEOUT)?;
+        Ok(Self::from_downloader(runtime_dir, downloader))
+    }
+
+    /// Creates a `JavaManager` that downloads runtimes through `downloader`, sharing its client
+    /// settings and progress tracker.
+    pub fn from_downloader(runtime_dir: PathBuf, downloader: Downloader) -> Self {
+        Self { runtime_dir, downloader }
+    }
+
+    /// Reports runtime download progress to `tracker`.
+    pub fn with_progress_tracker(mut self, tracker: ProgressTracker) -> Self {
+        self.downloader = self.downloader.with_progress_tracker(tracker);
+        self
+    }
+
+    /// The tracker runtime downloads report to, if any.
+    pub fn progress_tracker(&self) -> Option<&ProgressTracker> {
+        self.downloader.progress_tracker()
     }
 
     /// Gets the path to a suitable Java runtime for the given Minecraft version.
@@ -54,7 +86,13 @@ impl JavaManager {
             AZUL_API_URL, major_version, os, arch
         );
 
-        let response = self.client.get(&url).send().await?;
+        let response = self
+            .downloader
+            .client()
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| LauncherError::from_request("Azul API request failed", e))?;
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
@@ -67,7 +105,7 @@ impl JavaManager {
         }
 
         let packages: Vec<ZuluPackage> = response.json().await?;
-        let package = packages.get(0).ok_or_else(|| {
+        let package = packages.first().ok_or_else(|| {
             LauncherError::java(format!(
                 "No download package found for Java {}",
                 major_version
@@ -78,16 +116,35 @@ impl JavaManager {
         let file_name = &package.name;
         let download_path = self.runtime_dir.join(file_name);
         
-        // Note: Azul provides sha256, but for simplicity we are not verifying it here.
-        // In a production-ready launcher, you would want to implement sha256 verification.
-        crate::utils::download_file(&self.client, download_url, &download_path, None).await?;
+        let expected_hash = package.sha256_hash.clone().map(FileHash::Sha256);
+        self.downloader
+            .download_file_verified(download_url, &download_path, expected_hash.as_ref(), None, None)
+            .await?;
 
         let extraction_dir_name = self.get_extraction_dir_name(file_name);
         let extraction_path = self.runtime_dir.join(extraction_dir_name);
-        self.extract_archive(&download_path, &extraction_path)?;
+        let marker = ManagedRuntime { major_version, archive: file_name.clone() };
+        tokio::task::spawn_blocking(move || {
+            extract_archive(&download_path, &extraction_path)?;
+            std::fs::write(extraction_path.join(MANAGED_RUNTIME_MARKER), serde_json::to_vec(&marker)?)?;
+            find_java_executable(&extraction_path)
+                .ok_or_else(|| LauncherError::java("Failed to find Java runtime after extraction".to_string()))
+        })
+        .await
+        .map_err(|e| LauncherError::other(format!("Extracting Java runtime failed: {}", e)))?
+    }
+
+    /// Deletes the runtimes this launcher downloaded for the given Minecraft version's Java major
+    /// version, then downloads it again. Runtimes it didn't extract itself are left alone.
+    pub async fn repair_runtime(&self, version: &str) -> Result<PathBuf, LauncherError> {
+        let major_version = self.get_required_java_version(version).await?;
 
-        self.find_java_runtime(major_version)?
-            .ok_or_else(|| LauncherError::java("Failed to find Java runtime after extraction".to_string()))
+        let runtime_dir = self.runtime_dir.clone();
+        tokio::task::spawn_blocking(move || remove_managed_runtimes(&runtime_dir, major_version))
+            .await
+            .map_err(|e| LauncherError::other(format!("Removing Java runtime failed: {}", e)))??;
+
+        self.download_java_runtime(version).await
     }
 
     fn get_extraction_dir_name(&self, file_name: &str) -> String {
@@ -126,20 +183,6 @@ impl JavaManager {
         (os, arch)
     }
 
-    /// Extracts the downloaded archive.
-    fn extract_archive(&self, archive_path: &Path, extraction_path: &Path) -> Result<(), LauncherError> {
-        let file = std::fs::File::open(archive_path)?;
-        if archive_path.extension().map_or(false, |e| e == "gz") {
-            let decoder = GzDecoder::new(file);
-            let mut archive = Archive::new(decoder);
-            archive.unpack(extraction_path)?;
-        } else if archive_path.extension().map_or(false, |e| e == "zip") {
-            let mut archive = ZipArchive::new(file)?;
-            archive.extract(extraction_path)?;
-        }
-        Ok(())
-    }
-
     /// Finds a Java runtime for the given major version in the runtime directory.
     fn find_java_runtime(&self, major_version: u32) -> Result<Option<PathBuf>, LauncherError> {
         if !self.runtime_dir.exists() {
@@ -151,7 +194,7 @@ impl JavaManager {
             let entry = entry?;
             let path = entry.path();
             if path.is_dir() {
-                if let Some(executable) = self.find_java_executable(&path) {
+                if let Some(executable) = find_java_executable(&path) {
                     if self.check_java_version(&executable, major_version)? {
                         return Ok(Some(executable));
                     }
@@ -162,43 +205,6 @@ impl JavaManager {
         Ok(None)
     }
 
-    /// Finds the Java executable within a given directory.
-    fn find_java_executable(&self, dir: &Path) -> Option<PathBuf> {
-        let executable_name = if cfg!(windows) { "java.exe" } else { "java" };
-        
-        // For Azul Zulu, we need to search recursively since the structure varies
-        self.find_java_executable_recursive(dir, executable_name)
-    }
-    
-    /// Recursively searches for the Java executable in the directory tree
-    fn find_java_executable_recursive(&self, dir: &Path, executable_name: &str) -> Option<PathBuf> {
-        // First check direct bin directory
-        let direct_bin = dir.join("bin").join(executable_name);
-        if direct_bin.exists() {
-            return Some(direct_bin);
-        }
-        
-        // Check macOS structure
-        let macos_bin = dir.join("Contents").join("Home").join("bin").join(executable_name);
-        if macos_bin.exists() {
-            return Some(macos_bin);
-        }
-        
-        // Recursively search subdirectories (common with Azul Zulu extractions)
-        if let Ok(entries) = std::fs::read_dir(dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    if let Some(found) = self.find_java_executable_recursive(&path, executable_name) {
-                        return Some(found);
-                    }
-                }
-            }
-        }
-        
-        None
-    }
-
     /// Checks if the Java executable at the given path has the correct major version.
     fn check_java_version(
         &self,
@@ -221,19 +227,142 @@ impl JavaManager {
 
     /// Gets the required Java major version for the given Minecraft version.
     async fn get_required_java_version(&self, version: &str) -> Result<u32, LauncherError> {
-        let version_parts: Vec<&str> = version.split('.').collect();
-        if version_parts.len() >= 2 {
-            let major: u32 = version_parts[0].parse().unwrap_or(1);
-            let minor: u32 = version_parts[1].parse().unwrap_or(0);
-
-            if major == 1 {
-                return match minor {
-                    v if v >= 20 => Ok(21),
-                    v if v >= 17 => Ok(17),
-                    _ => Ok(8),
-                };
+        Ok(estimate_java_version(version))
+    }
+}
+
+/// Java major version a Minecraft version most likely needs, judged from its id alone.
+/// Snapshots and pre-releases count as the release they lead up to.
+pub(crate) fn estimate_java_version(version: &str) -> u32 {
+    match release_series(version) {
+        Some(series) if series >= (1, 20, 5) => 21,
+        Some(series) if series >= (1, 17, 0) => 17,
+        _ => 8, // Default to Java 8
+    }
+}
+
+/// Delete the runtime directories under `runtime_dir` that carry a [`MANAGED_RUNTIME_MARKER`] for
+/// `major_version`, along with the archives they were extracted from
+fn remove_managed_runtimes(runtime_dir: &Path, major_version: u32) -> Result<(), LauncherError> {
+    if !runtime_dir.exists() {
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(runtime_dir)? {
+        let path = entry?.path();
+        let Ok(content) = std::fs::read(path.join(MANAGED_RUNTIME_MARKER)) else {
+            continue;
+        };
+        let Ok(marker) = serde_json::from_slice::<ManagedRuntime>(&content) else {
+            continue;
+        };
+        if marker.major_version != major_version {
+            continue;
+        }
+
+        log::info!("Removing Java {} runtime at {}", major_version, path.display());
+        std::fs::remove_dir_all(&path)?;
+        // Only a plain file name, so a tampered marker can't point outside the runtime directory
+        let archive = Path::new(&marker.archive);
+        if archive.file_name() == Some(archive.as_os_str()) {
+            match std::fs::remove_file(runtime_dir.join(archive)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Extracts the downloaded archive.
+fn extract_archive(archive_path: &Path, extraction_path: &Path) -> Result<(), LauncherError> {
+    let file = std::fs::File::open(archive_path)?;
+    if archive_path.extension().is_some_and(|e| e == "gz") {
+        let decoder = GzDecoder::new(file);
+        let mut archive = Archive::new(decoder);
+        archive.unpack(extraction_path)?;
+    } else if archive_path.extension().is_some_and(|e| e == "zip") {
+        let mut archive = ZipArchive::new(file)?;
+        archive.extract(extraction_path)?;
+    }
+    Ok(())
+}
+
+/// Finds the Java executable within a given directory.
+fn find_java_executable(dir: &Path) -> Option<PathBuf> {
+    let executable_name = if cfg!(windows) { "java.exe" } else { "java" };
+    
+    // For Azul Zulu, we need to search recursively since the structure varies
+    find_java_executable_recursive(dir, executable_name)
+}
+
+/// Recursively searches for the Java executable in the directory tree
+fn find_java_executable_recursive(dir: &Path, executable_name: &str) -> Option<PathBuf> {
+    // First check direct bin directory
+    let direct_bin = dir.join("bin").join(executable_name);
+    if direct_bin.exists() {
+        return Some(direct_bin);
+    }
+    
+    // Check macOS structure
+    let macos_bin = dir.join("Contents").join("Home").join("bin").join(executable_name);
+    if macos_bin.exists() {
+        return Some(macos_bin);
+    }
+    
+    // Recursively search subdirectories (common with Azul Zulu extractions)
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if let Some(found) = find_java_executable_recursive(&path, executable_name) {
+                    return Some(found);
+                }
             }
         }
-        Ok(8) // Default to Java 8
+    }
+    
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn managed_runtime(runtime_dir: &Path, name: &str, major_version: u32) {
+        let dir = runtime_dir.join(name);
+        std::fs::create_dir_all(dir.join("bin")).unwrap();
+        let marker = ManagedRuntime { major_version, archive: format!("{}.zip", name) };
+        std::fs::write(dir.join(MANAGED_RUNTIME_MARKER), serde_json::to_vec(&marker).unwrap()).unwrap();
+        std::fs::write(runtime_dir.join(format!("{}.zip", name)), b"archive").unwrap();
+    }
+
+    #[test]
+    fn repair_removes_only_runtimes_it_installed() {
+        let runtime_dir = tempfile::tempdir().unwrap();
+        let runtime_dir = runtime_dir.path();
+        managed_runtime(runtime_dir, "zulu17.44.53-ca-jre17.0.8.1-linux_x64", 17);
+        managed_runtime(runtime_dir, "zulu8.72.0.17-ca-jre8.0.382-linux_x64", 8);
+        std::fs::create_dir_all(runtime_dir.join("java-runtime-gamma").join("bin")).unwrap();
+        std::fs::create_dir_all(runtime_dir.join("jdk17.0.2")).unwrap();
+        std::fs::write(runtime_dir.join("jdk17.0.2.zip"), b"user archive").unwrap();
+
+        remove_managed_runtimes(runtime_dir, 17).unwrap();
+
+        let mut left: Vec<String> = std::fs::read_dir(runtime_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            [
+                "java-runtime-gamma",
+                "jdk17.0.2",
+                "jdk17.0.2.zip",
+                "zulu8.72.0.17-ca-jre8.0.382-linux_x64",
+                "zulu8.72.0.17-ca-jre8.0.382-linux_x64.zip",
+            ]
+        );
     }
 }
@@ -1,18 +1,82 @@
 //! Main launcher implementation
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use futures::StreamExt;
 use crate::{
-    auth::{Authenticator, AuthenticatorConfig, Account},
-    config::{LauncherConfig, LaunchConfig},
-    downloader::Downloader,
+    auth::{self, Authenticator, AuthenticatorConfig, Account, AccountRefresh, AccountRefreshStatus, AccountStore, AccountType, AuthOutcome, TokenStatus, ACCOUNT_STORE_FILE},
+    config::{AssetDownloadMode, ConfigExport, DownloadVerification, JvmArgValidation, LaunchWarning, LauncherConfig, LaunchConfig, ModLoaderConfig, CONFIG_EXPORT_VERSION, DEFAULT_DATA_HOSTS},
+    downloader::{Downloader, FileHash, ProgressTracker},
     error::{LauncherError, Result},
-    minecraft::{MinecraftProcess, ProcessStatus},
-    version::{VersionManager, VersionInfo},
-    utils::JavaFinder,
+    java::{estimate_java_version, JavaManager},
+    instance::{self, ClasspathCache, CleanupReport, InstanceDiskUsage, InstanceMetadata, ScreenshotInfo, VanillaImportReport, WorldBackup},
+    minecraft::{LogLine, MinecraftProcess, ProcessOptions, ProcessStatus},
+    mods::{self, ModInfo, ModIssue},
+    version::{maven_path, AssetIndexFile, LoggingClient, release_series, ModLoaderDownloadPlan, PlannedDownload, VersionManager, VersionInfo, VersionSource},
+    utils::{self, find_jvm_arg_conflicts, native_binary_architectures, normalize_arch, run_hook_command, JavaFinder, JavaInstallation},
 };
 
+/// Time allowed for a single asset object download before it is retried
+const ASSET_DOWNLOAD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+
+/// Maximum heap (MB) below which `validate_launch_config` warns about running out of memory
+const LOW_MEMORY_WARNING_MB: u32 = 1024;
+
+/// File in a natives directory recording which native jars were extracted into it
+const NATIVES_MARKER_FILE: &str = ".extracted";
+
+/// Log4j property whose `true` value disables message lookups, the Log4Shell mitigation
+const LOG4J_NO_LOOKUPS_PROPERTY: &str = "-Dlog4j2.formatMsgNoLookups";
+
+/// JVM property pointing log4j at a logging config, used to swap in Mojang's patched one
+const LOG4J_CONFIGURATION_PROPERTY: &str = "-Dlog4j.configurationFile=";
+
+/// JVM flag LWJGL 3 needs on macOS
+const START_ON_FIRST_THREAD: &str = "-XstartOnFirstThread";
+
+/// JVM properties set by `LaunchConfig::disable_telemetry`; `.invalid` never resolves
+const TELEMETRY_DISABLE_PROPERTIES: &[(&str, &str)] = &[
+    ("-Dminecraft.api.telemetry.host", "https://telemetry.invalid"),
+];
+
+/// Release time (Unix seconds) of 16w32a, the first version with lowercase language codes
+const LOWERCASE_LANGUAGE_CODES_SINCE: i64 = 1_470_787_200;
+
+/// Rewrites the final argument list before the game is spawned, see [`Launcher::set_arg_transformer`]
+pub type ArgTransformer = Arc<dyn Fn(Vec<String>) -> Vec<String> + Send + Sync>;
+
+/// Command line for the game, split into its parts, see [`Launcher::build_launch_arguments`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchArguments {
+    /// JVM flags, in the order they are passed (without `-cp`)
+    pub jvm_args: Vec<String>,
+    /// Classpath entries: libraries first, the client jar last
+    pub classpath: Vec<PathBuf>,
+    /// Main class to run
+    pub main_class: String,
+    /// Arguments passed to the game's main class
+    pub game_args: Vec<String>,
+}
+
+impl LaunchArguments {
+    /// Classpath joined with the platform's separator
+    pub fn classpath_string(&self) -> String {
+        join_classpath(&self.classpath)
+    }
+
+    /// Flat argument list: JVM flags, `-cp <classpath>`, main class, game arguments
+    pub fn into_vec(self) -> Vec<String> {
+        let classpath = self.classpath_string();
+        let mut args = self.jvm_args;
+        args.push("-cp".to_string());
+        args.push(classpath);
+        args.push(self.main_class);
+        args.extend(self.game_args);
+        args
+    }
+}
+
 /// Main launcher instance
 pub struct Launcher {
     config: LauncherConfig,
@@ -20,6 +84,9 @@ pub struct Launcher {
     downloader: Downloader,
     java_finder: JavaFinder,
     active_processes: Arc<Mutex<Vec<MinecraftProcess>>>,
+    authenticator: Option<Authenticator>,
+    arg_transformer: Option<ArgTransformer>,
+    accounts: AccountStore,
 }
 
 impl Launcher {
@@ -35,9 +102,13 @@ impl Launcher {
             .await
             .map_err(|e| LauncherError::file(format!("Failed to create cache directory: {}", e)))?;
 
-        let version_manager = VersionManager::new(cache_dir.clone())?;
-        let downloader = Downloader::new(config.concurrent_downloads, config.download_timeout)?;
-        let java_finder = JavaFinder::new();
+        let version_manager = VersionManager::new(cache_dir.clone())?.with_endpoints(config.endpoints.clone());
+        let downloader = Downloader::new(config.concurrent_downloads, config.download_timeout)?
+            .with_endpoints(config.endpoints.clone())
+            .with_multipart_downloads(config.multipart_downloads)
+            .with_verification(config.download_verification);
+        let java_finder = JavaFinder::new().with_managed_dir(config.minecraft_dir.join("runtime"));
+        let accounts = AccountStore::load(config.minecraft_dir.join(ACCOUNT_STORE_FILE)).await?;
 
         Ok(Self {
             config,
@@ -45,14 +116,54 @@ impl Launcher {
             downloader,
             java_finder,
             active_processes: Arc::new(Mutex::new(Vec::new())),
+            authenticator: None,
+            arg_transformer: None,
+            accounts,
         })
     }
 
+    /// Wait for up to `max_wait` until Mojang's servers can be reached, see
+    /// [`utils::wait_for_connectivity`]. Call before signing in or fetching versions at startup.
+    pub async fn wait_for_connectivity(&self, max_wait: std::time::Duration) -> Result<()> {
+        let url = self.config.endpoints.rewrite(crate::version::VERSION_MANIFEST_V2_URL);
+        utils::wait_for_connectivity(&url, max_wait).await
+    }
+
     /// Create an authenticator with the given configuration
     pub fn create_authenticator(&self, auth_config: AuthenticatorConfig) -> Result<Authenticator> {
         Authenticator::new(auth_config)
     }
 
+    /// Set the authenticator used to validate the session before each launch
+    pub fn set_authenticator(&mut self, authenticator: Authenticator) {
+        self.authenticator = Some(authenticator);
+    }
+
+    /// Install a hook that receives the fully built argument list (everything passed to the
+    /// Java executable) right before the game is spawned and returns the list to use instead.
+    /// Useful for agents, profilers or debug flags the structured config doesn't cover.
+    ///
+    /// It runs after every flag the launcher adds, so the list it sees is exactly what is passed,
+    /// ordered as: JVM arguments (launcher `jvm_args`, the instance's `jvm_args`, then the
+    /// launch's additional JVM arguments, `-Xms`/`-Xmx`, the version's own `arguments.jvm`, any
+    /// natives properties the version didn't set, the telemetry properties, the Log4Shell
+    /// mitigation and `-XstartOnFirstThread` on macOS), then `-cp` followed by the classpath, then
+    /// the main class, then the game arguments. Inserting new JVM flags just before `-cp` is
+    /// therefore always safe. Flags removed by the transformer are not added back.
+    pub fn set_arg_transformer(&mut self, transformer: ArgTransformer) {
+        self.arg_transformer = Some(transformer);
+    }
+
+    /// Remove the hook installed by `set_arg_transformer`
+    pub fn clear_arg_transformer(&mut self) {
+        self.arg_transformer = None;
+    }
+
+    /// Report file and byte counts of game file downloads and `verify_instance` to `tracker`
+    pub fn set_progress_tracker(&mut self, tracker: ProgressTracker) {
+        self.downloader = self.downloader.clone().with_progress_tracker(tracker);
+    }
+
     /// Authenticate using the built-in authenticator
     pub async fn authenticate(&mut self, auth_config: AuthenticatorConfig) -> Result<Account> {
         let authenticator = self.create_authenticator(auth_config)?;
@@ -83,6 +194,106 @@ impl Launcher {
         authenticator.refresh_account(account).await
     }
 
+    /// Refresh an existing account, reporting whether its username changed
+    pub async fn refresh_account_outcome(&mut self, auth_config: AuthenticatorConfig, account: &Account) -> Result<AuthOutcome> {
+        let authenticator = self.create_authenticator(auth_config)?;
+        authenticator.refresh_account_outcome(account).await
+    }
+
+    /// Refresh every stored Microsoft account whose token has expired or expires soon, all at
+    /// once, and save the ones that succeed
+    ///
+    /// Accounts without a refresh token are reported as needing a new sign-in without contacting
+    /// Microsoft, as are accounts whose refresh token gets rejected. Only saving the account
+    /// store can fail the whole call; per-account failures are in the returned list.
+    pub async fn refresh_all_accounts(&mut self, auth_config: AuthenticatorConfig) -> Result<Vec<AccountRefresh>> {
+        let authenticator = self.create_authenticator(auth_config)?;
+        let authenticator = &authenticator;
+
+        let results = futures::future::join_all(self.accounts.accounts().iter().map(|account| async move {
+            let (status, refreshed) = if account.account_type != AccountType::Microsoft
+                || account.token_status() == TokenStatus::Valid
+            {
+                (AccountRefreshStatus::UpToDate, None)
+            } else if account.refresh_token.is_empty() {
+                (AccountRefreshStatus::ReauthRequired, None)
+            } else {
+                match authenticator.refresh_account_outcome(account).await {
+                    Ok(outcome) => (AccountRefreshStatus::Refreshed { name_changed: outcome.name_changed }, Some(outcome.account)),
+                    Err(e) if e.is_reauth_required() => {
+                        log::warn!("Account {} needs to sign in again: {}", account.name, e);
+                        (AccountRefreshStatus::ReauthRequired, None)
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to refresh account {}: {}", account.name, e);
+                        (AccountRefreshStatus::Failed { error: e.to_string() }, None)
+                    }
+                }
+            };
+            let refresh = AccountRefresh {
+                uuid: account.uuid.clone(),
+                name: account.name.clone(),
+                status,
+            };
+            (refresh, refreshed)
+        }))
+        .await;
+
+        let mut report = Vec::with_capacity(results.len());
+        let mut changed = false;
+        for (refresh, refreshed) in results {
+            if let Some(account) = refreshed {
+                self.accounts.upsert(account);
+                changed = true;
+            }
+            report.push(refresh);
+        }
+        if changed {
+            self.accounts.save().await?;
+        }
+        Ok(report)
+    }
+
+    /// Sign an account out locally: remove it from the account store and save the store, so its
+    /// refresh token is no longer on disk. Tokens are not revoked on Microsoft's side.
+    pub async fn logout(&mut self, auth_config: AuthenticatorConfig, account: &Account) -> Result<()> {
+        let authenticator = self.create_authenticator(auth_config)?;
+        authenticator.logout(account).await?;
+        self.remove_account(&account.uuid).await?;
+        Ok(())
+    }
+
+    /// Stored accounts, see [`AccountStore`]
+    pub fn accounts(&self) -> &AccountStore {
+        &self.accounts
+    }
+
+    /// Store an account (replacing one with the same UUID) and save the account store
+    pub async fn save_account(&mut self, account: Account) -> Result<()> {
+        self.accounts.upsert(account);
+        self.accounts.save().await
+    }
+
+    /// Remove a stored account and save the account store
+    pub async fn remove_account(&mut self, uuid: &str) -> Result<Option<Account>> {
+        let removed = self.accounts.remove(uuid);
+        if removed.is_some() {
+            self.accounts.save().await?;
+        }
+        Ok(removed)
+    }
+
+    /// The active account, or the only stored account if none was picked
+    pub fn active_account(&self) -> Option<&Account> {
+        self.accounts.active_account()
+    }
+
+    /// Make a stored account the active one and save the account store
+    pub async fn set_active_account(&mut self, uuid: &str) -> Result<()> {
+        self.accounts.set_active_account(uuid)?;
+        self.accounts.save().await
+    }
+
     /// Create a launch configuration for a specific version
     pub async fn create_launch_config(&mut self, version: &str, account: &Account) -> Result<LaunchConfig> {
         // Validate that the version exists
@@ -97,47 +308,294 @@ impl Launcher {
         Ok(launch_config)
     }
 
+    /// Create a launch configuration for a specific version using the active account
+    pub async fn create_launch_config_default(&mut self, version: &str) -> Result<LaunchConfig> {
+        let account = self
+            .active_account()
+            .cloned()
+            .ok_or_else(|| LauncherError::config("No active account; sign in or call set_active_account first"))?;
+        self.create_launch_config(version, &account).await
+    }
+
+    /// Download (if needed) and launch a version with default settings in one call
+    ///
+    /// `version` is a version ID, `"latest"` for the latest release or `"latest-snapshot"`
+    /// for the latest snapshot. The game runs in the default `instance-<version>` instance.
+    pub async fn quick_launch(&mut self, account: &Account, version: &str) -> Result<MinecraftProcess> {
+        let version_id = match version {
+            "latest" => self.version_manager.get_latest_release().await?.id,
+            "latest-snapshot" => self.version_manager.get_latest_snapshot().await?.id,
+            id => id.to_string(),
+        };
+        let launch_config = self.create_launch_config(&version_id, account).await?;
+        self.launch(launch_config).await
+    }
+
+    /// Check a launch configuration without downloading or starting anything: the version must
+    /// resolve, memory settings must be sane, Java must be available and the instance must not be
+    /// running. Hard problems are returned together as an error; softer ones as warnings.
+    pub async fn validate_launch_config(&self, launch_config: &LaunchConfig) -> Result<Vec<LaunchWarning>> {
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+
+        let instance_dir = launch_config
+            .custom_game_dir
+            .clone()
+            .unwrap_or_else(|| self.get_instance_dir(&launch_config.instance_name));
+        let version_source = launch_config
+            .version_source
+            .clone()
+            .unwrap_or_else(|| VersionSource::ManifestId(launch_config.version.clone()));
+        let version_info = match self.version_manager.resolve_version_info(&version_source).await {
+            Ok(version_info) => Some(version_info),
+            Err(e) if e.is_network_unreachable() || e.is_timeout() => {
+                match self.load_offline_version(launch_config, &instance_dir, e).await {
+                    Ok(version_info) => {
+                        warnings.push(LaunchWarning::Offline { version: version_info.id.clone() });
+                        Some(version_info)
+                    }
+                    Err(e) => {
+                        errors.push(format!("Version {} is not available offline: {}", launch_config.version, e));
+                        None
+                    }
+                }
+            }
+            Err(e) => {
+                errors.push(format!("Version {} could not be resolved: {}", launch_config.version, e));
+                None
+            }
+        };
+
+        if self.config.memory_max == 0 {
+            errors.push("Maximum memory must be greater than 0 MB".to_string());
+        } else if self.config.memory_min > self.config.memory_max {
+            errors.push(format!(
+                "Minimum memory ({} MB) is larger than maximum memory ({} MB)",
+                self.config.memory_min, self.config.memory_max
+            ));
+        } else if self.config.memory_max < LOW_MEMORY_WARNING_MB {
+            warnings.push(LaunchWarning::LowMemory { memory_max_mb: self.config.memory_max });
+        }
+
+        if self.config.jvm_arg_validation != JvmArgValidation::Off {
+            let mut jvm_args = self.config.jvm_args.clone();
+            jvm_args.extend(launch_config.additional_jvm_args.iter().cloned());
+            jvm_args.push(format!("-Xms{}m", self.config.memory_min));
+            jvm_args.push(format!("-Xmx{}m", self.config.memory_max));
+            for message in find_jvm_arg_conflicts(&jvm_args) {
+                if self.config.jvm_arg_validation == JvmArgValidation::Error {
+                    errors.push(message);
+                } else {
+                    warnings.push(LaunchWarning::JvmArgConflict { message });
+                }
+            }
+        }
+
+        if let Some(mod_loader) = launch_config.mod_loader.as_ref().filter(|loader| loader.enabled) {
+            if !mod_loader.loader_type.supports_version(&launch_config.version) {
+                warnings.push(LaunchWarning::UnsupportedModLoader {
+                    loader: mod_loader.loader_type.clone(),
+                    minecraft_version: launch_config.version.clone(),
+                });
+            }
+        }
+
+        if let Some(version_info) = &version_info {
+            if let Err(e) = self.get_java_path(version_info).await {
+                errors.push(format!("No usable Java installation: {}", e));
+            }
+
+            if version_info.lacks_security_patches() {
+                warnings.push(LaunchWarning::NonCompliantVersion {
+                    version: version_info.id.clone(),
+                    log4j_mitigated: self.log4shell_mitigated(version_info, launch_config),
+                });
+            }
+        }
+
+        if let Err(e) = self.ensure_instance_not_running(&instance_dir).await {
+            errors.push(e.to_string());
+        }
+
+        if !errors.is_empty() {
+            return Err(LauncherError::config(errors.join("; ")));
+        }
+        Ok(warnings)
+    }
+
     /// Launch Minecraft with the given configuration
     pub async fn launch(&mut self, launch_config: LaunchConfig) -> Result<MinecraftProcess> {
         log::info!("Starting Minecraft launch for version {}", launch_config.version);
 
-        // 1. Get version information
-        let version_entry = self.version_manager.find_version(&launch_config.version).await?;
-        let version_info = self.version_manager.fetch_version_info(&version_entry).await?;
+        // 1. Get version information, falling back to the cached version JSON when offline
+        let instance_dir = launch_config
+            .custom_game_dir
+            .clone()
+            .unwrap_or_else(|| self.get_instance_dir(&launch_config.instance_name));
+        let version_source = launch_config
+            .version_source
+            .clone()
+            .unwrap_or_else(|| VersionSource::ManifestId(launch_config.version.clone()));
+        let (version_info, offline) = match self.version_manager.resolve_version_info(&version_source).await {
+            Ok(version_info) => (version_info, false),
+            Err(e) if e.is_network_unreachable() || e.is_timeout() => {
+                let version_info = self.load_offline_version(&launch_config, &instance_dir, e).await?;
+                log::info!("Network unavailable, launching {} offline from cached files", version_info.id);
+                (version_info, true)
+            }
+            Err(e) => return Err(e),
+        };
 
-        // 2. Set up directories
-        let instance_dir = self.get_instance_dir(&launch_config.instance_name);
+        // 2. Make sure the session is still accepted before downloading anything or running hooks
+        if let Some(authenticator) = self.authenticator.as_ref().filter(|_| !offline) {
+            match authenticator.validate_session(&launch_config.account).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    return Err(LauncherError::auth(format!(
+                        "The session for {} is no longer valid; refresh the account or sign in again",
+                        launch_config.account.name
+                    )));
+                }
+                Err(e) => log::warn!("Could not validate session, launching anyway: {}", e),
+            }
+        }
+
+        // 3. Set up directories, refusing to start a second game on the same worlds
+        self.ensure_instance_not_running(&instance_dir).await?;
         self.setup_instance_directories(&instance_dir).await?;
+        self.link_custom_dirs(&launch_config, &instance_dir).await?;
+        if !offline {
+            self.cache_version_info(&version_info, &instance_dir).await?;
+        }
+
+        if let Some(seed_dir) = launch_config.config_seed_dir.clone() {
+            let config_dir = instance_dir.join("config");
+            let copied = tokio::task::spawn_blocking(move || instance::seed_directory(&seed_dir, &config_dir))
+                .await
+                .map_err(|e| LauncherError::other(format!("Config seeding failed: {}", e)))??;
+            log::info!("Seeded {} config files", copied);
+        }
 
-        // 3. Download required files
-        if launch_config.download_libraries {
-            self.download_libraries(&version_info, &instance_dir).await?;
+        if let Some(language) = launch_config.language.clone() {
+            let lowercase_codes = version_info.release_time.timestamp() >= LOWERCASE_LANGUAGE_CODES_SINCE;
+            let dir = instance_dir.clone();
+            let seeded = tokio::task::spawn_blocking(move || instance::seed_language(&dir, &language, lowercase_codes))
+                .await
+                .map_err(|e| LauncherError::other(format!("Language seeding failed: {}", e)))??;
+            if seeded {
+                log::info!("Set the game language in options.txt");
+            }
+        }
+
+        if launch_config.window_config.persist_to_options {
+            let window = &launch_config.window_config;
+            let entries = [
+                ("overrideWidth", window.width.to_string()),
+                ("overrideHeight", window.height.to_string()),
+                ("fullscreen", window.fullscreen.to_string()),
+            ];
+            let dir = instance_dir.clone();
+            let added = tokio::task::spawn_blocking(move || instance::seed_options(&dir, &entries))
+                .await
+                .map_err(|e| LauncherError::other(format!("Seeding options.txt failed: {}", e)))??;
+            if added > 0 {
+                log::info!("Wrote {} window settings to options.txt", added);
+            }
+        }
+
+        let mut metadata = self.read_instance_metadata(&instance_dir).await?
+            .unwrap_or_else(|| InstanceMetadata::new(launch_config.instance_name.clone(), launch_config.version.clone()));
+        metadata.version = launch_config.version.clone();
+        metadata.mod_loader = launch_config.mod_loader.clone();
+        metadata.last_played = Some(chrono::Utc::now());
+        let fingerprint = classpath_fingerprint(&version_info.id, &launch_config, &instance_dir)?;
+        let classpath = match &metadata.classpath_cache {
+            Some(cache) if cache.fingerprint == fingerprint => {
+                log::debug!("Reusing cached classpath ({} entries)", cache.entries.len());
+                cache.entries.clone()
+            }
+            _ => {
+                let entries = self.build_classpath(&version_info, &instance_dir.join("libraries"), &instance_dir);
+                metadata.classpath_cache = Some(ClasspathCache { fingerprint, entries: entries.clone() });
+                entries
+            }
+        };
+        let instance_jvm_args = metadata.jvm_args.clone();
+        self.write_instance_metadata(&instance_dir, metadata).await?;
+
+        // 4. Download required files
+        if launch_config.download_libraries && !offline {
+            let force_native_extraction = launch_config.force_native_extraction
+                || self.config.download_verification == DownloadVerification::Strict;
+            self.download_libraries(&version_info, &instance_dir, force_native_extraction).await?;
+        }
+
+        if !launch_config.disable_log4shell_mitigation && !offline {
+            self.download_log4j_config(&version_info, &instance_dir).await?;
         }
         
-        if launch_config.download_assets {
-            self.download_assets(&version_info, &instance_dir).await?;
+        if launch_config.download_assets && !offline {
+            let assets_dir = self.assets_dir(&launch_config, &instance_dir);
+            self.download_assets(&version_info, &assets_dir, &instance_dir, &launch_config.asset_download_mode).await?;
         }
 
-        // 4. Setup mod loader if specified
+        // 5. Setup mod loader if specified
         if let Some(mod_loader_config) = &launch_config.mod_loader {
             self.setup_mod_loader(mod_loader_config, &version_info, &instance_dir).await?;
         }
 
-        // 5. Find Java executable
+        // 6. Find Java executable
         let java_path = self.get_java_path(&version_info).await?;
+        let natives_dir = instance_dir.join("versions").join(&version_info.id).join("natives");
+        self.verify_native_architecture(&natives_dir, &java_path).await?;
+
+        // 7. Build launch arguments
+        let mut launch_args = self
+            .build_launch_arguments_with_classpath(&launch_config, &version_info, &instance_dir, classpath, &instance_jvm_args)?
+            .into_vec();
+        // Last, so the transformer sees every flag the launcher added
+        if let Some(transformer) = &self.arg_transformer {
+            launch_args = transformer(launch_args);
+        }
+
+        // 8. Run the pre-launch hook
+        if let Some(command) = &launch_config.pre_launch_command {
+            let env = [
+                ("INST_NAME", launch_config.instance_name.clone()),
+                ("INST_DIR", instance_dir.to_string_lossy().to_string()),
+                ("INST_JAVA", java_path.to_string_lossy().to_string()),
+            ];
+            let exit_code = run_hook_command(command, &instance_dir, &env).await?;
+            if exit_code != 0 {
+                return Err(LauncherError::launch(format!(
+                    "Pre-launch command exited with code {}",
+                    exit_code
+                )));
+            }
+        }
 
-        // 6. Build launch arguments
-        let launch_args = self.build_launch_arguments(&launch_config, &version_info, &instance_dir, &java_path)?;
+        // 9. Start the process
+        let mut process_options = ProcessOptions {
+            post_exit_command: launch_config.post_exit_command.clone(),
+            detached: launch_config.detached,
+            window_ready_patterns: launch_config.window_ready_patterns.clone(),
+            ..Default::default()
+        };
+        if let Some(log_capture) = &launch_config.log_capture {
+            let log_name = format!("launch-{}.log", chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"));
+            process_options.log_file = Some(instance_dir.join("logs").join("launcher").join(log_name));
+            process_options.log_capture = log_capture.clone();
+        }
 
-        // 7. Start the process
-        let process = MinecraftProcess::new(
+        let process = MinecraftProcess::new_with_options(
             java_path,
             launch_args,
             instance_dir,
             launch_config.account.clone(),
+            process_options,
         ).await?;
 
-        // 8. Track the process
+        // 10. Track the process
         {
             let mut processes = self.active_processes.lock().await;
             processes.push(process.clone());
@@ -147,21 +605,143 @@ impl Launcher {
         Ok(process)
     }
 
+    /// Download the official ProGuard mappings for a version's client jar into `dir`.
+    /// Only versions from 1.14.4 onwards publish mappings.
+    pub async fn download_client_mappings(&mut self, version: &str, dir: &Path) -> Result<PathBuf> {
+        let version_entry = self.version_manager.find_version(version).await?;
+        let version_info = self.version_manager.fetch_version_info(&version_entry).await?;
+        let mappings = version_info.downloads.client_mappings.as_ref().ok_or_else(|| {
+            LauncherError::config(format!("Version {} has no client mappings", version))
+        })?;
+
+        let destination = dir.join(format!("{}-client.txt", version_info.id));
+        self.downloader
+            .download_file(&mappings.url, &destination, Some(&mappings.sha1))
+            .await?;
+        Ok(destination)
+    }
+
+    /// List the files installing `mod_loader` for `minecraft_version` into an instance would download,
+    /// with sizes (from the loader metadata, or asked from the server). Fails if the loader version
+    /// doesn't exist or one of its files can't be found, so it doubles as a resolvability check.
+    /// Only Fabric, Quilt and Legacy Fabric can be planned; Forge and NeoForge return an error.
+    pub async fn plan_mod_loader_download(
+        &self,
+        instance_name: &str,
+        minecraft_version: &str,
+        mod_loader: &ModLoaderConfig,
+    ) -> Result<ModLoaderDownloadPlan> {
+        let loader = self
+            .version_manager
+            .get_mod_loader_versions(mod_loader.loader_type.clone(), minecraft_version)
+            .await?
+            .into_iter()
+            .find(|loader| loader.version == mod_loader.version)
+            .ok_or_else(|| {
+                LauncherError::config(format!(
+                    "{} {} is not available for Minecraft {}",
+                    mod_loader.loader_type, mod_loader.version, minecraft_version
+                ))
+            })?;
+
+        let libraries_dir = self.get_instance_dir(instance_name).join("libraries");
+        let mut candidates = Vec::new();
+        if let (Some(coordinate), Some(url)) = (&loader.build_info.maven, &loader.build_info.url) {
+            candidates.push((coordinate.clone(), url.clone(), None, None));
+        }
+        for library in &loader.build_info.libraries {
+            if let Some(rules) = &library.rules {
+                if !self.evaluate_rules(rules) {
+                    continue;
+                }
+            }
+            match library.downloads.as_ref().and_then(|downloads| downloads.artifact.as_ref()) {
+                Some(artifact) => candidates.push((
+                    library.name.clone(),
+                    artifact.url.clone(),
+                    Some(artifact.size),
+                    Some(artifact.sha1.clone()),
+                )),
+                None => match library.maven_url() {
+                    Some(url) => candidates.push((library.name.clone(), url, None, library.sha1.clone())),
+                    None => log::warn!("Library {} has no download information, skipping", library.name),
+                },
+            }
+        }
+
+        let files = futures::stream::iter(candidates)
+            .map(|(name, url, size, sha1)| {
+                let path = self.get_library_path(&name, &libraries_dir);
+                async move {
+                    let present = match &sha1 {
+                        Some(sha1) => self.downloader.file_matches_sha1(&path, sha1).await,
+                        None => path.exists(),
+                    };
+                    let size = match size {
+                        Some(size) => Some(size),
+                        None => self.downloader.remote_size(&url).await?,
+                    };
+                    Ok(PlannedDownload { name, url, path, size, sha1, present })
+                }
+            })
+            .buffered(self.config.concurrent_downloads.max(1))
+            .collect::<Vec<Result<PlannedDownload>>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ModLoaderDownloadPlan { loader, files })
+    }
+
+    /// Pick up a game that was launched detached in this instance (possibly by an earlier run of
+    /// the launcher) and track it like one launched now. Returns `None` if none is running.
+    pub async fn reattach_instance(&self, instance_name: &str) -> Result<Option<MinecraftProcess>> {
+        let instance_dir = self.get_instance_dir(instance_name);
+        let Some(process) = MinecraftProcess::reattach(instance_dir).await? else {
+            return Ok(None);
+        };
+
+        self.active_processes.lock().await.push(process.clone());
+        Ok(Some(process))
+    }
+
     /// Get all active Minecraft processes
     pub async fn get_active_processes(&self) -> Vec<MinecraftProcess> {
         let mut processes = self.active_processes.lock().await;
         
         // Remove finished processes
         processes.retain(|process| {
-            match process.get_status() {
-                ProcessStatus::Running => true,
-                _ => false,
-            }
+            matches!(process.get_status(), ProcessStatus::Running)
         });
 
         processes.clone()
     }
 
+    /// Check whether a game started by this launcher is still running in the instance
+    pub async fn is_instance_running(&self, instance_name: &str) -> bool {
+        let instance_dir = self.get_instance_dir(instance_name);
+        let processes = self.active_processes.lock().await.clone();
+        for process in processes {
+            if process.get_working_dir() == &instance_dir
+                && matches!(process.get_status_async().await, ProcessStatus::Starting | ProcessStatus::Running)
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Merged stdout/stderr and `latest.log` feed of a running game, by process ID
+    pub async fn get_log_stream(&self, process_id: u32) -> Result<tokio::sync::mpsc::Receiver<LogLine>> {
+        let processes = self.active_processes.lock().await.clone();
+        for process in processes {
+            if process.get_pid().await.ok() == Some(process_id) {
+                return Ok(process.log_stream());
+            }
+        }
+        Err(LauncherError::process(format!("No running Minecraft process with PID {}", process_id)))
+    }
+
     /// Kill all active Minecraft processes
     pub async fn kill_all(&mut self) -> Result<usize> {
         let processes = {
@@ -181,6 +761,36 @@ impl Launcher {
         Ok(killed)
     }
 
+    /// List the Java installations found on this system
+    pub async fn list_java_installations(&self) -> Vec<JavaInstallation> {
+        self.java_finder.list_java_installations().await
+    }
+
+    /// Java major version a Minecraft version needs, from its version JSON (`javaVersion`).
+    /// Falls back to an estimate from the version id when the JSON has no requirement or
+    /// can't be fetched because the network is down.
+    pub async fn required_java_version(&self, version_id: &str) -> Result<i32> {
+        let source = VersionSource::ManifestId(version_id.to_string());
+        match self.version_manager.resolve_version_info(&source).await {
+            Ok(version_info) => Ok(version_info
+                .java_version
+                .map(|java| java.major_version)
+                .unwrap_or_else(|| estimate_java_version(version_id) as i32)),
+            Err(e) if e.is_network_unreachable() || e.is_timeout() => {
+                log::warn!("Could not fetch {}, estimating its Java version: {}", version_id, e);
+                Ok(estimate_java_version(version_id) as i32)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Delete and re-download the managed Java runtime required by a Minecraft version
+    pub async fn repair_java(&self, version: &str) -> Result<PathBuf> {
+        JavaManager::from_downloader(self.config.minecraft_dir.join("runtime"), self.downloader.clone())
+            .repair_runtime(version)
+            .await
+    }
+
     /// Get launcher configuration
     pub fn get_config(&self) -> &LauncherConfig {
         &self.config
@@ -191,22 +801,765 @@ impl Launcher {
         self.config = config;
     }
 
+    /// Write the launcher configuration and the metadata of every instance to a single file.
+    /// Paths under the launcher root are stored relative to it so the file can be imported elsewhere.
+    pub async fn export_config(&self, path: &Path) -> Result<()> {
+        let instances_dir = self.config.minecraft_dir.join("instances");
+        let instances = tokio::task::spawn_blocking(move || instance::list_metadata(&instances_dir))
+            .await
+            .map_err(|e| LauncherError::other(format!("Listing instances failed: {}", e)))??;
+
+        let export = ConfigExport {
+            format_version: CONFIG_EXPORT_VERSION,
+            exported_at: chrono::Utc::now(),
+            config: self.config.to_portable(),
+            instances,
+        };
+        let content = serde_json::to_string_pretty(&export)?;
+        tokio::fs::write(path, content)
+            .await
+            .map_err(|e| LauncherError::file(format!("Failed to write config export: {}", e)))
+    }
+
+    /// Load a file written by `export_config`, replacing the launcher configuration (keeping the
+    /// current launcher root) and registering instances that don't exist here yet.
+    /// Returns the names of the instances that were added.
+    pub async fn import_config(&mut self, path: &Path) -> Result<Vec<String>> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| LauncherError::file(format!("Failed to read config export: {}", e)))?;
+        let export: ConfigExport = serde_json::from_str(&content)
+            .map_err(|e| LauncherError::json(format!("Failed to parse config export: {}", e)))?;
+        if export.format_version > CONFIG_EXPORT_VERSION {
+            return Err(LauncherError::config(format!(
+                "Config export format {} is newer than the supported format {}",
+                export.format_version, CONFIG_EXPORT_VERSION
+            )));
+        }
+
+        // Names become directory names; one like `../x` would write outside the instances root
+        for metadata in &export.instances {
+            instance::validate_entry_name(&metadata.name).map_err(|_| {
+                LauncherError::validation(format!("Config export has an invalid instance name: {:?}", metadata.name))
+            })?;
+        }
+
+        let mut added = Vec::new();
+        for metadata in export.instances {
+            let instance_dir = self.get_instance_dir(&metadata.name);
+            if self.read_instance_metadata(&instance_dir).await?.is_some() {
+                continue;
+            }
+            tokio::fs::create_dir_all(&instance_dir)
+                .await
+                .map_err(|e| LauncherError::file(format!("Failed to create instance directory: {}", e)))?;
+            added.push(metadata.name.clone());
+            self.write_instance_metadata(&instance_dir, metadata).await?;
+        }
+
+        self.config = export.config.resolve_portable(self.config.minecraft_dir.clone());
+        Ok(added)
+    }
+
+    /// Import profiles, installed versions and accounts from the official launcher's `.minecraft`
+    ///
+    /// Each profile becomes an instance with its JVM arguments and version folder copied over,
+    /// plus the saves and `options.txt` of its game directory if it sets one; installed versions no profile uses become instances named after the
+    /// version. Existing instances are left alone. Accounts are only imported while their access
+    /// token is readable and unexpired, since the official launcher keeps refresh tokens
+    /// encrypted; the others are reported as needing to sign in.
+    pub async fn import_vanilla_profiles(&mut self, mc_dir: &Path) -> Result<VanillaImportReport> {
+        if !mc_dir.is_dir() {
+            return Err(LauncherError::file(format!("Minecraft directory not found: {}", mc_dir.display())));
+        }
+
+        let dir = mc_dir.to_path_buf();
+        let (profiles, installed, accounts) = tokio::task::spawn_blocking(move || {
+            Ok::<_, LauncherError>((
+                instance::read_vanilla_profiles(&dir)?,
+                instance::installed_vanilla_versions(&dir)?,
+                auth::read_vanilla_accounts(&dir)?,
+            ))
+        })
+        .await
+        .map_err(|e| LauncherError::other(format!("Reading the official launcher files failed: {}", e)))??;
+
+        let mut report = VanillaImportReport::default();
+        let mut imports = Vec::new();
+        for profile in profiles {
+            let version = match profile.version.as_str() {
+                "latest-release" => self.version_manager.get_latest_release().await.map(|version| version.id),
+                "latest-snapshot" => self.version_manager.get_latest_snapshot().await.map(|version| version.id),
+                id => Ok(id.to_string()),
+            };
+            match version {
+                Ok(version) => imports.push((profile.name, version, profile.game_dir, profile.jvm_args)),
+                Err(e) => {
+                    log::warn!("Skipping profile {}: could not resolve {}: {}", profile.name, profile.version, e);
+                    report.skipped.push(profile.name);
+                }
+            }
+        }
+        for version in installed {
+            if !imports.iter().any(|(_, used, _, _)| *used == version) {
+                imports.push((version.clone(), version, None, Vec::new()));
+            }
+        }
+
+        for (name, version, game_dir, jvm_args) in imports {
+            let name = instance::sanitize_instance_name(&name);
+            let instance_dir = self.get_instance_dir(&name);
+            if instance_dir.exists() {
+                report.skipped.push(name);
+                continue;
+            }
+
+            let (source, dir, version_id) = (mc_dir.to_path_buf(), instance_dir.clone(), version.clone());
+            tokio::task::spawn_blocking(move || {
+                std::fs::create_dir_all(&dir)
+                    .map_err(|e| LauncherError::file(format!("Failed to create instance directory: {}", e)))?;
+                instance::copy_vanilla_game_data(&source, &version_id, game_dir.as_deref(), &dir)
+            })
+            .await
+            .map_err(|e| LauncherError::other(format!("Copying game data failed: {}", e)))??;
+
+            // Launching offline expects the resolved profile, not one that still inherits
+            let vanilla_json = mc_dir.join("versions").join(&version).join(format!("{}.json", version));
+            if vanilla_json.is_file() {
+                match self.version_manager.resolve_version_info(&VersionSource::LocalFile(vanilla_json)).await {
+                    Ok(version_info) => self.cache_version_info(&version_info, &instance_dir).await?,
+                    Err(e) => log::warn!("Could not resolve version {} for instance {}, it is fetched on first launch: {}", version, name, e),
+                }
+            }
+
+            let mut metadata = InstanceMetadata::new(name.clone(), version);
+            metadata.jvm_args = jvm_args;
+            self.write_instance_metadata(&instance_dir, metadata).await?;
+            report.instances.push(name);
+        }
+
+        for account in accounts.usable {
+            report.accounts.push(account.name.clone());
+            self.accounts.upsert(account);
+        }
+        if !report.accounts.is_empty() {
+            self.accounts.save().await?;
+        }
+        report.accounts_needing_sign_in = accounts.needing_sign_in;
+
+        log::info!(
+            "Imported {} instances and {} accounts from {}",
+            report.instances.len(),
+            report.accounts.len(),
+            mc_dir.display()
+        );
+        Ok(report)
+    }
+
+    /// Zip a world from an instance's `saves/` into a timestamped archive under `backups/`
+    pub async fn backup_world(&self, instance_name: &str, world_name: &str) -> Result<PathBuf> {
+        let instance_dir = self.get_instance_dir(instance_name);
+        let world_name = world_name.to_string();
+        tokio::task::spawn_blocking(move || {
+            instance::backup_world(&instance_dir.join("saves"), &instance_dir.join("backups"), &world_name)
+        })
+        .await
+        .map_err(|e| LauncherError::other(format!("Backup task failed: {}", e)))?
+    }
+
+    /// Restore a world backup into an instance, replacing the current copy of that world.
+    /// Returns the restored world name.
+    pub async fn restore_world(&self, instance_name: &str, backup_path: &Path) -> Result<String> {
+        let saves_dir = self.get_instance_dir(instance_name).join("saves");
+        let backup_path = backup_path.to_path_buf();
+        tokio::task::spawn_blocking(move || instance::restore_world(&saves_dir, &backup_path))
+            .await
+            .map_err(|e| LauncherError::other(format!("Restore task failed: {}", e)))?
+    }
+
+    /// List the world backups of an instance, newest first
+    pub async fn list_backups(&self, instance_name: &str) -> Result<Vec<WorldBackup>> {
+        let backups_dir = self.get_instance_dir(instance_name).join("backups");
+        tokio::task::spawn_blocking(move || instance::list_backups(&backups_dir))
+            .await
+            .map_err(|e| LauncherError::other(format!("Listing backups failed: {}", e)))?
+    }
+
+    /// List the screenshots of an instance, newest first
+    pub async fn list_screenshots(&self, instance_name: &str) -> Result<Vec<ScreenshotInfo>> {
+        let screenshots_dir = self.get_instance_dir(instance_name).join("screenshots");
+        tokio::task::spawn_blocking(move || instance::list_screenshots(&screenshots_dir))
+            .await
+            .map_err(|e| LauncherError::other(format!("Listing screenshots failed: {}", e)))?
+    }
+
+    /// Read a screenshot's image bytes by file name
+    pub async fn read_screenshot(&self, instance_name: &str, file_name: &str) -> Result<Vec<u8>> {
+        let screenshots_dir = self.get_instance_dir(instance_name).join("screenshots");
+        let file_name = file_name.to_string();
+        tokio::task::spawn_blocking(move || instance::read_screenshot(&screenshots_dir, &file_name))
+            .await
+            .map_err(|e| LauncherError::other(format!("Reading screenshot failed: {}", e)))?
+    }
+
+    /// Get the stored metadata of an instance
+    pub async fn get_instance_metadata(&self, instance_name: &str) -> Result<Option<InstanceMetadata>> {
+        let instance_dir = self.get_instance_dir(instance_name);
+        self.read_instance_metadata(&instance_dir).await
+    }
+
+    /// Whether an instance's files are all on disk: client jar, libraries, natives, the asset
+    /// index and its objects. With `verify_hashes` every file is also checked against its SHA1,
+    /// which reads all of them.
+    ///
+    /// Returns `false` for instances that were never launched. Assets are looked for in the
+    /// instance's own `assets` directory.
+    pub async fn is_installed(&self, instance_name: &str, verify_hashes: bool) -> Result<bool> {
+        let instance_dir = self.get_instance_dir(instance_name);
+        let Some(metadata) = self.read_instance_metadata(&instance_dir).await? else {
+            return Ok(false);
+        };
+        let version_path = self.cached_version_path(&instance_dir, &metadata.version);
+        if !version_path.exists() {
+            return Ok(false);
+        }
+
+        let version_info = self.version_manager.load_version_info_from_file(&version_path).await?;
+        let assets_dir = instance_dir.join("assets");
+        let natives_arch = self.natives_arch(&version_info).await;
+        let required = self.required_game_files(&version_info, &instance_dir, &assets_dir, &AssetDownloadMode::All, natives_arch)?;
+
+        let has_natives = version_info.libraries.iter().any(|library| {
+            let classifiers = library.downloads.as_ref().and_then(|downloads| downloads.classifiers.as_ref());
+            classifiers.is_some_and(|classifiers| classifiers.keys().any(|classifier| self.is_native_for_current_os(classifier)))
+        });
+        let natives_dir = instance_dir.join("versions").join(&version_info.id).join("natives");
+        if has_natives && !natives_dir.is_dir() {
+            return Ok(false);
+        }
+
+        if verify_hashes {
+            let files = required.into_iter().map(|(path, sha1)| (path, sha1.map(FileHash::Sha1))).collect();
+            return Ok(self.downloader.verify_files(files).await.is_empty());
+        }
+        for (path, _) in &required {
+            if !path.exists() {
+                log::debug!("Instance {} is missing {}", instance_name, path.display());
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Hash every file an instance needs (client jar, libraries, natives, asset index and
+    /// objects) and return the ones that are missing or corrupt
+    ///
+    /// Files are checked in parallel, as many at a time as the launcher downloads; each checked
+    /// file is reported as done on the tracker set with `set_progress_tracker`.
+    pub async fn verify_instance(&self, instance_name: &str) -> Result<Vec<PathBuf>> {
+        let instance_dir = self.get_instance_dir(instance_name);
+        let metadata = self
+            .read_instance_metadata(&instance_dir)
+            .await?
+            .ok_or_else(|| LauncherError::config(format!("Instance {} has never been launched", instance_name)))?;
+        let version_path = self.cached_version_path(&instance_dir, &metadata.version);
+        let version_info = self.version_manager.load_version_info_from_file(&version_path).await?;
+
+        let assets_dir = instance_dir.join("assets");
+        let natives_arch = self.natives_arch(&version_info).await;
+        let files: Vec<_> = self
+            .required_game_files(&version_info, &instance_dir, &assets_dir, &AssetDownloadMode::All, natives_arch)?
+            .into_iter()
+            .map(|(path, sha1)| (path, sha1.map(FileHash::Sha1)))
+            .collect();
+        log::info!("Verifying {} files of instance {}", files.len(), instance_name);
+        let broken = self.downloader.verify_files(files).await;
+        if !broken.is_empty() {
+            log::warn!("Instance {} has {} missing or corrupt files", instance_name, broken.len());
+        }
+        Ok(broken)
+    }
+
+    /// Set the JVM arguments used for every launch of an instance, replacing earlier ones
+    pub async fn set_instance_jvm_args(&self, instance_name: &str, jvm_args: Vec<String>) -> Result<()> {
+        let instance_dir = self.get_instance_dir(instance_name);
+        let mut metadata = self
+            .read_instance_metadata(&instance_dir)
+            .await?
+            .ok_or_else(|| LauncherError::config(format!("Instance not found: {}", instance_name)))?;
+        metadata.jvm_args = jvm_args;
+        self.write_instance_metadata(&instance_dir, metadata).await
+    }
+
+    /// List the mods installed in an instance, with metadata read from each jar
+    pub async fn list_mods(&self, instance_name: &str) -> Result<Vec<ModInfo>> {
+        let mods_dir = self.get_instance_dir(instance_name).join("mods");
+        tokio::task::spawn_blocking(move || mods::list_mods(&mods_dir))
+            .await
+            .map_err(|e| LauncherError::other(format!("Listing mods failed: {}", e)))?
+    }
+
+    /// Check an instance's mods for duplicates, missing dependencies and loader mismatches
+    pub async fn check_mods(&self, instance_name: &str) -> Result<Vec<ModIssue>> {
+        let mods = self.list_mods(instance_name).await?;
+        let instance_loader = self
+            .get_instance_metadata(instance_name)
+            .await?
+            .and_then(|metadata| metadata.mod_loader)
+            .filter(|loader| loader.enabled)
+            .map(|loader| loader.loader_type);
+
+        Ok(mods::detect_mod_issues(&mods, instance_loader.as_ref()))
+    }
+
+    /// Total size of an instance on disk, in bytes, including files it shares with other
+    /// instances (see [`InstanceDiskUsage::unique`] for what deleting it frees)
+    pub async fn instance_size(&self, instance_name: &str) -> Result<u64> {
+        Ok(self.instance_disk_usage(instance_name).await?.total)
+    }
+
+    /// Size of an instance on disk, broken down by libraries, assets, mods, saves and so on
+    pub async fn instance_disk_usage(&self, instance_name: &str) -> Result<InstanceDiskUsage> {
+        let instance_dir = self.get_instance_dir(instance_name);
+        tokio::task::spawn_blocking(move || instance::disk_usage(&instance_dir))
+            .await
+            .map_err(|e| LauncherError::other(format!("Measuring instance size failed: {}", e)))?
+    }
+
+    /// Remove `.tmp` files left in an instance by cancelled or crashed downloads.
+    /// Don't call this while the instance is downloading.
+    pub async fn clean_temp_files(&self, instance_name: &str) -> Result<CleanupReport> {
+        let instance_dir = self.get_instance_dir(instance_name);
+        tokio::task::spawn_blocking(move || instance::clean_instance_temp_files(&instance_dir))
+            .await
+            .map_err(|e| LauncherError::other(format!("Cleaning temporary files failed: {}", e)))?
+    }
+
+    /// Remove `.tmp` and `.partN` files left by interrupted downloads from every instance, the
+    /// cache and the Java runtime directory. Cached manifests and other complete files are kept.
+    /// Don't call this while downloads are running.
+    pub async fn clean_all_temp_files(&self) -> Result<CleanupReport> {
+        let minecraft_dir = self.config.minecraft_dir.clone();
+        tokio::task::spawn_blocking(move || -> Result<CleanupReport> {
+            let mut report = instance::clean_temp_files_in(&minecraft_dir.join("cache"), true)?;
+            report += instance::clean_temp_files_in(&minecraft_dir.join("runtime"), false)?;
+
+            let instances_dir = minecraft_dir.join("instances");
+            if instances_dir.is_dir() {
+                for entry in std::fs::read_dir(&instances_dir)? {
+                    let path = entry?.path();
+                    if path.is_dir() {
+                        report += instance::clean_instance_temp_files(&path)?;
+                    }
+                }
+            }
+            Ok(report)
+        })
+        .await
+        .map_err(|e| LauncherError::other(format!("Cleaning temporary files failed: {}", e)))?
+    }
+
+    /// Delete downloads that no instance uses any more: libraries, asset objects and indexes,
+    /// and `versions/` directories left over from versions an instance has moved away from.
+    ///
+    /// An instance's own `libraries/` and `assets/` are pruned against its cached version JSON.
+    /// Directories several instances share, `shared_assets_dirs` (see
+    /// `LaunchConfig::with_assets_dir`) and instance download directories that are symlinks, only
+    /// lose files no instance references. Instances that are running or have no cached version
+    /// JSON are skipped, and while there are any, shared directories are left alone. With
+    /// `dry_run` nothing is deleted and the report says what would be freed.
+    pub async fn prune_unused_downloads(&self, shared_assets_dirs: &[PathBuf], dry_run: bool) -> Result<CleanupReport> {
+        let instances_dir = self.config.minecraft_dir.join("instances");
+        let instances = tokio::task::spawn_blocking(move || instance::list_metadata(&instances_dir))
+            .await
+            .map_err(|e| LauncherError::other(format!("Listing instances failed: {}", e)))??;
+
+        let mut instances_to_prune = Vec::new();
+        let mut all_referenced = instance::ReferencedDownloads::empty();
+        let mut every_instance_known = true;
+        for metadata in instances {
+            let instance_dir = self.get_instance_dir(&metadata.name);
+            if self.is_instance_running(&metadata.name).await {
+                log::info!("Instance {} is running, not pruning it", metadata.name);
+                every_instance_known = false;
+                continue;
+            }
+            let version_path = self.cached_version_path(&instance_dir, &metadata.version);
+            if !version_path.exists() {
+                log::info!("No cached version JSON for instance {}, not pruning it", metadata.name);
+                every_instance_known = false;
+                continue;
+            }
+
+            let version_info = self.version_manager.load_version_info_from_file(&version_path).await?;
+            let mut assets_dirs = vec![instance_dir.join("assets")];
+            assets_dirs.extend(shared_assets_dirs.iter().cloned());
+            let index_id = version_info.asset_index.id.clone();
+            let assets = tokio::task::spawn_blocking(move || referenced_assets(&assets_dirs, &index_id))
+                .await
+                .map_err(|e| LauncherError::other(format!("Reading asset index failed: {}", e)))?;
+
+            let referenced = instance::ReferencedDownloads {
+                version_ids: vec![version_info.id.clone(), metadata.version.clone()],
+                libraries: self.referenced_libraries(&version_info),
+                assets,
+            };
+            all_referenced.merge(&referenced);
+            instances_to_prune.push((metadata.name, instance_dir, referenced));
+        }
+
+        let shared_assets_dirs = shared_assets_dirs.to_vec();
+        let instances_dir = self.config.minecraft_dir.join("instances");
+        tokio::task::spawn_blocking(move || {
+            // Instance download directories that are symlinks point into a shared store
+            let mut shared_libraries = std::collections::BTreeSet::new();
+            let mut shared_assets: std::collections::BTreeSet<PathBuf> =
+                shared_assets_dirs.iter().filter_map(|dir| std::fs::canonicalize(dir).ok()).collect();
+            for entry in std::fs::read_dir(&instances_dir).into_iter().flatten().filter_map(|entry| entry.ok()) {
+                for (name, shared) in [("libraries", &mut shared_libraries), ("assets", &mut shared_assets)] {
+                    let dir = entry.path().join(name);
+                    if dir.is_symlink() {
+                        shared.extend(std::fs::canonicalize(&dir).ok());
+                    }
+                }
+            }
+            let shared: std::collections::HashSet<PathBuf> =
+                shared_libraries.iter().chain(&shared_assets).cloned().collect();
+
+            let mut report = CleanupReport::default();
+            for (name, instance_dir, referenced) in &instances_to_prune {
+                let pruned = instance::prune_downloads(instance_dir, referenced, &shared, dry_run)?;
+                log::info!(
+                    "{} {} files ({} bytes) in instance {}",
+                    if dry_run { "Would remove" } else { "Removed" },
+                    pruned.files_removed,
+                    pruned.bytes_removed,
+                    name
+                );
+                report += pruned;
+            }
+
+            if !every_instance_known {
+                log::info!("Not every instance could be checked, leaving shared download directories alone");
+            } else if !shared.is_empty() {
+                let libraries: Vec<PathBuf> = shared_libraries.into_iter().collect();
+                let assets: Vec<PathBuf> = shared_assets.into_iter().collect();
+                let pruned = instance::prune_shared_downloads(&libraries, &assets, &all_referenced, dry_run)?;
+                log::info!(
+                    "{} {} files ({} bytes) in shared download directories",
+                    if dry_run { "Would remove" } else { "Removed" },
+                    pruned.files_removed,
+                    pruned.bytes_removed
+                );
+                report += pruned;
+            }
+            Ok(report)
+        })
+        .await
+        .map_err(|e| LauncherError::other(format!("Pruning downloads failed: {}", e)))?
+    }
+
+    /// Library jars `version_info` uses on any platform, relative to a libraries directory
+    fn referenced_libraries(&self, version_info: &VersionInfo) -> std::collections::HashSet<PathBuf> {
+        let mut libraries = std::collections::HashSet::new();
+        for library in &version_info.libraries {
+            libraries.insert(self.get_library_path(&library.name, Path::new("")));
+            let classifiers = library.downloads.as_ref().and_then(|downloads| downloads.classifiers.as_ref());
+            for classifier in classifiers.into_iter().flat_map(|classifiers| classifiers.keys()) {
+                libraries.insert(self.get_native_path(&library.name, classifier, Path::new("")));
+            }
+        }
+        libraries
+    }
+
+    /// Move an existing instance to another Minecraft version, keeping saves, config and mods.
+    ///
+    /// Downloads the new version's files and updates the instance metadata. Returns warnings
+    /// about content that may not work on the new version (mod loader, installed mods).
+    pub async fn update_instance_version(&mut self, instance_name: &str, new_version: &str) -> Result<Vec<String>> {
+        let instance_dir = self.get_instance_dir(instance_name);
+        if !instance_dir.is_dir() {
+            return Err(LauncherError::config(format!("Instance not found: {}", instance_name)));
+        }
+
+        let version_entry = self.version_manager.find_version(new_version).await?;
+        let version_info = self.version_manager.fetch_version_info(&version_entry).await?;
+
+        let mut metadata = self.read_instance_metadata(&instance_dir).await?
+            .unwrap_or_else(|| InstanceMetadata::new(instance_name.to_string(), new_version.to_string()));
+        let old_version = metadata.version.clone();
+
+        let mut warnings = Vec::new();
+        if let Some(mod_loader) = metadata.mod_loader.as_ref().filter(|loader| loader.enabled) {
+            warnings.push(format!(
+                "{} {} was installed for {}; make sure it supports {}",
+                mod_loader.loader_type, mod_loader.version, old_version, new_version
+            ));
+        }
+
+        let mod_count = std::fs::read_dir(instance_dir.join("mods"))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().extension().and_then(|e| e.to_str()) == Some("jar"))
+                    .count()
+            })
+            .unwrap_or(0);
+        if mod_count > 0 {
+            warnings.push(format!(
+                "{} mods in the mods folder may not be compatible with {}",
+                mod_count, new_version
+            ));
+        }
+
+        for warning in &warnings {
+            log::warn!("{}: {}", instance_name, warning);
+        }
+
+        self.setup_instance_directories(&instance_dir).await?;
+        self.download_libraries(&version_info, &instance_dir, false).await?;
+        self.download_assets(&version_info, &instance_dir.join("assets"), &instance_dir, &AssetDownloadMode::All).await?;
+
+        metadata.version = new_version.to_string();
+        self.write_instance_metadata(&instance_dir, metadata).await?;
+
+        log::info!("Updated instance {} from {} to {}", instance_name, old_version, new_version);
+        Ok(warnings)
+    }
+
     // Private helper methods
 
+    async fn read_instance_metadata(&self, instance_dir: &Path) -> Result<Option<InstanceMetadata>> {
+        let instance_dir = instance_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || instance::read_metadata(&instance_dir))
+            .await
+            .map_err(|e| LauncherError::other(format!("Reading instance metadata failed: {}", e)))?
+    }
+
+    async fn write_instance_metadata(&self, instance_dir: &Path, metadata: InstanceMetadata) -> Result<()> {
+        let instance_dir = instance_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || instance::write_metadata(&instance_dir, &metadata))
+            .await
+            .map_err(|e| LauncherError::other(format!("Writing instance metadata failed: {}", e)))?
+    }
+
+    /// Fail if a game is already running in `instance_dir`, either one started by this launcher
+    /// or one holding a world's `session.lock`; two games writing the same world corrupt it
+    async fn ensure_instance_not_running(&self, instance_dir: &Path) -> Result<()> {
+        let processes = self.active_processes.lock().await.clone();
+        for process in processes {
+            let running = matches!(process.get_status_async().await, ProcessStatus::Starting | ProcessStatus::Running);
+            if running && process.get_working_dir() == instance_dir {
+                return Err(LauncherError::launch(format!(
+                    "Instance {} is already running (PID {})",
+                    instance_dir.display(),
+                    process.get_pid().await.map(|pid| pid.to_string()).unwrap_or_else(|_| "unknown".to_string())
+                )));
+            }
+        }
+
+        let dir = instance_dir.to_path_buf();
+        let detached_pid = tokio::task::spawn_blocking(move || crate::minecraft::running_detached_pid(&dir))
+            .await
+            .map_err(|e| LauncherError::other(format!("Checking detached games failed: {}", e)))?;
+        if let Some(pid) = detached_pid {
+            return Err(LauncherError::launch(format!(
+                "Instance {} is already running detached (PID {})",
+                instance_dir.display(),
+                pid
+            )));
+        }
+
+        let saves_dir = instance_dir.join("saves");
+        let locked = tokio::task::spawn_blocking(move || instance::locked_worlds(&saves_dir))
+            .await
+            .map_err(|e| LauncherError::other(format!("Checking world locks failed: {}", e)))??;
+        if !locked.is_empty() {
+            return Err(LauncherError::launch(format!(
+                "Worlds {} are open in another running game; close it before launching this instance again",
+                locked.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Path of the version JSON cached next to the client jar
+    fn cached_version_path(&self, instance_dir: &Path, version_id: &str) -> PathBuf {
+        instance_dir.join("versions").join(version_id).join(format!("{}.json", version_id))
+    }
+
+    /// Keep a copy of the resolved version JSON so the instance can launch offline later
+    async fn cache_version_info(&self, version_info: &VersionInfo, instance_dir: &Path) -> Result<()> {
+        let path = self.cached_version_path(instance_dir, &version_info.id);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| LauncherError::file(format!("Failed to create versions directory: {}", e)))?;
+        }
+        let content = serde_json::to_vec_pretty(version_info)?;
+        // Left untouched when unchanged, as its modification time keys the classpath cache
+        if tokio::fs::read(&path).await.is_ok_and(|existing| existing == content) {
+            return Ok(());
+        }
+        tokio::fs::write(&path, content)
+            .await
+            .map_err(|e| LauncherError::file(format!("Failed to cache version JSON: {}", e)))
+    }
+
+    /// Load the cached version JSON for an offline launch, provided every file it needs is present.
+    /// Otherwise the original network error is returned.
+    async fn load_offline_version(
+        &self,
+        launch_config: &LaunchConfig,
+        instance_dir: &Path,
+        network_error: LauncherError,
+    ) -> Result<VersionInfo> {
+        let path = self.cached_version_path(instance_dir, &launch_config.version);
+        if !path.exists() {
+            log::warn!("No cached version JSON for {}, cannot launch offline", launch_config.version);
+            return Err(network_error);
+        }
+
+        let version_info = self.version_manager.load_version_info_from_file(&path).await?;
+        let assets_dir = self.assets_dir(launch_config, instance_dir);
+        let natives_arch = self.natives_arch(&version_info).await;
+        let missing = self.missing_game_files(&version_info, instance_dir, &assets_dir, &launch_config.asset_download_mode, natives_arch)?;
+        if let Some(first) = missing.first() {
+            log::warn!(
+                "Cannot launch {} offline: {} files are missing (e.g. {})",
+                version_info.id,
+                missing.len(),
+                first.display()
+            );
+            return Err(network_error);
+        }
+
+        Ok(version_info)
+    }
+
+    /// Files a version needs at launch that are not on disk: client jar, libraries, natives and assets
+    fn missing_game_files(
+        &self,
+        version_info: &VersionInfo,
+        instance_dir: &Path,
+        assets_dir: &Path,
+        asset_mode: &AssetDownloadMode,
+        natives_arch: Option<&'static str>,
+    ) -> Result<Vec<PathBuf>> {
+        Ok(self
+            .required_game_files(version_info, instance_dir, assets_dir, asset_mode, natives_arch)?
+            .into_iter()
+            .map(|(path, _)| path)
+            .filter(|path| !path.exists())
+            .collect())
+    }
+
+    /// Files a version needs at launch, with their SHA1 where the version declares one;
+    /// natives are those built for `natives_arch`
+    fn required_game_files(
+        &self,
+        version_info: &VersionInfo,
+        instance_dir: &Path,
+        assets_dir: &Path,
+        asset_mode: &AssetDownloadMode,
+        natives_arch: Option<&'static str>,
+    ) -> Result<Vec<(PathBuf, Option<String>)>> {
+        let libraries_dir = instance_dir.join("libraries");
+        let client_jar = instance_dir
+            .join("versions")
+            .join(&version_info.id)
+            .join(format!("{}.jar", version_info.id));
+        let mut required = vec![(client_jar, Some(version_info.downloads.client.sha1.clone()))];
+
+        for library in &version_info.libraries {
+            if library.rules.as_ref().is_some_and(|rules| !self.evaluate_rules(rules)) {
+                continue;
+            }
+
+            match &library.downloads {
+                Some(downloads) => {
+                    if let Some(artifact) = &downloads.artifact {
+                        required.push((self.get_library_path(&library.name, &libraries_dir), Some(artifact.sha1.clone())));
+                    }
+                    for (classifier, download) in downloads.classifiers.iter().flat_map(|classifiers| self.select_native_classifiers(classifiers, natives_arch)) {
+                        let path = self.get_native_path(&library.name, classifier, &libraries_dir);
+                        required.push((path, Some(download.sha1.clone())));
+                    }
+                }
+                None if library.maven_url().is_some() => {
+                    required.push((self.get_library_path(&library.name, &libraries_dir), library.sha1.clone()));
+                }
+                None => {}
+            }
+        }
+
+        let asset_index_path = assets_dir
+            .join("indexes")
+            .join(format!("{}.json", version_info.asset_index.id));
+        if asset_index_path.exists() {
+            let asset_index = read_asset_index(&asset_index_path)?;
+            for (asset_name, object) in &asset_index.objects {
+                if asset_mode.includes(asset_name) {
+                    required.push((assets_dir.join("objects").join(object.object_path()), Some(object.hash.clone())));
+                }
+            }
+        }
+        required.push((asset_index_path, Some(version_info.asset_index.sha1.clone())));
+
+        Ok(required)
+    }
+
+    /// Where a legacy asset index expects its files by name: the game directory's `resources/`
+    /// for `pre-1.6` (`map_to_resources`) and `virtual/<id>` for `legacy` (`virtual`)
+    fn legacy_assets_dir(
+        &self,
+        asset_index: &AssetIndexFile,
+        index_id: &str,
+        assets_dir: &Path,
+        instance_dir: &Path,
+    ) -> Option<PathBuf> {
+        if asset_index.map_to_resources {
+            Some(instance_dir.join("resources"))
+        } else if asset_index.is_virtual {
+            Some(assets_dir.join("virtual").join(index_id))
+        } else {
+            None
+        }
+    }
+
+    /// Value for `${game_assets}`: the by-name directory for legacy indexes, otherwise the assets root
+    fn game_assets_dir(&self, version_info: &VersionInfo, assets_dir: &Path, instance_dir: &Path) -> PathBuf {
+        let index_path = assets_dir.join("indexes").join(format!("{}.json", version_info.asset_index.id));
+        read_asset_index(&index_path)
+            .ok()
+            .and_then(|index| self.legacy_assets_dir(&index, &version_info.asset_index.id, assets_dir, instance_dir))
+            .unwrap_or_else(|| assets_dir.to_path_buf())
+    }
+
+    /// Directory holding `indexes/` and `objects/`; per-instance unless the launch config points elsewhere
+    fn assets_dir(&self, launch_config: &LaunchConfig, instance_dir: &Path) -> PathBuf {
+        launch_config
+            .assets_dir
+            .clone()
+            .unwrap_or_else(|| instance_dir.join("assets"))
+    }
+
     fn get_instance_dir(&self, instance_name: &str) -> PathBuf {
         self.config.minecraft_dir.join("instances").join(instance_name)
     }
 
-    async fn setup_instance_directories(&self, instance_dir: &PathBuf) -> Result<()> {
+    async fn setup_instance_directories(&self, instance_dir: &Path) -> Result<()> {
         let directories = [
-            instance_dir.clone(),
+            instance_dir.to_path_buf(),
             instance_dir.join("libraries"),
             instance_dir.join("assets"),
             instance_dir.join("versions"),
             instance_dir.join("mods"),
+            instance_dir.join("config"),
             instance_dir.join("resourcepacks"),
             instance_dir.join("shaderpacks"),
             instance_dir.join("saves"),
+            instance_dir.join("screenshots"),
             instance_dir.join("logs"),
             instance_dir.join("crash-reports"),
         ];
@@ -220,13 +1573,43 @@ impl Launcher {
         Ok(())
     }
 
-    async fn download_libraries(&mut self, version_info: &VersionInfo, instance_dir: &PathBuf) -> Result<()> {
+    /// Link the shared directories configured with `LaunchConfig::with_custom_dirs` into the instance.
+    /// A directory that can't be linked fails the launch with a config error naming it; the game
+    /// never silently runs on a private copy.
+    async fn link_custom_dirs(&self, launch_config: &LaunchConfig, instance_dir: &Path) -> Result<()> {
+        let links = [
+            (&launch_config.mods_dir, "mods"),
+            (&launch_config.saves_dir, "saves"),
+            (&launch_config.resource_packs_dir, "resourcepacks"),
+            (&launch_config.shader_packs_dir, "shaderpacks"),
+        ];
+
+        for (target, name) in links {
+            let Some(target) = target.clone() else {
+                continue;
+            };
+            let link = instance_dir.join(name);
+            let shared = target.clone();
+            let kind = tokio::task::spawn_blocking(move || instance::link_shared_dir(&target, &link))
+                .await
+                .map_err(|e| LauncherError::other(format!("Linking {} failed: {}", name, e)))?
+                .map_err(|e| {
+                    LauncherError::config(format!("Could not use {} as the shared {} directory: {}", shared.display(), name, e))
+                })?;
+            log::debug!("Linked {} as {:?}", name, kind);
+        }
+
+        Ok(())
+    }
+
+    async fn download_libraries(&mut self, version_info: &VersionInfo, instance_dir: &Path, force_native_extraction: bool) -> Result<()> {
         log::info!("Downloading libraries for version {}", version_info.id);
         
         let libraries_dir = instance_dir.join("libraries");
+        let natives_arch = self.natives_arch(version_info).await;
         let mut download_tasks = Vec::new();
 
-        // First, add the main Minecraft client JAR to download tasks
+        // First, the main Minecraft client JAR
         let client_download = &version_info.downloads.client;
         let versions_dir = instance_dir.join("versions").join(&version_info.id);
         let client_jar_path = versions_dir.join(format!("{}.jar", version_info.id));
@@ -239,8 +1622,19 @@ impl Launcher {
             }
         }
         
-        log::info!("Adding main client JAR to download: {}", client_jar_path.display());
-        download_tasks.push((client_download.url.clone(), client_jar_path, client_download.sha1.clone()));
+        // Old versions point at hosts that now 403/404, so the client jar gets its own fallback chain
+        log::info!("Downloading main client JAR to {}", client_jar_path.display());
+        let client_urls = self.client_jar_urls(&client_download.url, &version_info.id);
+        self.downloader
+            .download_file_with_fallbacks(&client_urls, &client_jar_path, Some(&client_download.sha1), Some(client_download.size))
+            .await
+            .map_err(|e| match e.http_status_code() {
+                Some(403 | 404) => LauncherError::download(format!(
+                    "Client jar for {} is unavailable from all known hosts: {}",
+                    version_info.id, e
+                )),
+                _ => e,
+            })?;
 
         for library in &version_info.libraries {
             // Check if library applies to current OS
@@ -255,38 +1649,90 @@ impl Launcher {
             if let Some(downloads) = &library.downloads {
                 if let Some(artifact) = &downloads.artifact {
                     let library_path = self.get_library_path(&library.name, &libraries_dir);
-                    download_tasks.push((artifact.url.clone(), library_path, artifact.sha1.clone()));
+                    download_tasks.push((artifact.url.clone(), library_path, Some(artifact.sha1.clone())));
                 }
 
                 // Handle native libraries
                 if let Some(classifiers) = &downloads.classifiers {
-                    for (classifier, download_info) in classifiers {
-                        if self.is_native_for_current_os(classifier) {
-                            let native_path = self.get_native_path(&library.name, classifier, &libraries_dir);
-                            download_tasks.push((download_info.url.clone(), native_path, download_info.sha1.clone()));
-                        }
+                    for (classifier, download_info) in self.select_native_classifiers(classifiers, natives_arch) {
+                        let native_path = self.get_native_path(&library.name, classifier, &libraries_dir);
+                        download_tasks.push((download_info.url.clone(), native_path, Some(download_info.sha1.clone())));
                     }
                 }
+            } else if let Some(url) = library.maven_url() {
+                // Coordinate-only library (mod loader profiles): resolve against its Maven repository
+                let library_path = self.get_library_path(&library.name, &libraries_dir);
+                download_tasks.push((url, library_path, library.sha1.clone()));
+            } else {
+                log::warn!("Library {} has no download information, skipping", library.name);
             }
         }
 
-        // Download all libraries and the main client JAR
+        // Download all libraries
         self.downloader.download_files(download_tasks).await?;
         
         // ARM compatibility is handled via JVM flags and Rosetta 2
         
         // Extract native libraries after downloading
-        self.extract_native_libraries(version_info, instance_dir).await?;
+        self.extract_native_libraries(version_info, instance_dir, natives_arch, force_native_extraction).await?;
         
         log::info!("Libraries and main client JAR downloaded successfully");
         Ok(())
     }
 
-    async fn download_assets(&mut self, version_info: &VersionInfo, instance_dir: &PathBuf) -> Result<()> {
-        log::info!("Downloading assets for version {}", version_info.id);
+    /// Fetch Mojang's patched logging config for versions whose log4j can't be fixed with
+    /// `-Dlog4j2.formatMsgNoLookups`
+    async fn download_log4j_config(&self, version_info: &VersionInfo, instance_dir: &Path) -> Result<()> {
+        if !version_info.is_log4shell_vulnerable() || version_info.supports_log4j_no_lookups() {
+            return Ok(());
+        }
+        let Some(logging) = version_info.client_logging() else {
+            return Ok(());
+        };
+
+        let path = log4j_config_path(instance_dir, logging);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| LauncherError::file(format!("Failed to create log config directory: {}", e)))?;
+        }
+        self.downloader.download_file(&logging.file.url, &path, Some(&logging.file.sha1)).await
+    }
+
+    /// Whether launching `version_info` leaves it protected against Log4Shell, taking the
+    /// automatic mitigation and any explicit `formatMsgNoLookups` value into account
+    fn log4shell_mitigated(&self, version_info: &VersionInfo, launch_config: &LaunchConfig) -> bool {
+        if !version_info.is_log4shell_vulnerable() {
+            return true;
+        }
+
+        let no_lookups_prefix = format!("{}=", LOG4J_NO_LOOKUPS_PROPERTY);
+        let lookups_disabled = match self
+            .config
+            .jvm_args
+            .iter()
+            .chain(&launch_config.additional_jvm_args)
+            .find(|arg| arg.starts_with(&no_lookups_prefix))
+        {
+            Some(arg) => arg.ends_with("=true"),
+            None => !launch_config.disable_log4shell_mitigation,
+        };
+        if version_info.supports_log4j_no_lookups() {
+            return lookups_disabled;
+        }
+        !launch_config.disable_log4shell_mitigation && version_info.client_logging().is_some()
+    }
+
+    async fn download_assets(
+        &mut self,
+        version_info: &VersionInfo,
+        assets_dir: &Path,
+        instance_dir: &Path,
+        mode: &AssetDownloadMode,
+    ) -> Result<()> {
+        log::info!("Downloading assets for version {} to {}", version_info.id, assets_dir.display());
         
         // Download asset index
-        let assets_dir = instance_dir.join("assets");
         let asset_index_path = assets_dir.join("indexes").join(format!("{}.json", version_info.asset_index.id));
         
         tokio::fs::create_dir_all(asset_index_path.parent().unwrap())
@@ -300,29 +1746,32 @@ impl Launcher {
         ).await?;
 
         // Parse asset index and download assets
-        let asset_index_content = tokio::fs::read_to_string(&asset_index_path)
+        let asset_index = tokio::task::spawn_blocking(move || read_asset_index(&asset_index_path))
             .await
-            .map_err(|e| LauncherError::file(format!("Failed to read asset index: {}", e)))?;
+            .map_err(|e| LauncherError::other(format!("Reading asset index failed: {}", e)))??;
 
-        let asset_index: serde_json::Value = serde_json::from_str(&asset_index_content)
-            .map_err(|e| LauncherError::json(format!("Failed to parse asset index: {}", e)))?;
-
-        if let Some(objects) = asset_index.get("objects").and_then(|o| o.as_object()) {
-            let mut download_tasks = Vec::new();
-            
-            for (_asset_name, asset_info) in objects {
-                if let (Some(hash), Some(_size)) = (
-                    asset_info.get("hash").and_then(|h| h.as_str()),
-                    asset_info.get("size").and_then(|s| s.as_u64()),
-                ) {
-                    let asset_url = format!("https://resources.download.minecraft.net/{}/{}", &hash[0..2], hash);
-                    let asset_path = assets_dir.join("objects").join(&hash[0..2]).join(hash);
-                    
-                    download_tasks.push((asset_url, asset_path, hash.to_string()));
-                }
+        let mut download_tasks = Vec::new();
+        for (asset_name, object) in &asset_index.objects {
+            if !mode.includes(asset_name) {
+                continue;
             }
+            let asset_path = assets_dir.join("objects").join(object.object_path());
+            download_tasks.push((object.url(), asset_path, Some(object.hash.clone())));
+        }
 
-            self.downloader.download_files(download_tasks).await?;
+        // Asset objects are tiny; a stuck connection is faster to retry than to wait out
+        self.downloader
+            .download_files_with_timeout(download_tasks, Some(ASSET_DOWNLOAD_TIMEOUT))
+            .await?;
+
+        // Versions before 1.7.3 look assets up by name rather than by hash
+        if let Some(target) = self.legacy_assets_dir(&asset_index, &version_info.asset_index.id, assets_dir, instance_dir) {
+            let (assets_dir, mode) = (assets_dir.to_path_buf(), mode.clone());
+            let copy_target = target.clone();
+            let copied = tokio::task::spawn_blocking(move || copy_assets_by_name(&asset_index, &assets_dir, &copy_target, &mode))
+                .await
+                .map_err(|e| LauncherError::other(format!("Copying legacy assets failed: {}", e)))??;
+            log::info!("Copied {} legacy assets to {}", copied, target.display());
         }
 
         log::info!("Assets downloaded successfully");
@@ -341,66 +1790,202 @@ impl Launcher {
     }
 
     async fn get_java_path(&self, version_info: &VersionInfo) -> Result<PathBuf> {
-        if let Some(java_path) = &self.config.java_path {
-            return Ok(java_path.clone());
-        }
-
         // Determine required Java version
         let required_java_version = version_info
             .java_version
             .as_ref()
             .map(|jv| jv.major_version)
-            .unwrap_or(8); // Default to Java 8 for older versions
+            // Snapshots and custom JSONs may lack `javaVersion`; guess from the version id
+            .unwrap_or_else(|| estimate_java_version(&version_info.id) as i32);
+
+        let pinned = u32::try_from(required_java_version).ok().and_then(|major| self.config.java_pins.get(&major));
+        if let Some(java_path) = pinned {
+            if !java_path.exists() {
+                return Err(LauncherError::java(format!(
+                    "Java {} is pinned to {}, which does not exist",
+                    required_java_version,
+                    java_path.display()
+                )));
+            }
+            return Ok(java_path.clone());
+        }
+
+        if let Some(java_path) = &self.config.java_path {
+            return Ok(java_path.clone());
+        }
 
         self.java_finder.find_java(required_java_version).await
     }
 
-    fn build_launch_arguments(
+    /// Build the game's command line for a resolved version without launching it
+    ///
+    /// `instance_dir` is the game directory. Nothing is downloaded or checked on disk apart
+    /// from reading the instance's JVM arguments from its metadata and whether the natives
+    /// directory exists; `arg_transformer` is not applied.
+    pub fn build_launch_arguments(
         &self,
         launch_config: &LaunchConfig,
         version_info: &VersionInfo,
-        instance_dir: &PathBuf,
-        _java_path: &PathBuf,
-    ) -> Result<Vec<String>> {
+        instance_dir: &Path,
+    ) -> Result<LaunchArguments> {
+        let classpath = self.build_classpath(version_info, &instance_dir.join("libraries"), instance_dir);
+        let instance_jvm_args = instance::read_metadata(instance_dir)?
+            .map(|metadata| metadata.jvm_args)
+            .unwrap_or_default();
+        self.build_launch_arguments_with_classpath(launch_config, version_info, instance_dir, classpath, &instance_jvm_args)
+    }
+
+    fn build_launch_arguments_with_classpath(
+        &self,
+        launch_config: &LaunchConfig,
+        version_info: &VersionInfo,
+        instance_dir: &Path,
+        classpath: Vec<PathBuf>,
+        instance_jvm_args: &[String],
+    ) -> Result<LaunchArguments> {
         let mut args = Vec::new();
 
-        // Add JVM arguments
+        // Add JVM arguments: launcher-wide, then the instance's own, then this launch's
         args.extend(self.config.jvm_args.clone());
+        args.extend(instance_jvm_args.iter().cloned());
         args.extend(launch_config.additional_jvm_args.clone());
 
         // Add memory settings
         args.push(format!("-Xms{}m", self.config.memory_min));
         args.push(format!("-Xmx{}m", self.config.memory_max));
 
-        // Add native library path arguments
+        self.validate_jvm_args(&args)?;
+
         let natives_dir = instance_dir.join("versions").join(&version_info.id).join("natives");
+        let natives_path = natives_dir.to_string_lossy().to_string();
+        let libraries_dir = instance_dir.join("libraries");
+
+        // Modern profiles template their JVM flags (including platform-specific ones such as
+        // -XstartOnFirstThread on macOS); -cp is left out here and always added last below
+        if let Some(arguments) = &version_info.arguments {
+            let assets_dir = self.assets_dir(launch_config, instance_dir);
+            let game_assets = self.game_assets_dir(version_info, &assets_dir, instance_dir);
+            let substitute = |arg: &str| {
+                self.substitute_argument_variables(arg, launch_config, version_info, instance_dir, &game_assets)
+                    .replace("${natives_directory}", &natives_path)
+                    .replace("${library_directory}", &libraries_dir.to_string_lossy())
+                    .replace("${classpath_separator}", if cfg!(windows) { ";" } else { ":" })
+                    .replace("${launcher_name}", &self.config.launcher_name)
+                    .replace("${launcher_version}", &self.config.launcher_version)
+                    .replace("${classpath}", &join_classpath(&classpath))
+            };
+
+            let mut jvm_args = Vec::new();
+            for arg in &arguments.jvm {
+                match arg {
+                    crate::version::ArgumentValue::String(s) => jvm_args.push(substitute(s)),
+                    crate::version::ArgumentValue::Conditional { rules, value } => {
+                        if self.evaluate_rules(rules) {
+                            jvm_args.extend(value.iter().map(|v| substitute(v)));
+                        }
+                    }
+                }
+            }
+            args.extend(strip_classpath_args(jvm_args));
+        }
+
+        // Natives properties for profiles that don't template them (legacy versions)
         if natives_dir.exists() {
-            let natives_path = natives_dir.to_string_lossy();
-            args.push(format!("-Djava.library.path={}", natives_path));
-            args.push(format!("-Djna.tmpdir={}", natives_path));
-            args.push(format!("-Dorg.lwjgl.system.SharedLibraryExtractPath={}", natives_path));
-            args.push(format!("-Dio.netty.native.workdir={}", natives_path));
+            let properties = [
+                "-Djava.library.path",
+                "-Djna.tmpdir",
+                "-Dorg.lwjgl.system.SharedLibraryExtractPath",
+                "-Dio.netty.native.workdir",
+            ];
+            for property in properties {
+                let prefix = format!("{}=", property);
+                if !args.iter().any(|arg| arg.starts_with(&prefix)) {
+                    args.push(format!("{}{}", prefix, natives_path));
+                }
+            }
         }
 
-        // ARM64 compatibility is handled by Rosetta 2 emulation at the process level
+        // User-supplied values win over the null host
+        if launch_config.disable_telemetry {
+            for (property, value) in TELEMETRY_DISABLE_PROPERTIES {
+                let prefix = format!("{}=", property);
+                if !args.iter().any(|arg| arg.starts_with(&prefix)) {
+                    args.push(format!("{}{}", prefix, value));
+                }
+            }
+        }
 
-        // Add library path
-        let libraries_dir = instance_dir.join("libraries");
-        let classpath = self.build_classpath(version_info, &libraries_dir, instance_dir)?;
-        args.push("-cp".to_string());
-        args.push(classpath);
+        if version_info.is_log4shell_vulnerable() && !launch_config.disable_log4shell_mitigation {
+            // A value the user set explicitly is kept
+            let no_lookups_prefix = format!("{}=", LOG4J_NO_LOOKUPS_PROPERTY);
+            if !args.iter().any(|arg| arg.starts_with(&no_lookups_prefix)) {
+                log::info!("Version {} ships a vulnerable log4j, disabling message lookups", version_info.id);
+                args.push(format!("{}true", no_lookups_prefix));
+            }
+
+            // log4j before 2.10 ignores the property, so the bundled logging config is replaced
+            // with Mojang's patched one instead
+            if !version_info.supports_log4j_no_lookups()
+                && !args.iter().any(|arg| arg.starts_with(LOG4J_CONFIGURATION_PROPERTY))
+            {
+                match version_info.client_logging().map(|logging| (logging, log4j_config_path(instance_dir, logging))) {
+                    Some((logging, path)) if path.is_file() => {
+                        args.push(logging.argument.replace("${path}", &path.to_string_lossy()));
+                    }
+                    _ => log::warn!(
+                        "No patched logging config available for {}, launching without Log4Shell mitigation",
+                        version_info.id
+                    ),
+                }
+            }
+        }
+
+        // LWJGL 3 can only create its window on the main thread on macOS; without this flag the
+        // game hangs at startup when the profile doesn't template it
+        if launch_config.start_on_first_thread == Some(false) {
+            args.retain(|arg| arg != START_ON_FIRST_THREAD);
+        } else if cfg!(target_os = "macos") && !args.iter().any(|arg| arg == START_ON_FIRST_THREAD) {
+            let needed = launch_config
+                .start_on_first_thread
+                .unwrap_or_else(|| uses_lwjgl3(version_info));
+            if needed {
+                args.push(START_ON_FIRST_THREAD.to_string());
+            }
+        }
 
-        // Add main class
-        args.push(version_info.main_class.clone());
+        // ARM64 compatibility is handled by Rosetta 2 emulation at the process level
 
-        // Add game arguments
+        let main_class = launch_config.main_class_override.as_ref().unwrap_or(&version_info.main_class);
         let game_args = self.build_game_arguments(launch_config, version_info, instance_dir)?;
-        args.extend(game_args);
 
-        Ok(args)
+        Ok(LaunchArguments {
+            jvm_args: args,
+            classpath,
+            main_class: main_class.clone(),
+            game_args,
+        })
+    }
+
+    /// Report conflicting JVM flags according to `LauncherConfig::jvm_arg_validation`
+    fn validate_jvm_args(&self, jvm_args: &[String]) -> Result<()> {
+        if self.config.jvm_arg_validation == JvmArgValidation::Off {
+            return Ok(());
+        }
+
+        let problems = find_jvm_arg_conflicts(jvm_args);
+        if problems.is_empty() {
+            return Ok(());
+        }
+        if self.config.jvm_arg_validation == JvmArgValidation::Error {
+            return Err(LauncherError::config(format!("Conflicting JVM arguments: {}", problems.join("; "))));
+        }
+        for problem in &problems {
+            log::warn!("JVM arguments: {}", problem);
+        }
+        Ok(())
     }
 
-    fn build_classpath(&self, version_info: &VersionInfo, libraries_dir: &PathBuf, instance_dir: &PathBuf) -> Result<String> {
+    fn build_classpath(&self, version_info: &VersionInfo, libraries_dir: &Path, instance_dir: &Path) -> Vec<PathBuf> {
         let mut classpath_entries = Vec::new();
 
         // Add libraries first
@@ -414,7 +1999,7 @@ impl Launcher {
             // All libraries work normally via Rosetta 2 emulation on ARM64
 
             let library_path = self.get_library_path(&library.name, libraries_dir);
-            classpath_entries.push(library_path.to_string_lossy().to_string());
+            classpath_entries.push(library_path);
         }
 
         // ARM compatibility is handled via JVM flags, not separate libraries
@@ -423,45 +2008,67 @@ impl Launcher {
         // The client jar should be in instance_dir/versions/{version_id}/{version_id}.jar
         let versions_dir = instance_dir.join("versions").join(&version_info.id);
         let client_jar = versions_dir.join(format!("{}.jar", version_info.id));
-        classpath_entries.push(client_jar.to_string_lossy().to_string());
-
-        log::info!("Built classpath with {} entries", classpath_entries.len());
+        log::info!("Built classpath with {} entries", classpath_entries.len() + 1);
         log::debug!("Client jar path: {}", client_jar.display());
-        
-        Ok(classpath_entries.join(if cfg!(windows) { ";" } else { ":" }))
+        classpath_entries.push(client_jar);
+
+        classpath_entries
     }
 
     fn build_game_arguments(
         &self,
         launch_config: &LaunchConfig,
         version_info: &VersionInfo,
-        instance_dir: &PathBuf,
+        instance_dir: &Path,
     ) -> Result<Vec<String>> {
-        let mut args = Vec::new();
+        // Without either format the game would start without even a username and fail obscurely
+        let has_legacy = version_info.minecraft_arguments.as_deref().is_some_and(|arguments| !arguments.trim().is_empty());
+        let has_modern = version_info.arguments.as_ref().is_some_and(|arguments| !arguments.game.is_empty());
+        if !has_legacy && !has_modern {
+            return Err(LauncherError::launch(format!("Version JSON for {} has no launch arguments", version_info.id)));
+        }
+
+        let assets_dir = self.assets_dir(launch_config, instance_dir);
+        let game_assets = self.game_assets_dir(version_info, &assets_dir, instance_dir);
+        let substitute = |arg: &str| {
+            self.substitute_argument_variables(arg, launch_config, version_info, instance_dir, &game_assets)
+        };
 
-        // Handle modern argument format
+        // Legacy format; merged profiles may carry it alongside the modern block
+        let legacy_args: Vec<String> = version_info
+            .minecraft_arguments
+            .iter()
+            .flat_map(|arguments| arguments.split_whitespace())
+            .map(&substitute)
+            .collect();
+
+        // Modern format
+        let mut modern_args = Vec::new();
         if let Some(arguments) = &version_info.arguments {
             for arg in &arguments.game {
                 match arg {
                     crate::version::ArgumentValue::String(s) => {
-                        args.push(self.substitute_argument_variables(s, launch_config, instance_dir));
+                        modern_args.push(substitute(s));
                     }
                     crate::version::ArgumentValue::Conditional { rules, value } => {
                         if self.evaluate_rules(rules) {
                             for v in value {
-                                args.push(self.substitute_argument_variables(v, launch_config, instance_dir));
+                                modern_args.push(substitute(v));
                             }
                         }
                     }
                 }
             }
-        } 
-        // Handle legacy argument format
-        else if let Some(minecraft_arguments) = &version_info.minecraft_arguments {
-            let legacy_args: Vec<&str> = minecraft_arguments.split_whitespace().collect();
-            for arg in legacy_args {
-                args.push(self.substitute_argument_variables(arg, launch_config, instance_dir));
-            }
+        }
+
+        let mut args = merge_game_arguments(legacy_args, modern_args);
+
+        // Hashed-layout versions need the index id next to the assets root, even if the profile omits it
+        let uses_assets_root = args.iter().any(|arg| arg == "--assetsDir");
+        let legacy_layout = game_assets != assets_dir;
+        if uses_assets_root && !legacy_layout && !args.iter().any(|arg| arg == "--assetIndex") {
+            args.push("--assetIndex".to_string());
+            args.push(version_info.asset_index.id.clone());
         }
 
         // Add additional game arguments
@@ -470,7 +2077,14 @@ impl Launcher {
         Ok(args)
     }
 
-    fn substitute_argument_variables(&self, arg: &str, launch_config: &LaunchConfig, instance_dir: &PathBuf) -> String {
+    fn substitute_argument_variables(
+        &self,
+        arg: &str,
+        launch_config: &LaunchConfig,
+        version_info: &VersionInfo,
+        instance_dir: &Path,
+        game_assets: &Path,
+    ) -> String {
         // Validate authentication data to prevent JSON parsing errors
         let safe_player_name = if launch_config.account.name.is_empty() {
             log::warn!("Empty player name detected, using placeholder");
@@ -493,22 +2107,23 @@ impl Launcher {
             launch_config.account.access_token.clone()
         };
         
-        let safe_user_type = if launch_config.account.account_type.is_empty() {
-            log::warn!("Empty user type detected, using 'msa' as default");
-            "msa".to_string()
-        } else {
-            launch_config.account.account_type.clone()
-        };
+        let user_type = launch_config.account.account_type.user_type();
+        let assets_dir = self.assets_dir(launch_config, instance_dir);
 
         arg.replace("${auth_player_name}", &safe_player_name)
             .replace("${version_name}", &launch_config.version)
             .replace("${game_directory}", &instance_dir.to_string_lossy())
-            .replace("${assets_root}", &instance_dir.join("assets").to_string_lossy())
-            .replace("${game_assets}", &instance_dir.join("assets").to_string_lossy())
+            .replace("${assets_root}", &assets_dir.to_string_lossy())
+            .replace("${assets_index_name}", &version_info.asset_index.id)
+            .replace("${game_assets}", &game_assets.to_string_lossy())
             .replace("${auth_uuid}", &safe_uuid)
             .replace("${auth_access_token}", &safe_access_token)
-            .replace("${user_type}", &safe_user_type)
-            .replace("${version_type}", "release")
+            .replace("${auth_xuid}", launch_config.account.xuid.as_deref().unwrap_or_default())
+            .replace("${clientid}", launch_config.account.client_id.as_deref().unwrap_or_default())
+            .replace("${user_type}", user_type)
+            .replace("${version_type}", if version_info.version_type.is_empty() { "release" } else { &version_info.version_type })
+            .replace("${launcher_name}", &self.config.launcher_name)
+            .replace("${launcher_version}", &self.config.launcher_version)
             .replace("${resolution_width}", &launch_config.window_config.width.to_string())
             .replace("${resolution_height}", &launch_config.window_config.height.to_string())
     }
@@ -551,30 +2166,52 @@ impl Launcher {
             }
         }
 
-        // TODO: Implement version and arch matching
+        if let Some(arch) = &os_rule.arch {
+            // Mojang uses "x86" for 32-bit JVMs
+            let current_arch = match std::env::consts::ARCH {
+                "x86" => "x86",
+                "aarch64" => "arm64",
+                other => other,
+            };
+            if arch != current_arch {
+                return false;
+            }
+        }
+
+        // TODO: Implement version matching
         true
     }
 
-    fn get_library_path(&self, library_name: &str, libraries_dir: &PathBuf) -> PathBuf {
-        // Parse Maven coordinate: group:artifact:version[:classifier]
-        let parts: Vec<&str> = library_name.split(':').collect();
-        if parts.len() >= 3 {
-            let group = parts[0].replace('.', "/");
-            let artifact = parts[1];
-            let version = parts[2];
-            let classifier = if parts.len() > 3 { format!("-{}", parts[3]) } else { String::new() };
-            
-            libraries_dir
-                .join(group)
-                .join(artifact)
-                .join(version)
-                .join(format!("{}-{}{}.jar", artifact, version, classifier))
-        } else {
-            libraries_dir.join(library_name)
+    /// Candidate URLs for a client jar: the declared one, its path on the other Mojang hosts,
+    /// and the legacy per-version location. Hosts the configured endpoints map to the same
+    /// mirror are only tried once.
+    fn client_jar_urls(&self, primary_url: &str, version_id: &str) -> Vec<String> {
+        let mut urls = vec![primary_url.to_string()];
+        if let Some(path) = DEFAULT_DATA_HOSTS.iter().find_map(|host| primary_url.strip_prefix(host)) {
+            for host in DEFAULT_DATA_HOSTS {
+                let url = format!("{}{}", host, path);
+                let rewritten = self.config.endpoints.rewrite(&url);
+                if !urls.iter().any(|known| self.config.endpoints.rewrite(known) == rewritten) {
+                    urls.push(url);
+                }
+            }
         }
+        urls.push(format!(
+            "https://s3.amazonaws.com/Minecraft.Download/versions/{0}/{0}.jar",
+            version_id
+        ));
+        urls
     }
 
-    fn get_native_path(&self, library_name: &str, classifier: &str, libraries_dir: &PathBuf) -> PathBuf {
+    fn get_library_path(&self, library_name: &str, libraries_dir: &Path) -> PathBuf {
+        // Parse Maven coordinate: group:artifact:version[:classifier][@extension]
+        match maven_path(library_name) {
+            Some(path) => libraries_dir.join(path),
+            None => libraries_dir.join(library_name),
+        }
+    }
+
+    fn get_native_path(&self, library_name: &str, classifier: &str, libraries_dir: &Path) -> PathBuf {
         let parts: Vec<&str> = library_name.split(':').collect();
         if parts.len() >= 3 {
             let group = parts[0].replace('.', "/");
@@ -601,18 +2238,76 @@ impl Launcher {
         }
     }
 
+    /// Architecture the game's natives are picked for: the configured preference, otherwise the
+    /// architecture of the Java runtime the version launches with (an x86_64 JRE runs under
+    /// Rosetta 2 on Apple Silicon), falling back to this machine's if Java can't be probed
+    async fn natives_arch(&self, version_info: &VersionInfo) -> Option<&'static str> {
+        if let Some(arch) = &self.config.preferred_natives_arch {
+            return normalize_arch(arch);
+        }
+        let java_arch = match self.get_java_path(version_info).await {
+            Ok(java_path) => self.java_finder.java_architecture(&java_path).await,
+            Err(_) => None,
+        };
+        java_arch
+            .as_deref()
+            .and_then(normalize_arch)
+            .or_else(|| normalize_arch(std::env::consts::ARCH))
+    }
+
+    /// The classifiers of a library to download and extract on this OS: the one built for
+    /// `arch` (see `natives_arch`) if there is one, otherwise those without an architecture suffix
+    fn select_native_classifiers<'a, T>(
+        &self,
+        classifiers: &'a std::collections::HashMap<String, T>,
+        arch: Option<&'static str>,
+    ) -> Vec<(&'a String, &'a T)> {
+        let for_os: Vec<(&String, &T)> = classifiers
+            .iter()
+            .filter(|(classifier, _)| self.is_native_for_current_os(classifier))
+            .collect();
+        match for_os.iter().find(|(classifier, _)| arch.is_some() && native_classifier_arch(classifier) == arch) {
+            Some(exact) => vec![*exact],
+            None => for_os
+                .into_iter()
+                .filter(|(classifier, _)| native_classifier_arch(classifier).is_none())
+                .collect(),
+        }
+    }
 
-    async fn extract_native_libraries(&self, version_info: &VersionInfo, instance_dir: &PathBuf) -> Result<()> {
-        log::info!("Extracting native libraries for version {}", version_info.id);
-        
+
+    /// Extract the version's native jars for this OS and `natives_arch` into `versions/<id>/natives`
+    ///
+    /// After a successful extraction the set of native jars and their hashes is recorded in
+    /// the natives directory; later calls skip extraction while it is unchanged unless `force` is set.
+    async fn extract_native_libraries(
+        &self,
+        version_info: &VersionInfo,
+        instance_dir: &Path,
+        natives_arch: Option<&'static str>,
+        force: bool,
+    ) -> Result<()> {
         let libraries_dir = instance_dir.join("libraries");
         let natives_dir = instance_dir.join("versions").join(&version_info.id).join("natives");
-        
+        let marker_path = natives_dir.join(NATIVES_MARKER_FILE);
+        let fingerprint = self.natives_fingerprint(version_info, natives_arch);
+        if !force && std::fs::read_to_string(&marker_path).is_ok_and(|marker| marker.trim() == fingerprint) {
+            log::info!("Native libraries for version {} are up to date, skipping extraction", version_info.id);
+            return Ok(());
+        }
+
+        log::info!("Extracting native libraries for version {}", version_info.id);
+
         // Create natives directory
         if !natives_dir.exists() {
             std::fs::create_dir_all(&natives_dir)
                 .map_err(|e| LauncherError::file(format!("Failed to create natives directory: {}", e)))?;
         }
+        // An extraction that fails halfway must not be mistaken for a complete one
+        if marker_path.exists() {
+            std::fs::remove_file(&marker_path)
+                .map_err(|e| LauncherError::file(format!("Failed to remove natives marker: {}", e)))?;
+        }
 
         for library in &version_info.libraries {
             // Check if library applies to current OS
@@ -624,25 +2319,66 @@ impl Launcher {
 
             if let Some(downloads) = &library.downloads {
                 if let Some(classifiers) = &downloads.classifiers {
-                    for (classifier, _download_info) in classifiers {
-                        if self.is_native_for_current_os(classifier) {
-                            let native_jar_path = self.get_native_path(&library.name, classifier, &libraries_dir);
-                            
-                            if native_jar_path.exists() {
-                                log::info!("Extracting native library: {}", native_jar_path.display());
-                                self.extract_native_jar(&native_jar_path, &natives_dir).await?;
-                            }
+                    for (classifier, download_info) in self.select_native_classifiers(classifiers, natives_arch) {
+                        let native_jar_path = self.get_native_path(&library.name, classifier, &libraries_dir);
+
+                        // A jar left half-written by an interrupted download would otherwise fail
+                        // extraction on every launch until it is deleted by hand
+                        if native_jar_path.exists()
+                            && !self.downloader.file_matches_sha1(&native_jar_path, &download_info.sha1).await
+                        {
+                            log::warn!(
+                                "Native library {} ({}) is corrupt or incomplete, downloading it again",
+                                library.name, classifier
+                            );
+                            tokio::fs::remove_file(&native_jar_path)
+                                .await
+                                .map_err(|e| LauncherError::file(format!("Failed to remove corrupt native JAR: {}", e)))?;
+                            self.downloader
+                                .download_file(&download_info.url, &native_jar_path, Some(&download_info.sha1))
+                                .await?;
+                        }
+
+                        if native_jar_path.exists() {
+                            log::info!("Extracting native library: {}", native_jar_path.display());
+                            self.extract_native_jar(&native_jar_path, &natives_dir).await.map_err(|e| {
+                                LauncherError::file(format!(
+                                    "Failed to extract native library {} ({}): {}",
+                                    library.name, classifier, e
+                                ))
+                            })?;
                         }
                     }
                 }
             }
         }
 
+        std::fs::write(&marker_path, &fingerprint)
+            .map_err(|e| LauncherError::file(format!("Failed to write natives marker: {}", e)))?;
         log::info!("Native libraries extracted to: {}", natives_dir.display());
         Ok(())
     }
 
-    async fn extract_native_jar(&self, jar_path: &PathBuf, natives_dir: &PathBuf) -> Result<()> {
+    /// Hash of the native jars (library, classifier and SHA1) that apply to this OS and `natives_arch`
+    fn natives_fingerprint(&self, version_info: &VersionInfo, natives_arch: Option<&'static str>) -> String {
+        use sha1::{Digest, Sha1};
+
+        let mut natives: Vec<String> = version_info
+            .libraries
+            .iter()
+            .filter(|library| library.rules.as_ref().is_none_or(|rules| self.evaluate_rules(rules)))
+            .filter_map(|library| Some((library, library.downloads.as_ref()?.classifiers.as_ref()?)))
+            .flat_map(|(library, classifiers)| {
+                self.select_native_classifiers(classifiers, natives_arch)
+                    .into_iter()
+                    .map(move |(classifier, download)| format!("{} {} {}", library.name, classifier, download.sha1))
+            })
+            .collect();
+        natives.sort();
+        format!("{:x}", Sha1::digest(natives.join("\n")))
+    }
+
+    async fn extract_native_jar(&self, jar_path: &Path, natives_dir: &Path) -> Result<()> {
         
         let file = std::fs::File::open(jar_path)
             .map_err(|e| LauncherError::file(format!("Failed to open native JAR: {}", e)))?;
@@ -698,4 +2434,634 @@ impl Launcher {
         Ok(())
     }
 
+    /// Check that the extracted natives match the architecture of the Java runtime that will load them
+    ///
+    /// Natives that ship 32- and 64-bit variants side by side (`lwjgl.dll`/`lwjgl64.dll`) only need one usable variant.
+    async fn verify_native_architecture(&self, natives_dir: &Path, java_path: &Path) -> Result<()> {
+        if !natives_dir.exists() {
+            return Ok(());
+        }
+
+        let Some(java_arch) = self.java_finder.java_architecture(java_path).await else {
+            log::debug!("Could not determine the architecture of {}, skipping natives check", java_path.display());
+            return Ok(());
+        };
+        let Some(java_arch) = normalize_arch(&java_arch) else {
+            log::debug!("Unknown Java architecture '{}', skipping natives check", java_arch);
+            return Ok(());
+        };
+
+        let natives = natives_dir.to_path_buf();
+        let mismatches = tokio::task::spawn_blocking(move || mismatched_natives(&natives, java_arch))
+            .await
+            .map_err(|e| LauncherError::other(format!("Checking native libraries failed: {}", e)))??;
+        if mismatches.is_empty() {
+            return Ok(());
+        }
+
+        Err(LauncherError::launch(format!(
+            "Native libraries do not match the {} Java runtime at {}: {}. Use a Java runtime built for the same architecture as the natives",
+            java_arch,
+            java_path.display(),
+            mismatches.join(", ")
+        )))
+    }
+}
+
+/// Native libraries under `natives_dir` that have no variant built for `java_arch`, as
+/// `file (archs)` descriptions
+fn mismatched_natives(natives_dir: &Path, java_arch: &str) -> Result<Vec<String>> {
+    let mut groups: std::collections::BTreeMap<String, Vec<(String, Vec<&'static str>)>> = std::collections::BTreeMap::new();
+    let mut pending = vec![natives_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+
+            let is_native = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| matches!(ext, "so" | "dll" | "dylib" | "jnilib"));
+            if !is_native {
+                continue;
+            }
+
+            let architectures = native_binary_architectures(&std::fs::read(&path)?);
+            if architectures.is_empty() {
+                continue;
+            }
+
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let group = stem.trim_end_matches("64").trim_end_matches("32").trim_end_matches(['-', '_']).to_string();
+            groups.entry(group).or_default().push((file_name, architectures));
+        }
+    }
+
+    Ok(groups
+        .values()
+        .filter(|variants| !variants.iter().any(|(_, archs)| archs.contains(&java_arch)))
+        .flat_map(|variants| variants.iter().map(|(name, archs)| format!("{} ({})", name, archs.join("/"))))
+        .collect())
+}
+
+/// Asset index `index_id` and its objects, relative to an assets directory, read from the first
+/// of `assets_dirs` that has the index; `None` if none does
+fn referenced_assets(assets_dirs: &[PathBuf], index_id: &str) -> Option<std::collections::HashSet<PathBuf>> {
+    let index_path = Path::new("indexes").join(format!("{}.json", index_id));
+    let asset_index = assets_dirs.iter().find_map(|dir| read_asset_index(&dir.join(&index_path)).ok())?;
+    let mut assets: std::collections::HashSet<PathBuf> = asset_index
+        .objects
+        .values()
+        .map(|object| Path::new("objects").join(object.object_path()))
+        .collect();
+    assets.insert(index_path);
+    Some(assets)
+}
+
+fn read_asset_index(path: &Path) -> Result<AssetIndexFile> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| LauncherError::file(format!("Failed to read asset index: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| LauncherError::json(format!("Failed to parse asset index: {}", e)))
+}
+
+/// Copy hashed asset objects to their names under `target`, skipping files already there
+fn copy_assets_by_name(
+    asset_index: &AssetIndexFile,
+    assets_dir: &Path,
+    target: &Path,
+    mode: &AssetDownloadMode,
+) -> Result<usize> {
+    let mut copied = 0;
+    for (asset_name, object) in &asset_index.objects {
+        let source = assets_dir.join("objects").join(object.object_path());
+        let destination = target.join(asset_name);
+        if !mode.includes(asset_name) || destination.exists() || !source.exists() {
+            continue;
+        }
+
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| LauncherError::file(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+        std::fs::copy(&source, &destination)
+            .map_err(|e| LauncherError::file(format!("Failed to copy asset {}: {}", asset_name, e)))?;
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+/// Merge legacy `minecraftArguments` with a modern `arguments.game` block.
+///
+/// The modern block takes precedence: a `--flag` it sets removes the same flag (and its value)
+/// from the legacy arguments. Flags that may be given several times ([`REPEATABLE_GAME_FLAGS`])
+/// are additive instead; only legacy pairs the modern block repeats exactly are dropped.
+/// The remaining legacy arguments come first, then the modern ones.
+fn merge_game_arguments(legacy: Vec<String>, modern: Vec<String>) -> Vec<String> {
+    if legacy.is_empty() {
+        return modern;
+    }
+
+    let modern_flags: std::collections::HashSet<&str> = modern
+        .iter()
+        .filter(|arg| arg.starts_with("--"))
+        .map(String::as_str)
+        .collect();
+    let modern_pairs: std::collections::HashSet<(&str, &str)> = modern
+        .windows(2)
+        .filter(|pair| REPEATABLE_GAME_FLAGS.contains(&pair[0].as_str()))
+        .map(|pair| (pair[0].as_str(), pair[1].as_str()))
+        .collect();
+
+    let mut merged = Vec::with_capacity(legacy.len() + modern.len());
+    let mut legacy = legacy.into_iter().peekable();
+    while let Some(arg) = legacy.next() {
+        let value = if arg.starts_with("--") {
+            legacy.next_if(|next| !next.starts_with("--"))
+        } else {
+            None
+        };
+        let replaced = if REPEATABLE_GAME_FLAGS.contains(&arg.as_str()) {
+            value.as_deref().is_some_and(|value| modern_pairs.contains(&(arg.as_str(), value)))
+        } else {
+            modern_flags.contains(arg.as_str())
+        };
+        if replaced {
+            continue;
+        }
+        merged.push(arg);
+        merged.extend(value);
+    }
+
+    merged.extend(modern.iter().cloned());
+    merged
+}
+
+/// Game flags that legacy mod stacks (LaunchWrapper tweakers, LiteLoader, Mixin) pass once per value
+const REPEATABLE_GAME_FLAGS: &[&str] = &["--tweakClass", "--mixin"];
+
+/// Whether a version runs on LWJGL 3 (Minecraft 1.13 and later), judged by its libraries and,
+/// failing that, its release
+fn uses_lwjgl3(version_info: &VersionInfo) -> bool {
+    let lwjgl_version = version_info
+        .libraries
+        .iter()
+        .find_map(|library| library.name.strip_prefix("org.lwjgl:lwjgl:"));
+    match lwjgl_version {
+        Some(version) => version.starts_with("3."),
+        None => release_series(&version_info.id).is_some_and(|series| series >= (1, 13, 0)),
+    }
+}
+
+/// Architecture in a native classifier's suffix (`natives-macos-arm64`, `natives-windows-64`),
+/// or `None` for plain ones like `natives-osx`
+fn native_classifier_arch(classifier: &str) -> Option<&'static str> {
+    match classifier.rsplit_once('-')?.1 {
+        "32" => Some("x86"),
+        "64" => Some("x86_64"),
+        suffix => normalize_arch(suffix),
+    }
+}
+
+/// Where the patched logging config for a version is stored, named after its download
+fn log4j_config_path(instance_dir: &Path, logging: &LoggingClient) -> PathBuf {
+    let file_name = logging.file.url.rsplit('/').next().unwrap_or("client.xml");
+    instance_dir.join("assets").join("log_configs").join(file_name)
+}
+
+/// Cheap key for reusing a cached classpath: the version and mod loader, the platform, and the
+/// size and modification time of the instance's cached version JSON, which is only rewritten
+/// when the version (and so its library list) changes
+fn classpath_fingerprint(version_id: &str, launch_config: &LaunchConfig, instance_dir: &Path) -> Result<String> {
+    let version_json = instance_dir.join("versions").join(version_id).join(format!("{}.json", version_id));
+    let metadata = std::fs::metadata(&version_json)
+        .map_err(|e| LauncherError::file(format!("Failed to read {}: {}", version_json.display(), e)))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|since_epoch| since_epoch.as_nanos())
+        .unwrap_or_default();
+    let mod_loader = launch_config
+        .mod_loader
+        .as_ref()
+        .map(|loader| format!("{:?} {}", loader.loader_type, loader.version))
+        .unwrap_or_default();
+
+    Ok(format!(
+        "{} {}-{} {} {} [{}] {} {}",
+        crate::VERSION,
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        instance_dir.display(),
+        version_id,
+        mod_loader,
+        metadata.len(),
+        modified
+    ))
+}
+
+/// Join classpath entries with the platform's separator
+fn join_classpath(entries: &[PathBuf]) -> String {
+    entries
+        .iter()
+        .map(|entry| entry.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(if cfg!(windows) { ";" } else { ":" })
+}
+
+/// Remove `-cp`/`-classpath`/`--class-path` and their values from templated JVM arguments
+fn strip_classpath_args(args: Vec<String>) -> Vec<String> {
+    let mut stripped = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "-cp" || arg == "-classpath" || arg == "--class-path" {
+            args.next();
+        } else {
+            stripped.push(arg);
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::ProfileInfo;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn merge_prefers_modern_flags() {
+        let legacy = strings(&["--username", "Steve", "--version", "1.12.2", "--assetIndex", "1.12"]);
+        let modern = strings(&["--version", "1.12.2-forge", "--demo"]);
+        assert_eq!(
+            merge_game_arguments(legacy, modern),
+            strings(&["--username", "Steve", "--assetIndex", "1.12", "--version", "1.12.2-forge", "--demo"])
+        );
+
+        // Tweakers stack: both are kept, and one given by both blocks is passed once
+        let legacy = strings(&["--username", "Steve", "--tweakClass", "liteloader.Tweaker", "--tweakClass", "forge.Tweaker"]);
+        let modern = strings(&["--tweakClass", "forge.Tweaker", "--demo"]);
+        assert_eq!(
+            merge_game_arguments(legacy, modern),
+            strings(&["--username", "Steve", "--tweakClass", "liteloader.Tweaker", "--tweakClass", "forge.Tweaker", "--demo"])
+        );
+    }
+
+    #[test]
+    fn merge_keeps_single_format_unchanged() {
+        let legacy = strings(&["--username", "Steve", "--fullscreen"]);
+        assert_eq!(merge_game_arguments(legacy.clone(), Vec::new()), legacy);
+        assert_eq!(merge_game_arguments(Vec::new(), legacy.clone()), legacy);
+    }
+
+    #[test]
+    fn merge_handles_valueless_legacy_flags() {
+        let legacy = strings(&["--demo", "--username", "Steve"]);
+        let modern = strings(&["--demo"]);
+        assert_eq!(merge_game_arguments(legacy, modern), strings(&["--username", "Steve", "--demo"]));
+    }
+
+    #[test]
+    fn native_classifier_arch_reads_suffixes() {
+        assert_eq!(native_classifier_arch("natives-macos-arm64"), Some("aarch64"));
+        assert_eq!(native_classifier_arch("natives-windows-64"), Some("x86_64"));
+        assert_eq!(native_classifier_arch("natives-windows-32"), Some("x86"));
+        assert_eq!(native_classifier_arch("natives-osx"), None);
+        assert_eq!(native_classifier_arch("natives-linux"), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn natives_follow_the_java_runtime_architecture() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        // Stands in for an arm64 JRE, whatever this machine is
+        let java = dir.path().join("java");
+        std::fs::write(&java, "#!/bin/sh\necho '    java.version = 17.0.9' >&2\necho '    os.arch = aarch64' >&2\n").unwrap();
+        std::fs::set_permissions(&java, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let config = LauncherConfig::new(dir.path().join("launcher")).with_java_path(java);
+        let launcher = Launcher::new(config).await.unwrap();
+
+        let version_info: VersionInfo = serde_json::from_value(serde_json::json!({
+            "id": "1.19.4",
+            "assetIndex": { "id": "3", "sha1": "", "size": 0, "url": "" },
+            "assets": "3",
+            "downloads": { "client": { "sha1": "", "size": 0, "url": "" } },
+            "libraries": [],
+            "mainClass": "net.minecraft.client.main.Main",
+            "releaseTime": "2023-03-14T12:56:18+00:00",
+            "time": "2023-03-14T12:56:18+00:00",
+            "type": "release"
+        }))
+        .unwrap();
+        let arch = launcher.natives_arch(&version_info).await;
+        assert_eq!(arch, Some("aarch64"));
+
+        let os = if cfg!(target_os = "macos") { "natives-macos" } else { "natives-linux" };
+        let classifiers: std::collections::HashMap<String, ()> =
+            [os.to_string(), format!("{}-arm64", os), format!("{}-x86_64", os)].into_iter().map(|c| (c, ())).collect();
+        let selected: Vec<&String> = launcher.select_native_classifiers(&classifiers, arch).into_iter().map(|(c, _)| c).collect();
+        assert_eq!(selected, vec![&format!("{}-arm64", os)]);
+    }
+
+    fn test_account() -> Account {
+        Account {
+            uuid: "00000000-0000-0000-0000-000000000001".to_string(),
+            name: "Steve".to_string(),
+            access_token: "token".to_string(),
+            refresh_token: String::new(),
+            expires_at: chrono::Utc::now(),
+            account_type: AccountType::Offline,
+            profile: ProfileInfo {
+                id: "00000000-0000-0000-0000-000000000001".to_string(),
+                name: "Steve".to_string(),
+                skins: Vec::new(),
+                capes: Vec::new(),
+            },
+            xuid: None,
+            client_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn import_config_rejects_instance_names_outside_the_instances_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut launcher = Launcher::new(LauncherConfig::new(dir.path().join("launcher"))).await.unwrap();
+        let export_path = dir.path().join("export.json");
+
+        for name in ["../escaped", "/tmp/absolute", "C:escaped", ".."] {
+            let export = ConfigExport {
+                format_version: CONFIG_EXPORT_VERSION,
+                exported_at: chrono::Utc::now(),
+                config: launcher.config.to_portable(),
+                instances: vec![InstanceMetadata::new(name.to_string(), "1.20.1".to_string())],
+            };
+            std::fs::write(&export_path, serde_json::to_string(&export).unwrap()).unwrap();
+
+            let error = launcher.import_config(&export_path).await.unwrap_err();
+            assert!(error.to_string().contains("invalid instance name"), "{}", error);
+        }
+        assert!(!dir.path().join("launcher").join("escaped").exists());
+        assert!(!dir.path().join("escaped").exists());
+    }
+
+    #[tokio::test]
+    async fn unlinkable_shared_dir_fails_the_launch() {
+        let dir = tempfile::tempdir().unwrap();
+        let launcher = Launcher::new(LauncherConfig::new(dir.path().to_path_buf())).await.unwrap();
+        let instance_dir = dir.path().join("instances").join("test");
+        std::fs::create_dir_all(instance_dir.join("mods")).unwrap();
+        std::fs::write(instance_dir.join("mods").join("private.jar"), b"jar").unwrap();
+
+        let shared_mods = dir.path().join("shared-mods");
+        let launch_config = LaunchConfig::new("1.20.1".to_string(), "test".to_string(), test_account())
+            .with_custom_dirs(Some(shared_mods.clone()), None, None, None);
+        let error = launcher.link_custom_dirs(&launch_config, &instance_dir).await.unwrap_err();
+        assert!(matches!(error, LauncherError::Config(_)), "{:?}", error);
+        assert!(error.to_string().contains("shared mods directory"), "{}", error);
+        assert!(instance_dir.join("mods").join("private.jar").exists());
+    }
+
+    #[tokio::test]
+    async fn vanilla_import_caches_the_resolved_version_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let mc_dir = dir.path().join(".minecraft");
+        let mut launcher = Launcher::new(LauncherConfig::new(dir.path().join("launcher"))).await.unwrap();
+
+        let parent = serde_json::json!({
+            "id": "1.20.1",
+            "assetIndex": { "id": "5", "sha1": "", "size": 0, "url": "" },
+            "assets": "5",
+            "downloads": { "client": { "sha1": "", "size": 0, "url": "" } },
+            "libraries": [{ "name": "com.mojang:brigadier:1.1.8" }],
+            "mainClass": "net.minecraft.client.main.Main",
+            "arguments": { "game": [], "jvm": [] },
+            "releaseTime": "2023-06-12T13:25:51+00:00",
+            "time": "2023-06-12T13:25:51+00:00",
+            "type": "release"
+        });
+        let child = serde_json::json!({
+            "id": "fabric-loader-0.16.9-1.20.1",
+            "inheritsFrom": "1.20.1",
+            "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+            "libraries": [{ "name": "net.fabricmc:fabric-loader:0.16.9", "url": "https://maven.fabricmc.net/" }],
+            "releaseTime": "2024-11-18T00:00:00+00:00",
+            "time": "2024-11-18T00:00:00+00:00",
+            "type": "release"
+        });
+        for profile in [&parent, &child] {
+            let id = profile["id"].as_str().unwrap();
+            std::fs::create_dir_all(mc_dir.join("versions").join(id)).unwrap();
+            std::fs::write(mc_dir.join("versions").join(id).join(format!("{}.json", id)), profile.to_string()).unwrap();
+        }
+        std::fs::create_dir_all(mc_dir.join("saves").join("World")).unwrap();
+        std::fs::write(
+            mc_dir.join("launcher_profiles.json"),
+            r#"{"profiles": {"x": {"name": "Fabric", "type": "custom", "lastVersionId": "fabric-loader-0.16.9-1.20.1"}}}"#,
+        )
+        .unwrap();
+
+        let report = launcher.import_vanilla_profiles(&mc_dir).await.unwrap();
+        assert_eq!(report.instances, ["Fabric", "1.20.1"]);
+
+        let instance_dir = dir.path().join("launcher").join("instances").join("Fabric");
+        let cached: serde_json::Value = serde_json::from_slice(
+            &std::fs::read(launcher.cached_version_path(&instance_dir, "fabric-loader-0.16.9-1.20.1")).unwrap(),
+        )
+        .unwrap();
+        assert!(cached.get("inheritsFrom").is_none_or(|value| value.is_null()));
+        assert_eq!(cached["assetIndex"]["id"], "5");
+        assert_eq!(cached["libraries"].as_array().unwrap().len(), 2);
+        // The profile sets no game directory, so no worlds are copied
+        assert!(!instance_dir.join("saves").join("World").exists());
+    }
+
+    #[tokio::test]
+    async fn logout_removes_the_stored_account() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut launcher = Launcher::new(LauncherConfig::new(dir.path().to_path_buf())).await.unwrap();
+        let account = test_account();
+        launcher.save_account(account.clone()).await.unwrap();
+
+        launcher.logout(AuthenticatorConfig::default(), &account).await.unwrap();
+        assert!(launcher.accounts().get(&account.uuid).is_none());
+        let reloaded = AccountStore::load(dir.path().join(ACCOUNT_STORE_FILE)).await.unwrap();
+        assert!(reloaded.get(&account.uuid).is_none());
+    }
+
+    #[tokio::test]
+    async fn profile_without_launch_arguments_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let launcher = Launcher::new(LauncherConfig::new(dir.path().to_path_buf())).await.unwrap();
+
+        let version_info: VersionInfo = serde_json::from_value(serde_json::json!({
+            "id": "broken",
+            "assetIndex": { "id": "1.12", "sha1": "", "size": 0, "url": "" },
+            "assets": "1.12",
+            "downloads": { "client": { "sha1": "", "size": 0, "url": "" } },
+            "libraries": [],
+            "mainClass": "net.minecraft.client.main.Main",
+            "releaseTime": "2017-09-18T08:39:46+00:00",
+            "time": "2017-09-18T08:39:46+00:00",
+            "type": "release"
+        }))
+        .unwrap();
+
+        let launch_config = LaunchConfig::new("broken".to_string(), "test".to_string(), test_account());
+        let error = launcher
+            .build_launch_arguments(&launch_config, &version_info, &dir.path().join("instances").join("test"))
+            .unwrap_err();
+        assert!(error.to_string().contains("no launch arguments"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn classpath_cache_follows_library_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let launcher = Launcher::new(LauncherConfig::new(dir.path().to_path_buf())).await.unwrap();
+        let instance_dir = dir.path().join("instances").join("test");
+        let launch_config = LaunchConfig::new("1.12.2".to_string(), "test".to_string(), test_account());
+
+        let version_with = |libraries: serde_json::Value| -> VersionInfo {
+            serde_json::from_value(serde_json::json!({
+                "id": "1.12.2",
+                "assetIndex": { "id": "1.12", "sha1": "", "size": 0, "url": "" },
+                "assets": "1.12",
+                "downloads": { "client": { "sha1": "", "size": 0, "url": "" } },
+                "libraries": libraries,
+                "mainClass": "net.minecraft.client.main.Main",
+                "releaseTime": "2017-09-18T08:39:46+00:00",
+                "time": "2017-09-18T08:39:46+00:00",
+                "type": "release"
+            }))
+            .unwrap()
+        };
+        let original = version_with(serde_json::json!([{ "name": "com.mojang:patchy:1.1" }]));
+        let changed = version_with(serde_json::json!([
+            { "name": "com.mojang:patchy:1.1" },
+            { "name": "net.minecraftforge:forge:1.12.2-14.23.5.2859" }
+        ]));
+
+        launcher.cache_version_info(&original, &instance_dir).await.unwrap();
+        let first = classpath_fingerprint("1.12.2", &launch_config, &instance_dir).unwrap();
+        launcher.cache_version_info(&original, &instance_dir).await.unwrap();
+        assert_eq!(classpath_fingerprint("1.12.2", &launch_config, &instance_dir).unwrap(), first);
+
+        launcher.cache_version_info(&changed, &instance_dir).await.unwrap();
+        assert_ne!(classpath_fingerprint("1.12.2", &launch_config, &instance_dir).unwrap(), first);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn prune_keeps_files_any_instance_references_in_shared_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let launcher = Launcher::new(LauncherConfig::new(dir.path().to_path_buf())).await.unwrap();
+        let store = dir.path().join("store");
+        let shared_assets = store.join("assets");
+        let write = |path: PathBuf, content: &str| {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+
+        for (name, hash) in [("a", "aa00"), ("b", "bb00")] {
+            let instance_dir = dir.path().join("instances").join(name);
+            std::fs::create_dir_all(&instance_dir).unwrap();
+            instance::write_metadata(&instance_dir, &InstanceMetadata::new(name.to_string(), format!("v{}", name))).unwrap();
+            let version_info: VersionInfo = serde_json::from_value(serde_json::json!({
+                "id": format!("v{}", name),
+                "assetIndex": { "id": format!("index-{}", name), "sha1": "", "size": 0, "url": "" },
+                "assets": format!("index-{}", name),
+                "downloads": { "client": { "sha1": "", "size": 0, "url": "" } },
+                "libraries": [{ "name": format!("org.example:{}:1", name) }],
+                "mainClass": "net.minecraft.client.main.Main",
+                "releaseTime": "2017-09-18T08:39:46+00:00",
+                "time": "2017-09-18T08:39:46+00:00",
+                "type": "release"
+            }))
+            .unwrap();
+            launcher.cache_version_info(&version_info, &instance_dir).await.unwrap();
+            std::os::unix::fs::symlink(store.join("libraries"), instance_dir.join("libraries")).unwrap();
+
+            write(store.join(format!("libraries/org/example/{0}/1/{0}-1.jar", name)), "jar");
+            let index = serde_json::json!({ "objects": { format!("{}.ogg", name): { "hash": hash, "size": 3 } } });
+            write(shared_assets.join(format!("indexes/index-{}.json", name)), &index.to_string());
+            write(shared_assets.join(format!("objects/{}/{}", &hash[..2], hash)), "ogg");
+        }
+        write(store.join("libraries/org/example/unused/1/unused-1.jar"), "jar");
+        write(shared_assets.join("indexes/index-old.json"), r#"{"objects": {}}"#);
+        write(shared_assets.join("objects/cc/cc00"), "ogg");
+        write(dir.path().join("instances/a/versions/old/old.jar"), "jar");
+
+        let dry_run = launcher.prune_unused_downloads(std::slice::from_ref(&shared_assets), true).await.unwrap();
+        assert_eq!(dry_run.files_removed, 4);
+        assert!(store.join("libraries/org/example/unused/1/unused-1.jar").exists());
+
+        let report = launcher.prune_unused_downloads(std::slice::from_ref(&shared_assets), false).await.unwrap();
+        assert_eq!(report, dry_run);
+        assert!(!store.join("libraries/org/example/unused").exists());
+        assert!(!shared_assets.join("objects/cc").exists());
+        assert!(!shared_assets.join("indexes/index-old.json").exists());
+        assert!(!dir.path().join("instances/a/versions/old").exists());
+        for kept in ["libraries/org/example/a/1/a-1.jar", "libraries/org/example/b/1/b-1.jar", "assets/objects/aa/aa00", "assets/objects/bb/bb00"] {
+            assert!(store.join(kept).exists(), "{}", kept);
+        }
+    }
+
+    #[tokio::test]
+    async fn profile_with_both_argument_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let launcher = Launcher::new(LauncherConfig::new(dir.path().to_path_buf())).await.unwrap();
+
+        let version_info: VersionInfo = serde_json::from_value(serde_json::json!({
+            "id": "1.12.2-forge",
+            "assetIndex": { "id": "1.12", "sha1": "", "size": 0, "url": "" },
+            "assets": "1.12",
+            "downloads": { "client": { "sha1": "", "size": 0, "url": "" } },
+            "libraries": [],
+            "mainClass": "net.minecraft.launchwrapper.Launch",
+            "minecraftArguments": "--username ${auth_player_name} --assetsDir ${assets_root} --tweakClass old.Tweaker",
+            "arguments": { "game": ["--tweakClass", "net.minecraftforge.fml.common.launcher.FMLTweaker"], "jvm": [] },
+            "releaseTime": "2017-09-18T08:39:46+00:00",
+            "time": "2017-09-18T08:39:46+00:00",
+            "type": "release"
+        }))
+        .unwrap();
+
+        let launch_config = LaunchConfig::new("1.12.2-forge".to_string(), "test".to_string(), test_account());
+        let instance_dir = dir.path().join("instances").join("test");
+
+        let launch_args = launcher.build_launch_arguments(&launch_config, &version_info, &instance_dir).unwrap();
+        assert_eq!(launch_args.main_class, "net.minecraft.launchwrapper.Launch");
+        assert!(launch_args.jvm_args.contains(&"-Xmx8192m".to_string()));
+        assert_eq!(
+            launch_args.classpath.last(),
+            Some(&instance_dir.join("versions").join("1.12.2-forge").join("1.12.2-forge.jar"))
+        );
+
+        let args = launch_args.game_args;
+        let assets_root = instance_dir.join("assets").to_string_lossy().to_string();
+        assert_eq!(
+            args,
+            strings(&[
+                "--username",
+                "Steve",
+                "--assetsDir",
+                &assets_root,
+                "--tweakClass",
+                "old.Tweaker",
+                "--tweakClass",
+                "net.minecraftforge.fml.common.launcher.FMLTweaker",
+                "--assetIndex",
+                "1.12",
+            ])
+        );
+    }
 }
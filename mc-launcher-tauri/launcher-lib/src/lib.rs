@@ -22,7 +22,7 @@
 //!     let account = launcher.authenticate(auth_config).await?;
 //!     
 //!     // Launch Minecraft
-//!     let launch_config = launcher.create_launch_config("1.21.4", &account)?;
+//!     let launch_config = launcher.create_launch_config("1.21.4", &account).await?;
 //!     let process = launcher.launch(launch_config).await?;
 //!     
 //!     Ok(())
@@ -33,19 +33,29 @@ pub mod auth;
 pub mod config;
 pub mod downloader;
 pub mod error;
+pub mod instance;
 pub mod launcher;
 pub mod minecraft;
+pub mod mods;
 pub mod utils;
 pub mod version;
 pub mod java;
 
 // Re-export main types
-pub use auth::{Authenticator, AuthenticatorConfig, Account};
-pub use config::{LauncherConfig, LaunchConfig};
-pub use error::{LauncherError, Result};
-pub use launcher::Launcher;
-pub use minecraft::{MinecraftProcess, ProcessStatus};
-pub use version::{VersionManifest, VersionInfo, ModLoader, ModLoaderType};
+pub use auth::{Authenticator, AuthenticatorConfig, Account, AccountRefresh, AccountRefreshStatus, AccountStore, AccountType, AuthOutcome, LocalServerFlow, ProfileInfo, SkinInfo, CapeInfo, DeviceCodeFlow, DeviceCodePoll, TokenStatus};
+pub use config::{AssetDownloadMode, ConfigExport, DownloadVerification, JvmArgValidation, LaunchWarning, LauncherConfig, LaunchConfig, LogCaptureConfig, MojangEndpoints, MultipartDownloadConfig, CONFIG_EXPORT_VERSION};
+pub use downloader::{FileHash, ProgressSnapshot, ProgressTracker};
+pub use error::{AuthErrorKind, LauncherError, Result};
+pub use instance::{ClasspathCache, CleanupReport, InstanceDiskUsage, InstanceMetadata, ScreenshotInfo, VanillaImportReport, WorldBackup};
+pub use launcher::{ArgTransformer, LaunchArguments, Launcher};
+pub use minecraft::{LogLine, LogSource, MinecraftProcess, ProcessOptions, ProcessStatus, ResourceUsage, DEFAULT_WINDOW_READY_PATTERNS};
+pub use mods::{ModDependency, ModInfo, ModIssue};
+pub use utils::{validate_jar, wait_for_connectivity, JavaFinder, JavaInstallation, JavaSource};
+pub use version::{compare_maven_versions, AssetIndexFile, AssetObject, release_series, ReleaseSeries, VersionManifest, VersionInfo, VersionManager, VersionSource, ModLoader, ModLoaderDownloadPlan, ModLoaderType, PlannedDownload};
+
+/// Token for cancelling sign-in flows. Re-exported from `tokio-util` 0.7, so a major bump of
+/// that crate is a breaking change here too.
+pub use tokio_util::sync::CancellationToken;
 
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
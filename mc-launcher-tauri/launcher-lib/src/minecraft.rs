@@ -1,13 +1,18 @@
 //! Minecraft process management
 
-use std::path::PathBuf;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Utc};
 use tokio::process::{Child, Command};
-use tokio::sync::RwLock;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::{broadcast, mpsc, watch, RwLock};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, BufReader};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use serde::{Deserialize, Serialize};
-use crate::{auth::Account, error::{LauncherError, Result}};
+use tokio::io::AsyncWriteExt;
+use crate::{auth::Account, config::LogCaptureConfig, error::{LauncherError, Result}};
 
 /// Status of a Minecraft process
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -24,6 +29,182 @@ pub enum ProcessStatus {
     Failed(String),
 }
 
+/// CPU and memory use of a running game, see [`MinecraftProcess::resource_usage`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    /// CPU usage in percent of one core, so it can exceed 100 on multi-core machines
+    pub cpu_percent: f32,
+    /// Resident memory (working set on Windows) in bytes, including JVM overhead beyond the heap
+    pub memory_bytes: u64,
+}
+
+/// Lines of game output kept so that late log subscribers still see startup messages
+const LOG_HISTORY_LINES: usize = 2000;
+
+/// How often `latest.log` is checked for new lines
+const LOG_TAIL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Where a log line came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogSource {
+    /// The JVM's standard output
+    Stdout,
+    /// The JVM's standard error
+    Stderr,
+    /// `logs/latest.log`, for lines the game did not also print to the console
+    LatestLog,
+}
+
+/// One line of game output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLine {
+    pub source: LogSource,
+    pub line: String,
+    /// When the launcher received the line
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Recent console output, replayed to new subscribers before live lines
+#[derive(Debug)]
+struct LogHub {
+    history: std::sync::Mutex<VecDeque<LogLine>>,
+    sender: broadcast::Sender<LogLine>,
+}
+
+impl LogHub {
+    fn new() -> Self {
+        Self {
+            history: std::sync::Mutex::new(VecDeque::new()),
+            sender: broadcast::channel(1024).0,
+        }
+    }
+
+    fn push(&self, source: LogSource, line: String) {
+        let line = LogLine { source, line, timestamp: Utc::now() };
+        // Hold the history lock while sending so a subscriber never misses or repeats a line
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        if history.len() == LOG_HISTORY_LINES {
+            history.pop_front();
+        }
+        history.push_back(line.clone());
+        let _ = self.sender.send(line);
+    }
+
+    fn subscribe(&self) -> (Vec<LogLine>, broadcast::Receiver<LogLine>) {
+        let history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        (history.iter().cloned().collect(), self.sender.subscribe())
+    }
+}
+
+/// Bounded multiset of console lines, used to skip their copies in `latest.log`
+#[derive(Default)]
+struct RecentLines {
+    counts: HashMap<String, usize>,
+    order: VecDeque<String>,
+}
+
+impl RecentLines {
+    fn insert(&mut self, line: &str) {
+        let key = log_line_key(line).to_string();
+        *self.counts.entry(key.clone()).or_default() += 1;
+        self.order.push_back(key);
+        if self.order.len() > LOG_HISTORY_LINES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.remove(&oldest);
+            }
+        }
+    }
+
+    /// Forget one occurrence of `line`, returning whether it had been seen
+    fn take(&mut self, line: &str) -> bool {
+        let key = log_line_key(line);
+        if !self.counts.contains_key(key) {
+            return false;
+        }
+        self.remove(key);
+        if let Some(position) = self.order.iter().position(|entry| entry == key) {
+            self.order.remove(position);
+        }
+        true
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(count) = self.counts.get_mut(key) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(key);
+            }
+        }
+    }
+}
+
+/// Line without its leading `[timestamp]`; console and file appenders often format the time differently
+fn log_line_key(line: &str) -> &str {
+    match line.strip_prefix('[').and_then(|rest| rest.split_once("] ")) {
+        Some((_, rest)) => rest,
+        None => line,
+    }
+}
+
+/// Log line fragments that mean the game window is on screen, used when
+/// [`ProcessOptions::window_ready_patterns`] is not set: LWJGL reporting its version as the
+/// window is created (old and new wording), and the first texture atlas being built
+pub const DEFAULT_WINDOW_READY_PATTERNS: &[&str] = &["Backend library: LWJGL", "LWJGL Version:", "minecraft:textures/atlas/"];
+
+/// Options controlling how a Minecraft process is spawned
+#[derive(Debug, Clone, Default)]
+pub struct ProcessOptions {
+    /// Path of the per-launch log file that stdout/stderr are copied into
+    pub log_file: Option<PathBuf>,
+    /// Rotation settings for the log file
+    pub log_capture: LogCaptureConfig,
+    /// Shell command to run after the game exits
+    pub post_exit_command: Option<String>,
+    /// Start the game in its own process group with output going to `log_file` instead of pipes,
+    /// so it keeps running after the launcher exits; see [`MinecraftProcess::reattach`]
+    pub detached: bool,
+    /// Console output fragments that mark the game window as ready; `None` uses
+    /// [`DEFAULT_WINDOW_READY_PATTERNS`]
+    pub window_ready_patterns: Option<Vec<String>>,
+}
+
+/// File in the game directory recording a detached game so a later launcher can reattach to it
+const DETACHED_RECORD_FILE: &str = "launcher-process.json";
+
+/// What is persisted about a detached game
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DetachedRecord {
+    pid: u32,
+    java_path: PathBuf,
+    /// The account, with its tokens removed
+    account: Account,
+    started_at: DateTime<Utc>,
+    log_file: Option<PathBuf>,
+    /// See [`crate::utils::process_start_time`]; a record without one can't be trusted
+    #[serde(default)]
+    process_start: Option<String>,
+}
+
+impl DetachedRecord {
+    /// Whether the recorded game is still running, and not some other process that got its PID
+    fn is_running(&self) -> bool {
+        self.process_start.is_some() && crate::utils::process_start_time(self.pid) == self.process_start
+    }
+}
+
+/// PID of a detached game recorded in `working_dir` that is still running. A stale record is removed.
+pub(crate) fn running_detached_pid(working_dir: &Path) -> Option<u32> {
+    let record_path = working_dir.join(DETACHED_RECORD_FILE);
+    let content = std::fs::read_to_string(&record_path).ok()?;
+    let record: DetachedRecord = serde_json::from_str(&content).ok()?;
+    if record.is_running() {
+        return Some(record.pid);
+    }
+    let _ = std::fs::remove_file(&record_path);
+    None
+}
+
 /// Minecraft process wrapper
 #[derive(Debug, Clone)]
 pub struct MinecraftProcess {
@@ -37,8 +218,20 @@ struct MinecraftProcessInner {
     args: Vec<String>,
     working_dir: PathBuf,
     account: Account,
-    status: RwLock<ProcessStatus>,
+    status: watch::Sender<ProcessStatus>,
+    window_ready: watch::Sender<bool>,
     pid: RwLock<Option<u32>>,
+    options: ProcessOptions,
+    exit_handled: AtomicBool,
+    post_exit_code: RwLock<Option<i32>>,
+    logs: LogHub,
+    started_at: SystemTime,
+    /// Adopted from a detached launch by an earlier launcher, so there is no `Child` handle
+    reattached: bool,
+    /// Start time of the reattached process, checked before it is polled or killed by PID
+    process_start: Option<String>,
+    /// When `resource_usage` last sampled the process and the CPU time it had used by then
+    cpu_sample: std::sync::Mutex<Option<(std::time::Instant, Duration)>>,
 }
 
 impl MinecraftProcess {
@@ -48,6 +241,17 @@ impl MinecraftProcess {
         args: Vec<String>,
         working_dir: PathBuf,
         account: Account,
+    ) -> Result<Self> {
+        Self::new_with_options(java_path, args, working_dir, account, ProcessOptions::default()).await
+    }
+
+    /// Create and start a new Minecraft process with custom spawn options
+    pub async fn new_with_options(
+        java_path: PathBuf,
+        args: Vec<String>,
+        working_dir: PathBuf,
+        account: Account,
+        options: ProcessOptions,
     ) -> Result<Self> {
         let inner = Arc::new(MinecraftProcessInner {
             child: RwLock::new(None),
@@ -55,8 +259,17 @@ impl MinecraftProcess {
             args,
             working_dir,
             account,
-            status: RwLock::new(ProcessStatus::Starting),
+            status: watch::channel(ProcessStatus::Starting).0,
+            window_ready: watch::channel(false).0,
             pid: RwLock::new(None),
+            options,
+            exit_handled: AtomicBool::new(false),
+            post_exit_code: RwLock::new(None),
+            logs: LogHub::new(),
+            started_at: SystemTime::now(),
+            reattached: false,
+            process_start: None,
+            cpu_sample: std::sync::Mutex::new(None),
         });
 
         let process = Self { inner };
@@ -64,18 +277,137 @@ impl MinecraftProcess {
         Ok(process)
     }
 
+    /// Adopt a game started detached (see [`ProcessOptions::detached`]) in `working_dir`, e.g. by a
+    /// launcher that has since restarted. Returns `None` if no detached game is running there;
+    /// a record whose PID now belongs to another process (compared by start time) is removed.
+    ///
+    /// Reattached processes have no console output beyond `latest.log`, their exit code is
+    /// not known (reported as -1), and the account has no tokens.
+    pub async fn reattach(working_dir: PathBuf) -> Result<Option<Self>> {
+        let record_path = working_dir.join(DETACHED_RECORD_FILE);
+        let Ok(content) = tokio::fs::read_to_string(&record_path).await else {
+            return Ok(None);
+        };
+        let record: DetachedRecord = serde_json::from_str(&content)
+            .map_err(|e| LauncherError::json(format!("Failed to parse {}: {}", record_path.display(), e)))?;
+
+        let pid = record.pid;
+        let (record, alive) = tokio::task::spawn_blocking(move || {
+            let alive = record.is_running();
+            (record, alive)
+        })
+        .await
+        .map_err(|e| LauncherError::other(format!("Checking process {} failed: {}", pid, e)))?;
+        if !alive {
+            let _ = tokio::fs::remove_file(&record_path).await;
+            return Ok(None);
+        }
+
+        let options = ProcessOptions {
+            log_file: record.log_file,
+            detached: true,
+            ..Default::default()
+        };
+        let inner = Arc::new(MinecraftProcessInner {
+            child: RwLock::new(None),
+            java_path: record.java_path,
+            args: Vec::new(),
+            working_dir,
+            account: record.account,
+            status: watch::channel(ProcessStatus::Running).0,
+            window_ready: watch::channel(true).0,
+            pid: RwLock::new(Some(pid)),
+            options,
+            exit_handled: AtomicBool::new(false),
+            post_exit_code: RwLock::new(None),
+            logs: LogHub::new(),
+            started_at: record.started_at.into(),
+            reattached: true,
+            process_start: record.process_start,
+            cpu_sample: std::sync::Mutex::new(None),
+        });
+
+        let process = Self { inner };
+        let monitor = process.clone();
+        tokio::spawn(async move {
+            monitor.monitor_exit().await;
+        });
+        log::info!("Reattached to detached Minecraft process with PID {}", pid);
+        Ok(Some(process))
+    }
+
+    /// Poll a reattached process until it is gone
+    async fn monitor_reattached_exit(&self) {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+            let Some(pid) = *self.inner.pid.read().await else {
+                return;
+            };
+            if self.reattached_process_running(pid).await {
+                continue;
+            }
+
+            log::info!("Reattached process {} exited", pid);
+            self.inner.status.send_if_modified(|status| {
+                let running = matches!(*status, ProcessStatus::Starting | ProcessStatus::Running);
+                if running {
+                    *status = ProcessStatus::Exited(-1);
+                }
+                running
+            });
+            self.run_post_exit_command().await;
+            return;
+        }
+    }
+
+    /// Whether the reattached process is still the one with `pid`
+    async fn reattached_process_running(&self, pid: u32) -> bool {
+        let expected = self.inner.process_start.clone();
+        tokio::task::spawn_blocking(move || expected.is_some() && crate::utils::process_start_time(pid) == expected)
+            .await
+            .unwrap_or(true)
+    }
+
+    /// Remember a detached game so `reattach` can find it
+    async fn write_detached_record(&self, pid: u32) -> Result<()> {
+        let mut account = self.inner.account.clone();
+        account.access_token.clear();
+        account.refresh_token.clear();
+        let process_start = tokio::task::spawn_blocking(move || crate::utils::process_start_time(pid))
+            .await
+            .map_err(|e| LauncherError::other(format!("Checking process {} failed: {}", pid, e)))?;
+
+        let record = DetachedRecord {
+            pid,
+            java_path: self.inner.java_path.clone(),
+            account,
+            started_at: DateTime::<Utc>::from(self.inner.started_at),
+            log_file: self.inner.options.log_file.clone(),
+            process_start,
+        };
+        let content = serde_json::to_string_pretty(&record)?;
+        tokio::fs::write(self.inner.working_dir.join(DETACHED_RECORD_FILE), content)
+            .await
+            .map_err(|e| LauncherError::file(format!("Failed to record detached process: {}", e)))
+    }
+
     /// Start the Minecraft process
     async fn start(&self) -> Result<()> {
-        let mut status = self.inner.status.write().await;
-        *status = ProcessStatus::Starting;
-        drop(status);
+        self.set_status(ProcessStatus::Starting);
 
         log::info!("Starting Minecraft process with Java: {}", self.inner.java_path.display());
         log::info!("Working directory: {}", self.inner.working_dir.display());
         // Log arguments with sensitive data redacted for debugging
         let mut debug_args = self.inner.args.clone();
+        let mut redact_next = false;
         for arg in &mut debug_args {
-            if arg.contains("accessToken") || arg.contains("access_token") {
+            if redact_next {
+                *arg = "***REDACTED***".to_string();
+                redact_next = false;
+            } else if arg == "--accessToken" {
+                redact_next = true;
+            } else if arg.contains("accessToken") || arg.contains("access_token") {
                 *arg = arg.chars().take(20).collect::<String>() + "...***REDACTED***";
             }
         }
@@ -95,10 +427,38 @@ impl MinecraftProcess {
         command
             .args(&self.inner.args)
             .current_dir(&self.inner.working_dir)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
             .stdin(Stdio::null());
 
+        if self.inner.options.detached {
+            // Pipes would break once the launcher exits, so output goes straight to a file
+            let log_path = self
+                .inner
+                .options
+                .log_file
+                .clone()
+                .unwrap_or_else(|| self.inner.working_dir.join("logs").join("launcher").join("detached.log"));
+            if let Some(parent) = log_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let output = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&log_path)
+                .map_err(|e| LauncherError::file(format!("Failed to open {}: {}", log_path.display(), e)))?;
+            command.stdout(output.try_clone()?).stderr(output);
+
+            #[cfg(unix)]
+            command.process_group(0);
+            #[cfg(windows)]
+            {
+                const DETACHED_PROCESS: u32 = 0x0000_0008;
+                const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+                command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+            }
+        } else {
+            command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        }
+
         // Set environment variables if needed
         #[cfg(target_os = "macos")]
         {
@@ -109,14 +469,38 @@ impl MinecraftProcess {
             .map_err(|e| LauncherError::launch(format!("Failed to start Minecraft process: {}", e)))?;
 
         let pid = child.id();
-        
+        if let (true, Some(pid)) = (self.inner.options.detached, pid) {
+            // The game is already running; without the record it just can't be reattached
+            if let Err(e) = self.write_detached_record(pid).await {
+                log::warn!("Failed to record detached game process {}, it cannot be reattached: {}", pid, e);
+            }
+        }
+
+        // Open the launch log before reading any output so early JVM errors are kept
+        let log_file = match &self.inner.options.log_file {
+            Some(path) if !self.inner.options.detached => {
+                let mut log_file = RotatingLogFile::create(path.clone(), &self.inner.options.log_capture).await?;
+                log_file.write_line(&format!("Java: {}", self.inner.java_path.display())).await;
+                log_file.write_line(&format!("Arguments: {:?}", debug_args)).await;
+                Some(Arc::new(tokio::sync::Mutex::new(log_file)))
+            }
+            _ => None,
+        };
+
         // Capture stdout and stderr for debugging
         if let Some(stdout) = child.stdout.take() {
             let stdout_reader = BufReader::new(stdout);
             let mut stdout_lines = stdout_reader.lines();
+            let log_file = log_file.clone();
+            let process = self.clone();
             tokio::spawn(async move {
                 while let Ok(Some(line)) = stdout_lines.next_line().await {
                     log::info!("[Minecraft STDOUT] {}", line);
+                    if let Some(log_file) = &log_file {
+                        log_file.lock().await.write_line(&line).await;
+                    }
+                    process.check_window_ready(&line);
+                    process.inner.logs.push(LogSource::Stdout, line);
                 }
             });
         }
@@ -124,18 +508,22 @@ impl MinecraftProcess {
         if let Some(stderr) = child.stderr.take() {
             let stderr_reader = BufReader::new(stderr);
             let mut stderr_lines = stderr_reader.lines();
+            let log_file = log_file.clone();
+            let process = self.clone();
             tokio::spawn(async move {
                 while let Ok(Some(line)) = stderr_lines.next_line().await {
                     log::error!("[Minecraft STDERR] {}", line);
+                    if let Some(log_file) = &log_file {
+                        log_file.lock().await.write_line(&format!("[STDERR] {}", line)).await;
+                    }
+                    process.check_window_ready(&line);
+                    process.inner.logs.push(LogSource::Stderr, line);
                 }
             });
         }
         
         // Update status and PID
-        {
-            let mut status = self.inner.status.write().await;
-            *status = ProcessStatus::Running;
-        }
+        self.set_status(ProcessStatus::Running);
         {
             let mut stored_pid = self.inner.pid.write().await;
             *stored_pid = pid;
@@ -147,10 +535,92 @@ impl MinecraftProcess {
             *stored_child = Some(child);
         }
 
+        // Watch for the game exiting on its own so exit handling runs without an explicit wait()
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            monitor.monitor_exit().await;
+        });
+
         log::info!("Minecraft process started with PID: {:?}", pid);
         Ok(())
     }
 
+    /// Poll the child until it exits, unless `wait` or `kill` takes ownership of it first
+    async fn monitor_exit(&self) {
+        if self.inner.reattached {
+            self.monitor_reattached_exit().await;
+            return;
+        }
+
+        loop {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+            let exit_status = {
+                let mut child_guard = self.inner.child.write().await;
+                let Some(child) = child_guard.as_mut() else {
+                    return;
+                };
+                match child.try_wait() {
+                    Ok(Some(exit_status)) => {
+                        child_guard.take();
+                        exit_status
+                    }
+                    Ok(None) => continue,
+                    Err(e) => {
+                        log::warn!("Failed to poll Minecraft process: {}", e);
+                        return;
+                    }
+                }
+            };
+
+            log::info!("Process exited with status: {}", exit_status);
+            self.set_status(ProcessStatus::Exited(exit_status.code().unwrap_or(-1)));
+            self.run_post_exit_command().await;
+            return;
+        }
+    }
+
+    /// Run the configured post-exit command once, recording its exit code
+    async fn run_post_exit_command(&self) {
+        if self.inner.exit_handled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        if self.inner.options.detached {
+            let _ = tokio::fs::remove_file(self.inner.working_dir.join(DETACHED_RECORD_FILE)).await;
+        }
+
+        let Some(command) = &self.inner.options.post_exit_command else {
+            return;
+        };
+
+        let exit_code = match &self.get_status() {
+            ProcessStatus::Exited(code) => code.to_string(),
+            _ => String::new(),
+        };
+        let env = [
+            ("INST_DIR", self.inner.working_dir.to_string_lossy().to_string()),
+            ("INST_JAVA", self.inner.java_path.to_string_lossy().to_string()),
+            ("INST_EXIT_CODE", exit_code),
+        ];
+
+        match crate::utils::run_hook_command(command, &self.inner.working_dir, &env).await {
+            Ok(code) => {
+                if code != 0 {
+                    log::warn!("Post-exit command exited with code {}", code);
+                }
+                let mut post_exit_code = self.inner.post_exit_code.write().await;
+                *post_exit_code = Some(code);
+            }
+            Err(e) => log::error!("Failed to run post-exit command: {}", e),
+        }
+    }
+
+    /// Get the exit code of the post-exit command, once it has run
+    pub async fn get_post_exit_code(&self) -> Option<i32> {
+        *self.inner.post_exit_code.read().await
+    }
+
     /// Get the process ID
     pub async fn get_pid(&self) -> Result<u32> {
         let pid = self.inner.pid.read().await;
@@ -159,25 +629,96 @@ impl MinecraftProcess {
 
     /// Get the current status of the process
     pub fn get_status(&self) -> ProcessStatus {
-        // This is a simplified synchronous version
-        // In practice, you might want to check if the process is still running
-        ProcessStatus::Running // Placeholder
+        self.inner.status.borrow().clone()
     }
 
     /// Get detailed status asynchronously
     pub async fn get_status_async(&self) -> ProcessStatus {
-        let status = self.inner.status.read().await;
-        status.clone()
+        self.get_status()
     }
 
-    /// Check if the process is running
+    /// Watch the status: the receiver holds the current status and is notified of every
+    /// transition (`Starting` → `Running` → `Exited`/`Killed`/`Failed`)
+    pub fn subscribe_status(&self) -> watch::Receiver<ProcessStatus> {
+        self.inner.status.subscribe()
+    }
+
+    /// Check if the process is running (or still starting)
+    ///
+    /// Exits are noticed by a background monitor, so this can lag the real exit by up to
+    /// half a second.
     pub async fn is_running(&self) -> bool {
-        let child_guard = self.inner.child.read().await;
-        if let Some(_child) = child_guard.as_ref() {
-            // Try to poll the process without blocking
-            true // Simplified - in practice you'd check child.try_wait()
+        matches!(self.get_status(), ProcessStatus::Starting | ProcessStatus::Running)
+    }
+
+    fn set_status(&self, status: ProcessStatus) {
+        self.inner.status.send_replace(status);
+    }
+
+    /// CPU and memory use of the game process, as reported by the OS
+    ///
+    /// CPU usage is averaged since the previous call, or since the game started on the first
+    /// one, so poll at the interval the figure should cover. Memory is the whole JVM's resident
+    /// size, so it is normally somewhat above the `-Xmx` heap limit.
+    pub async fn resource_usage(&self) -> Result<ResourceUsage> {
+        let pid = self.get_pid().await?;
+        let sample = tokio::task::spawn_blocking(move || crate::utils::process_sample(pid))
+            .await
+            .map_err(|e| LauncherError::process(format!("Reading resource usage of process {} failed: {}", pid, e)))?
+            .ok_or_else(|| LauncherError::process(format!("No resource usage available for process {}", pid)))?;
+
+        let now = std::time::Instant::now();
+        let previous = self
+            .inner
+            .cpu_sample
+            .lock()
+            .map_err(|_| LauncherError::process("Resource usage state is poisoned"))?
+            .replace((now, sample.cpu_time));
+        let (wall_time, cpu_time) = match previous {
+            Some((sampled_at, cpu_time)) => (now - sampled_at, sample.cpu_time.saturating_sub(cpu_time)),
+            None => (self.inner.started_at.elapsed().unwrap_or_default(), sample.cpu_time),
+        };
+        let cpu_percent = if wall_time.is_zero() {
+            0.0
         } else {
-            false
+            cpu_time.as_secs_f32() / wall_time.as_secs_f32() * 100.0
+        };
+        Ok(ResourceUsage { cpu_percent, memory_bytes: sample.memory_bytes })
+    }
+
+    /// Whether the game window has appeared, judged from the game's console output
+    ///
+    /// Detection matches log lines against [`ProcessOptions::window_ready_patterns`], so it
+    /// depends on the game version's logging. Detached games have no console to watch; they
+    /// count as ready once reattached and never otherwise.
+    pub fn is_window_ready(&self) -> bool {
+        *self.inner.window_ready.borrow()
+    }
+
+    /// Wait until the game window appears. Returns `false` if the game exits first.
+    pub async fn wait_for_window_ready(&self) -> bool {
+        let mut ready = self.inner.window_ready.subscribe();
+        let mut status = self.subscribe_status();
+        tokio::select! {
+            result = ready.wait_for(|ready| *ready) => result.is_ok(),
+            _ = status.wait_for(|status| !matches!(status, ProcessStatus::Starting | ProcessStatus::Running)) => {
+                self.is_window_ready()
+            }
+        }
+    }
+
+    /// Mark the window as ready the first time a console line matches a ready pattern
+    fn check_window_ready(&self, line: &str) {
+        if self.is_window_ready() {
+            return;
+        }
+        let matches = match &self.inner.options.window_ready_patterns {
+            Some(patterns) => patterns.iter().any(|pattern| line.contains(pattern.as_str())),
+            None => DEFAULT_WINDOW_READY_PATTERNS.iter().any(|pattern| line.contains(pattern)),
+        };
+        if matches {
+            log::info!("Game window is ready");
+            self.inner.window_ready.send_replace(true);
         }
     }
 
@@ -186,6 +727,22 @@ impl MinecraftProcess {
         log::info!("Killing Minecraft process");
 
         let mut child_guard = self.inner.child.write().await;
+        if self.inner.reattached {
+            drop(child_guard);
+            let pid = self.get_pid().await?;
+            // The game may have exited and its PID been given to an unrelated process since
+            if self.reattached_process_running(pid).await {
+                tokio::task::spawn_blocking(move || crate::utils::terminate_process(pid))
+                    .await
+                    .map_err(|e| LauncherError::process(format!("Failed to terminate process {}: {}", pid, e)))??;
+            } else {
+                log::info!("Reattached process {} already exited", pid);
+            }
+            self.set_status(ProcessStatus::Killed);
+            *self.inner.pid.write().await = None;
+            self.run_post_exit_command().await;
+            return Ok(());
+        }
         if let Some(mut child) = child_guard.take() {
             // Try graceful shutdown first
             if let Err(e) = child.kill().await {
@@ -195,24 +752,18 @@ impl MinecraftProcess {
             // Wait for the process to exit
             match child.wait().await {
                 Ok(exit_status) => {
-                    let mut status = self.inner.status.write().await;
-                    if exit_status.success() {
-                        *status = ProcessStatus::Exited(0);
-                    } else {
-                        let code = exit_status.code().unwrap_or(-1);
-                        *status = ProcessStatus::Exited(code);
-                    }
+                    self.set_status(ProcessStatus::Exited(exit_status.code().unwrap_or(-1)));
                     log::info!("Process exited with status: {}", exit_status);
                 }
                 Err(e) => {
-                    let mut status = self.inner.status.write().await;
-                    *status = ProcessStatus::Failed(format!("Wait failed: {}", e));
+                    self.set_status(ProcessStatus::Failed(format!("Wait failed: {}", e)));
                     log::error!("Failed to wait for process: {}", e);
                 }
             }
         } else {
             return Err(LauncherError::process("No process to kill"));
         }
+        drop(child_guard);
 
         // Clear PID
         {
@@ -220,6 +771,7 @@ impl MinecraftProcess {
             *pid = None;
         }
 
+        self.run_post_exit_command().await;
         Ok(())
     }
 
@@ -235,21 +787,38 @@ impl MinecraftProcess {
                         ProcessStatus::Exited(exit_status.code().unwrap_or(-1))
                     };
 
-                    let mut stored_status = self.inner.status.write().await;
-                    *stored_status = status.clone();
+                    self.set_status(status.clone());
 
+                    drop(child_guard);
+                    self.run_post_exit_command().await;
                     Ok(status)
                 }
                 Err(e) => {
-                    let error_status = ProcessStatus::Failed(format!("Wait failed: {}", e));
-                    let mut stored_status = self.inner.status.write().await;
-                    *stored_status = error_status.clone();
-                    
+                    self.set_status(ProcessStatus::Failed(format!("Wait failed: {}", e)));
+
                     Err(LauncherError::process(format!("Failed to wait for process: {}", e)))
                 }
             }
         } else {
-            Err(LauncherError::process("No process to wait for"))
+            drop(child_guard);
+            if self.inner.reattached {
+                // Only the exit monitor can tell when a reattached process is gone
+                let mut status = self.subscribe_status();
+                let _ = status
+                    .wait_for(|status| !matches!(status, ProcessStatus::Starting | ProcessStatus::Running))
+                    .await;
+            }
+
+            // The exit monitor may already have reaped the process
+            match self.get_status() {
+                ProcessStatus::Starting | ProcessStatus::Running => {
+                    Err(LauncherError::process("No process to wait for"))
+                }
+                status => {
+                    self.run_post_exit_command().await;
+                    Ok(status)
+                }
+            }
         }
     }
 
@@ -289,6 +858,11 @@ impl MinecraftProcess {
         self.inner.working_dir.join("logs").join("latest.log")
     }
 
+    /// Get the path of the launcher-captured stdout/stderr log, if capture is enabled
+    pub fn get_launch_log_path(&self) -> Option<&PathBuf> {
+        self.inner.options.log_file.as_ref()
+    }
+
     /// Read the latest log file
     pub async fn read_logs(&self) -> Result<String> {
         let log_path = self.get_log_path();
@@ -302,18 +876,128 @@ impl MinecraftProcess {
         }
     }
 
+    /// Stream the game's output as one chronological feed: console lines from the first JVM
+    /// message on (including ones printed before this call), plus lines that only appear in
+    /// `latest.log`. Lines printed to both are sent once. The stream ends after the game exits.
+    pub fn log_stream(&self) -> mpsc::Receiver<LogLine> {
+        let (sender, receiver) = mpsc::channel(256);
+        let process = self.clone();
+        tokio::spawn(async move {
+            process.forward_logs(sender).await;
+        });
+        receiver
+    }
+
+    async fn forward_logs(&self, sender: mpsc::Sender<LogLine>) {
+        let (history, mut live) = self.inner.logs.subscribe();
+        let mut console_lines = RecentLines::default();
+        for line in history {
+            console_lines.insert(&line.line);
+            if sender.send(line).await.is_err() {
+                return;
+            }
+        }
+
+        let log_path = self.get_log_path();
+        let mut offset = 0;
+        let mut partial = String::new();
+        let mut interval = tokio::time::interval(LOG_TAIL_INTERVAL);
+        loop {
+            tokio::select! {
+                received = live.recv() => match received {
+                    Ok(line) => {
+                        console_lines.insert(&line.line);
+                        if sender.send(line).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!("Log stream fell behind, skipped {} lines", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                },
+                _ = interval.tick() => {
+                    let exited = !matches!(self.get_status_async().await, ProcessStatus::Starting | ProcessStatus::Running);
+                    if exited {
+                        // Console lines still queued were printed before the file's last lines
+                        while let Ok(line) = live.try_recv() {
+                            console_lines.insert(&line.line);
+                            if sender.send(line).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+
+                    for line in self.read_new_log_lines(&log_path, &mut offset, &mut partial).await {
+                        if console_lines.take(&line) {
+                            continue;
+                        }
+                        let line = LogLine { source: LogSource::LatestLog, line, timestamp: Utc::now() };
+                        if sender.send(line).await.is_err() {
+                            return;
+                        }
+                    }
+
+                    if exited {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Read complete lines appended to `latest.log` since `offset`, ignoring a file left over
+    /// from before this process started
+    async fn read_new_log_lines(&self, path: &Path, offset: &mut u64, partial: &mut String) -> Vec<String> {
+        let Ok(metadata) = tokio::fs::metadata(path).await else {
+            return Vec::new();
+        };
+        if metadata.modified().is_ok_and(|modified| modified < self.inner.started_at) {
+            return Vec::new();
+        }
+        if metadata.len() < *offset {
+            // The game rotated the file
+            *offset = 0;
+            partial.clear();
+        }
+        if metadata.len() == *offset {
+            return Vec::new();
+        }
+
+        let mut buffer = Vec::new();
+        let read = async {
+            let mut file = tokio::fs::File::open(path).await?;
+            file.seek(std::io::SeekFrom::Start(*offset)).await?;
+            file.read_to_end(&mut buffer).await
+        };
+        if let Err(e) = read.await {
+            log::debug!("Failed to read {}: {}", path.display(), e);
+            return Vec::new();
+        }
+        *offset += buffer.len() as u64;
+
+        partial.push_str(&String::from_utf8_lossy(&buffer));
+        let Some(last_newline) = partial.rfind('\n') else {
+            return Vec::new();
+        };
+        let complete: String = partial.drain(..=last_newline).collect();
+        complete.lines().map(|line| line.trim_end_matches('\r').to_string()).collect()
+    }
+
     /// Get crash reports directory
     pub fn get_crash_reports_dir(&self) -> PathBuf {
         self.inner.working_dir.join("crash-reports")
     }
 
-    /// List available crash reports
+    /// List available crash reports, newest first. A missing crash-reports directory means none.
     pub async fn list_crash_reports(&self) -> Result<Vec<PathBuf>> {
         let crash_dir = self.get_crash_reports_dir();
         
-        let mut entries = tokio::fs::read_dir(&crash_dir)
-            .await
-            .map_err(|e| LauncherError::file(format!("Failed to read crash reports directory: {}", e)))?;
+        let mut entries = match tokio::fs::read_dir(&crash_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(LauncherError::file(format!("Failed to read crash reports directory: {}", e))),
+        };
 
         let mut crash_reports = Vec::new();
         
@@ -353,3 +1037,146 @@ impl MinecraftProcess {
         }
     }
 }
+
+/// Log file that rotates to numbered backups once it exceeds a size limit
+struct RotatingLogFile {
+    path: PathBuf,
+    file: tokio::fs::File,
+    written: u64,
+    max_file_size: u64,
+    max_files: usize,
+}
+
+impl RotatingLogFile {
+    async fn create(path: PathBuf, config: &LogCaptureConfig) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| LauncherError::file(format!("Failed to create log directory {}: {}", parent.display(), e)))?;
+        }
+
+        let file = tokio::fs::File::create(&path)
+            .await
+            .map_err(|e| LauncherError::file(format!("Failed to create log file {}: {}", path.display(), e)))?;
+
+        Ok(Self {
+            path,
+            file,
+            written: 0,
+            max_file_size: config.max_file_size,
+            max_files: config.max_files.max(1),
+        })
+    }
+
+    /// Append a line, rotating first if the file is full. Failures are logged, not returned,
+    /// so a full disk never interrupts the game's output pipes.
+    async fn write_line(&mut self, line: &str) {
+        if self.written > 0 && self.written + line.len() as u64 + 1 > self.max_file_size {
+            if let Err(e) = self.rotate().await {
+                log::warn!("Failed to rotate launch log {}: {}", self.path.display(), e);
+            }
+        }
+
+        let entry = format!("{}\n", line);
+        match self.file.write_all(entry.as_bytes()).await {
+            Ok(()) => {
+                self.written += entry.len() as u64;
+                let _ = self.file.flush().await;
+            }
+            Err(e) => log::warn!("Failed to write launch log {}: {}", self.path.display(), e),
+        }
+    }
+
+    /// Shift `name.log.N-1` to `name.log.N` (dropping the oldest) and start a fresh file
+    async fn rotate(&mut self) -> Result<()> {
+        let _ = self.file.flush().await;
+
+        let backup = |index: usize| PathBuf::from(format!("{}.{}", self.path.display(), index));
+        let _ = tokio::fs::remove_file(backup(self.max_files)).await;
+        for index in (1..self.max_files).rev() {
+            let from = backup(index);
+            if from.exists() {
+                tokio::fs::rename(&from, backup(index + 1)).await?;
+            }
+        }
+        tokio::fs::rename(&self.path, backup(1)).await?;
+
+        self.file = tokio::fs::File::create(&self.path).await?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_record(dir: &Path, process_start: Option<String>) {
+        let record = DetachedRecord {
+            pid: std::process::id(),
+            java_path: PathBuf::from("java"),
+            account: Account {
+                uuid: "00000000-0000-0000-0000-000000000001".to_string(),
+                name: "Steve".to_string(),
+                access_token: String::new(),
+                refresh_token: String::new(),
+                expires_at: Utc::now(),
+                account_type: crate::auth::AccountType::Offline,
+                profile: crate::auth::ProfileInfo {
+                    id: "00000000-0000-0000-0000-000000000001".to_string(),
+                    name: "Steve".to_string(),
+                    skins: Vec::new(),
+                    capes: Vec::new(),
+                },
+                xuid: None,
+                client_id: None,
+            },
+            started_at: Utc::now(),
+            log_file: None,
+            process_start,
+        };
+        std::fs::write(dir.join(DETACHED_RECORD_FILE), serde_json::to_string(&record).unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn stale_record_for_a_reused_pid_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // The PID is alive (it's this test), but the process started at another time
+        write_record(dir.path(), Some("1".to_string()));
+        assert!(MinecraftProcess::reattach(dir.path().to_path_buf()).await.unwrap().is_none());
+        assert!(!dir.path().join(DETACHED_RECORD_FILE).exists());
+
+        write_record(dir.path(), None);
+        assert_eq!(running_detached_pid(dir.path()), None);
+        assert!(!dir.path().join(DETACHED_RECORD_FILE).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn matching_record_is_running() {
+        let dir = tempfile::tempdir().unwrap();
+        write_record(dir.path(), crate::utils::process_start_time(std::process::id()));
+        assert_eq!(running_detached_pid(dir.path()), Some(std::process::id()));
+        assert!(dir.path().join(DETACHED_RECORD_FILE).exists());
+    }
+
+    #[tokio::test]
+    async fn rotating_log_shifts_backups_and_drops_the_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("launch.log");
+        let config = LogCaptureConfig { max_file_size: 15, max_files: 2 };
+        let mut log_file = RotatingLogFile::create(path.clone(), &config).await.unwrap();
+
+        // Every line is 11 bytes with its newline, so each write after the first rotates
+        for line in ["line1-aaaa", "line2-bbbb", "line3-cccc", "line4-dddd"] {
+            log_file.write_line(line).await;
+        }
+
+        let read = |suffix: &str| std::fs::read_to_string(format!("{}{}", path.display(), suffix)).unwrap();
+        assert_eq!(read(""), "line4-dddd\n");
+        assert_eq!(read(".1"), "line3-cccc\n");
+        assert_eq!(read(".2"), "line2-bbbb\n");
+        assert!(!dir.path().join("launch.log.3").exists());
+    }
+}
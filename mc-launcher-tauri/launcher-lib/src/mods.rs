@@ -0,0 +1,746 @@
+//! Installed mod inspection (metadata parsing, duplicate and dependency checks)
+
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::error::{LauncherError, Result};
+use crate::version::{compare_maven_versions, ModLoaderType};
+
+/// Dependency IDs provided by the game or the loader itself rather than by another mod
+const BUILTIN_DEPENDENCIES: &[&str] = &[
+    "minecraft", "java", "fabricloader", "fabric-loader", "quilt_loader", "forge", "neoforge", "fml",
+];
+
+/// How deep nested (jar-in-jar) Fabric mods are followed
+const MAX_NESTED_DEPTH: usize = 3;
+
+/// A mod jar found in an instance's `mods/` directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModInfo {
+    /// Path to the jar
+    pub path: PathBuf,
+    /// File name of the jar
+    pub file_name: String,
+    /// Mod ID from the jar's metadata, if it has any
+    pub id: Option<String>,
+    /// Display name
+    pub name: Option<String>,
+    /// Mod version
+    pub version: Option<String>,
+    /// Loaders the jar declares metadata for
+    pub loaders: Vec<ModLoaderType>,
+    /// Other mod IDs this jar provides (aliases, extra mods, bundled jar-in-jar mods)
+    pub provides: Vec<String>,
+    /// Declared dependencies
+    pub dependencies: Vec<ModDependency>,
+    /// False for `.jar.disabled` files
+    pub enabled: bool,
+    /// File size in bytes
+    pub size: u64,
+}
+
+impl ModInfo {
+    /// Mod ID, or the file name for jars without metadata
+    pub fn display_id(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.file_name)
+    }
+}
+
+/// A dependency declared by a mod
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModDependency {
+    /// ID of the required mod
+    pub id: String,
+    /// Version requirement as written in the metadata
+    pub version_requirement: Option<String>,
+    /// Whether the mod refuses to load without it
+    pub required: bool,
+}
+
+/// A problem found among the installed mods
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ModIssue {
+    /// The same mod ID is installed more than once
+    DuplicateMod { mod_id: String, files: Vec<PathBuf> },
+    /// A required dependency is not installed
+    MissingDependency {
+        mod_id: String,
+        dependency: String,
+        version_requirement: Option<String>,
+    },
+    /// A required dependency is installed, but in a version outside the declared range
+    DependencyVersionMismatch {
+        mod_id: String,
+        dependency: String,
+        version_requirement: String,
+        installed_version: String,
+    },
+    /// The mod was built for a different loader than the instance uses
+    IncompatibleLoader {
+        mod_id: String,
+        file: PathBuf,
+        mod_loaders: Vec<ModLoaderType>,
+        instance_loader: ModLoaderType,
+    },
+}
+
+impl std::fmt::Display for ModIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModIssue::DuplicateMod { mod_id, files } => {
+                write!(f, "{} is installed {} times", mod_id, files.len())
+            }
+            ModIssue::MissingDependency { mod_id, dependency, version_requirement } => match version_requirement {
+                Some(requirement) => write!(f, "{} requires {} {}", mod_id, dependency, requirement),
+                None => write!(f, "{} requires {}", mod_id, dependency),
+            },
+            ModIssue::DependencyVersionMismatch { mod_id, dependency, version_requirement, installed_version } => {
+                write!(f, "{} requires {} {}, but {} is installed", mod_id, dependency, version_requirement, installed_version)
+            }
+            ModIssue::IncompatibleLoader { mod_id, instance_loader, .. } => {
+                write!(f, "{} does not support {}", mod_id, instance_loader)
+            }
+        }
+    }
+}
+
+/// Read metadata from every `.jar` and `.jar.disabled` in `mods_dir`
+pub(crate) fn list_mods(mods_dir: &Path) -> Result<Vec<ModInfo>> {
+    let mut mods = Vec::new();
+    if !mods_dir.exists() {
+        return Ok(mods);
+    }
+
+    let entries = std::fs::read_dir(mods_dir)
+        .map_err(|e| LauncherError::file(format!("Failed to read mods directory {}: {}", mods_dir.display(), e)))?;
+    for entry in entries {
+        let path = entry?.path();
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let enabled = if file_name.ends_with(".jar") {
+            true
+        } else if file_name.ends_with(".jar.disabled") {
+            false
+        } else {
+            continue;
+        };
+        if !path.is_file() {
+            continue;
+        }
+
+        let mut info = ModInfo {
+            size: std::fs::metadata(&path)?.len(),
+            path,
+            file_name,
+            id: None,
+            name: None,
+            version: None,
+            loaders: Vec::new(),
+            provides: Vec::new(),
+            dependencies: Vec::new(),
+            enabled,
+        };
+
+        if let Err(e) = read_mod_metadata(&mut info) {
+            log::warn!("Could not read mod metadata from {}: {}", info.file_name, e);
+        }
+        mods.push(info);
+    }
+
+    mods.sort_by_key(|m| m.file_name.to_lowercase());
+    Ok(mods)
+}
+
+/// Find duplicate mods, missing or mismatched required dependencies and mods built for another loader
+pub fn detect_mod_issues(mods: &[ModInfo], instance_loader: Option<&ModLoaderType>) -> Vec<ModIssue> {
+    let enabled: Vec<&ModInfo> = mods.iter().filter(|m| m.enabled).collect();
+    let mut issues = Vec::new();
+
+    let mut files_by_id: HashMap<&str, Vec<PathBuf>> = HashMap::new();
+    for info in &enabled {
+        if let Some(id) = &info.id {
+            files_by_id.entry(id).or_default().push(info.path.clone());
+        }
+    }
+    let mut duplicates: Vec<_> = files_by_id.into_iter().filter(|(_, files)| files.len() > 1).collect();
+    duplicates.sort_by_key(|(mod_id, _)| *mod_id);
+    for (mod_id, files) in duplicates {
+        issues.push(ModIssue::DuplicateMod { mod_id: mod_id.to_string(), files });
+    }
+
+    let available: HashSet<&str> = enabled
+        .iter()
+        .flat_map(|m| m.id.iter().chain(m.provides.iter()))
+        .map(String::as_str)
+        .collect();
+
+    let mut installed_versions: HashMap<&str, &str> = HashMap::new();
+    for info in &enabled {
+        if let (Some(id), Some(version)) = (&info.id, &info.version) {
+            installed_versions.entry(id).or_insert(version);
+        }
+    }
+
+    for info in &enabled {
+        let mod_id = info.display_id().to_string();
+
+        for dependency in info.dependencies.iter().filter(|d| d.required) {
+            if BUILTIN_DEPENDENCIES.contains(&dependency.id.as_str()) {
+                continue;
+            }
+            if available.contains(dependency.id.as_str()) {
+                // Versions of provided aliases are unknown, so only real IDs are range-checked
+                let installed = installed_versions.get(dependency.id.as_str());
+                if let (Some(requirement), Some(installed)) = (&dependency.version_requirement, installed) {
+                    if version_satisfies(installed, requirement) == Some(false) {
+                        issues.push(ModIssue::DependencyVersionMismatch {
+                            mod_id: mod_id.clone(),
+                            dependency: dependency.id.clone(),
+                            version_requirement: requirement.clone(),
+                            installed_version: installed.to_string(),
+                        });
+                    }
+                }
+                continue;
+            }
+            issues.push(ModIssue::MissingDependency {
+                mod_id: mod_id.clone(),
+                dependency: dependency.id.clone(),
+                version_requirement: dependency.version_requirement.clone(),
+            });
+        }
+
+        if let Some(instance_loader) = instance_loader {
+            if !info.loaders.is_empty() && !info.loaders.iter().any(|loader| loader_runs_on(loader, instance_loader)) {
+                issues.push(ModIssue::IncompatibleLoader {
+                    mod_id,
+                    file: info.path.clone(),
+                    mod_loaders: info.loaders.clone(),
+                    instance_loader: instance_loader.clone(),
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+/// Whether `version` meets a Forge Maven range (`[1.2,2.0)`) or a Fabric/Quilt requirement
+/// (`>=1.2 <2`, `^1.2`, `~1.2`, `1.20.x`, alternatives joined by `||`).
+/// `None` when the requirement uses syntax this doesn't understand.
+fn version_satisfies(version: &str, requirement: &str) -> Option<bool> {
+    let requirement = requirement.trim();
+    if requirement.starts_with('[') || requirement.starts_with('(') {
+        return maven_range_satisfied(version, requirement);
+    }
+
+    let mut any_known = false;
+    for alternative in requirement.split("||") {
+        let mut satisfied = true;
+        for constraint in alternative.split_whitespace() {
+            satisfied &= semver_constraint_satisfied(version, constraint)?;
+        }
+        any_known = true;
+        if satisfied {
+            return Some(true);
+        }
+    }
+    any_known.then_some(false)
+}
+
+/// One or more comma-separated Maven ranges such as `[1.0,2.0)`, `[47,)` or `[1.20.1]`
+fn maven_range_satisfied(version: &str, ranges: &str) -> Option<bool> {
+    use std::cmp::Ordering::*;
+
+    let mut rest = ranges.trim();
+    while !rest.is_empty() {
+        let end = rest.find([']', ')'])?;
+        let (range, close) = (&rest[1..end], &rest[end..=end]);
+        let lower_inclusive = rest.starts_with('[');
+        let upper_inclusive = close == "]";
+
+        let satisfied = match range.split_once(',') {
+            None => compare_maven_versions(version, range.trim()) == Equal,
+            Some((lower, upper)) => {
+                let (lower, upper) = (lower.trim(), upper.trim());
+                let above = lower.is_empty()
+                    || match compare_maven_versions(version, lower) {
+                        Greater => true,
+                        Equal => lower_inclusive,
+                        Less => false,
+                    };
+                let below = upper.is_empty()
+                    || match compare_maven_versions(version, upper) {
+                        Less => true,
+                        Equal => upper_inclusive,
+                        Greater => false,
+                    };
+                above && below
+            }
+        };
+        if satisfied {
+            return Some(true);
+        }
+
+        rest = rest[end + 1..].trim_start().trim_start_matches(',').trim_start();
+        if !rest.is_empty() && !rest.starts_with(['[', '(']) {
+            return None;
+        }
+    }
+    Some(false)
+}
+
+/// A single Fabric-style constraint such as `>=1.2`, `^1.2`, `~1.2`, `1.20.x` or `*`
+fn semver_constraint_satisfied(version: &str, constraint: &str) -> Option<bool> {
+    use std::cmp::Ordering::*;
+
+    // Build metadata doesn't take part in comparisons
+    let version = version.split('+').next().unwrap_or(version);
+    let (operator, target) = match constraint.find(|c: char| c.is_ascii_alphanumeric() || c == '*') {
+        Some(start) => constraint.split_at(start),
+        None => return None,
+    };
+    let target = target.split('+').next().unwrap_or(target);
+
+    if target == "*" || target.eq_ignore_ascii_case("x") {
+        return Some(operator.is_empty() || operator == "=");
+    }
+    if let Some(prefix) = target.strip_suffix(".x").or_else(|| target.strip_suffix(".*")) {
+        if !operator.is_empty() && operator != "=" {
+            return None;
+        }
+        return Some(version == prefix || version.starts_with(&format!("{}.", prefix)));
+    }
+
+    let ordering = compare_maven_versions(version, target);
+    let numbers: Vec<u64> = target.split('.').map_while(|part| part.parse().ok()).collect();
+    Some(match operator {
+        "" | "=" => ordering == Equal,
+        ">=" => ordering != Less,
+        ">" => ordering == Greater,
+        "<=" => ordering != Greater,
+        "<" => ordering == Less,
+        // Same major (or minor, for `~`) version, at least `target`
+        "^" | "~" => {
+            let bump_index = if operator == "^" { 0 } else { 1.min(numbers.len().saturating_sub(1)) };
+            let bumped = *numbers.get(bump_index)?;
+            let mut upper: Vec<String> = numbers[..bump_index].iter().map(u64::to_string).collect();
+            upper.push((bumped + 1).to_string());
+            ordering != Less && compare_maven_versions(version, &upper.join(".")) == Less
+        }
+        _ => return None,
+    })
+}
+
+/// Whether mods written for `mod_loader` load on `instance_loader`
+fn loader_runs_on(mod_loader: &ModLoaderType, instance_loader: &ModLoaderType) -> bool {
+    use ModLoaderType::*;
+    match mod_loader {
+        // Quilt loads Fabric mods; NeoForge started as a Forge fork and 1.20.1 still loads Forge mods
+        Fabric => matches!(instance_loader, Fabric | Quilt | LegacyFabric),
+        Forge => matches!(instance_loader, Forge | NeoForge),
+        other => other == instance_loader,
+    }
+}
+
+fn read_mod_metadata(info: &mut ModInfo) -> Result<()> {
+    let file = std::fs::File::open(&info.path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+
+    if let Some(content) = read_zip_entry(&mut archive, "fabric.mod.json") {
+        let json: serde_json::Value = serde_json::from_str(&content)?;
+        apply_fabric_metadata(info, &json);
+        info.loaders.push(ModLoaderType::Fabric);
+        info.provides.extend(nested_fabric_ids(&mut archive, &json, 1));
+    }
+
+    if let Some(content) = read_zip_entry(&mut archive, "quilt.mod.json") {
+        let json: serde_json::Value = serde_json::from_str(&content)?;
+        apply_quilt_metadata(info, &json);
+        info.loaders.push(ModLoaderType::Quilt);
+    }
+
+    let neoforge_toml = read_zip_entry(&mut archive, "META-INF/neoforge.mods.toml");
+    let is_neoforge_toml = neoforge_toml.is_some();
+    if let Some(content) = neoforge_toml.or_else(|| read_zip_entry(&mut archive, "META-INF/mods.toml")) {
+        let jar_version = read_zip_entry(&mut archive, "META-INF/MANIFEST.MF")
+            .and_then(|manifest| manifest_value(&manifest, "Implementation-Version"));
+        let depends_on_neoforge = apply_forge_metadata(info, &content, jar_version.as_deref());
+        info.loaders.push(if is_neoforge_toml || depends_on_neoforge {
+            ModLoaderType::NeoForge
+        } else {
+            ModLoaderType::Forge
+        });
+    }
+
+    Ok(())
+}
+
+fn read_zip_entry<R: Read + std::io::Seek>(archive: &mut zip::ZipArchive<R>, name: &str) -> Option<String> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+fn apply_fabric_metadata(info: &mut ModInfo, json: &serde_json::Value) {
+    set_if_missing(&mut info.id, json_str(json, "id"));
+    set_if_missing(&mut info.name, json_str(json, "name"));
+    set_if_missing(&mut info.version, json_str(json, "version"));
+
+    if let Some(provides) = json.get("provides").and_then(|p| p.as_array()) {
+        info.provides.extend(provides.iter().filter_map(|p| p.as_str().map(String::from)));
+    }
+
+    if let Some(depends) = json.get("depends").and_then(|d| d.as_object()) {
+        for (id, requirement) in depends {
+            info.dependencies.push(ModDependency {
+                id: id.clone(),
+                version_requirement: version_requirement(requirement),
+                required: true,
+            });
+        }
+    }
+}
+
+fn apply_quilt_metadata(info: &mut ModInfo, json: &serde_json::Value) {
+    let Some(loader) = json.get("quilt_loader") else {
+        return;
+    };
+
+    set_if_missing(&mut info.id, json_str(loader, "id"));
+    set_if_missing(&mut info.version, json_str(loader, "version"));
+    set_if_missing(&mut info.name, loader.get("metadata").and_then(|m| json_str(m, "name")));
+
+    if let Some(provides) = loader.get("provides").and_then(|p| p.as_array()) {
+        info.provides.extend(provides.iter().filter_map(|p| {
+            p.as_str().map(String::from).or_else(|| json_str(p, "id"))
+        }));
+    }
+
+    if let Some(depends) = loader.get("depends").and_then(|d| d.as_array()) {
+        for dependency in depends {
+            let (id, version_requirement, required) = match dependency {
+                serde_json::Value::String(id) => (id.clone(), None, true),
+                object => match json_str(object, "id") {
+                    Some(id) => (
+                        id,
+                        object.get("versions").and_then(version_requirement),
+                        !object.get("optional").and_then(|o| o.as_bool()).unwrap_or(false),
+                    ),
+                    None => continue,
+                },
+            };
+            // Quilt IDs may be namespaced as `maven.group:id`
+            let id = id.rsplit(':').next().unwrap_or(&id).to_string();
+            info.dependencies.push(ModDependency { id, version_requirement, required });
+        }
+    }
+}
+
+/// Apply `mods.toml` metadata; returns true if any mod in it depends on NeoForge
+fn apply_forge_metadata(info: &mut ModInfo, content: &str, jar_version: Option<&str>) -> bool {
+    let mut depends_on_neoforge = false;
+
+    for (table, values) in parse_mods_toml(content) {
+        if table == "mods" {
+            let Some(mod_id) = values.get("modId").cloned() else {
+                continue;
+            };
+            if info.id.is_none() {
+                info.id = Some(mod_id);
+                info.name = values.get("displayName").cloned();
+                info.version = values.get("version").map(|version| match jar_version {
+                    Some(jar_version) => version.replace("${file.jarVersion}", jar_version),
+                    None => version.clone(),
+                });
+            } else if info.id.as_deref() != Some(mod_id.as_str()) {
+                info.provides.push(mod_id);
+            }
+        } else if table.starts_with("dependencies.") {
+            let Some(id) = values.get("modId").cloned() else {
+                continue;
+            };
+            depends_on_neoforge |= id == "neoforge";
+
+            // Forge uses `mandatory`, NeoForge uses `type` and defaults to required
+            let required = match (values.get("mandatory"), values.get("type")) {
+                (Some(mandatory), _) => mandatory == "true",
+                (None, Some(kind)) => kind.eq_ignore_ascii_case("required"),
+                (None, None) => true,
+            };
+            info.dependencies.push(ModDependency {
+                id,
+                version_requirement: values.get("versionRange").cloned(),
+                required,
+            });
+        }
+    }
+
+    depends_on_neoforge
+}
+
+/// Collect the IDs (and provided aliases) of Fabric mods bundled as jar-in-jar
+fn nested_fabric_ids<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    json: &serde_json::Value,
+    depth: usize,
+) -> Vec<String> {
+    let mut ids = Vec::new();
+    if depth > MAX_NESTED_DEPTH {
+        return ids;
+    }
+
+    let Some(jars) = json.get("jars").and_then(|j| j.as_array()) else {
+        return ids;
+    };
+
+    for file in jars.iter().filter_map(|jar| json_str(jar, "file")) {
+        let mut bytes = Vec::new();
+        match archive.by_name(&file) {
+            Ok(mut entry) => {
+                if entry.read_to_end(&mut bytes).is_err() {
+                    continue;
+                }
+            }
+            Err(_) => continue,
+        }
+
+        let Ok(mut nested) = zip::ZipArchive::new(std::io::Cursor::new(bytes)) else {
+            continue;
+        };
+        let Some(nested_json) = read_zip_entry(&mut nested, "fabric.mod.json")
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        else {
+            continue;
+        };
+
+        ids.extend(json_str(&nested_json, "id"));
+        if let Some(provides) = nested_json.get("provides").and_then(|p| p.as_array()) {
+            ids.extend(provides.iter().filter_map(|p| p.as_str().map(String::from)));
+        }
+        ids.extend(nested_fabric_ids(&mut nested, &nested_json, depth + 1));
+    }
+
+    ids
+}
+
+/// Minimal reader for the TOML subset used by `mods.toml`: table headers and `key = value` pairs.
+/// Returns one entry per table, in file order; keys before the first header use the table name "".
+fn parse_mods_toml(content: &str) -> Vec<(String, HashMap<String, String>)> {
+    let mut tables = vec![(String::new(), HashMap::new())];
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            let name = line.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+            tables.push((name, HashMap::new()));
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().trim_matches('"').to_string();
+        let value = value.trim();
+
+        let value = if let Some(delimiter) = ["'''", "\"\"\""].into_iter().find(|d| value.starts_with(d)) {
+            // Multi-line string (usually a description); keep its text but skip to the closing delimiter
+            let mut text = value[3..].to_string();
+            if let Some(end) = text.find(delimiter) {
+                text.truncate(end);
+            } else {
+                for next in lines.by_ref() {
+                    if let Some(end) = next.find(delimiter) {
+                        text.push('\n');
+                        text.push_str(&next[..end]);
+                        break;
+                    }
+                    text.push('\n');
+                    text.push_str(next);
+                }
+            }
+            text
+        } else if let Some(rest) = value.strip_prefix('"') {
+            rest.split('"').next().unwrap_or_default().to_string()
+        } else if let Some(rest) = value.strip_prefix('\'') {
+            rest.split('\'').next().unwrap_or_default().to_string()
+        } else {
+            value.split('#').next().unwrap_or_default().trim().to_string()
+        };
+
+        if let Some((_, values)) = tables.last_mut() {
+            values.insert(key, value);
+        }
+    }
+
+    tables
+}
+
+/// Read a `Key: value` line from a jar manifest
+fn manifest_value(manifest: &str, key: &str) -> Option<String> {
+    manifest
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim() == key)
+        .map(|(_, value)| value.trim().to_string())
+}
+
+fn json_str(json: &serde_json::Value, key: &str) -> Option<String> {
+    json.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+/// Render a Fabric/Quilt version requirement (a string or a list of alternatives)
+fn version_requirement(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(requirement) if requirement != "*" => Some(requirement.clone()),
+        serde_json::Value::Array(alternatives) => {
+            let alternatives: Vec<&str> = alternatives.iter().filter_map(|a| a.as_str()).collect();
+            (!alternatives.is_empty()).then(|| alternatives.join(" || "))
+        }
+        _ => None,
+    }
+}
+
+fn set_if_missing(field: &mut Option<String>, value: Option<String>) {
+    if field.is_none() {
+        *field = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mod_info(id: &str, version: &str, loaders: Vec<ModLoaderType>, dependencies: Vec<ModDependency>) -> ModInfo {
+        ModInfo {
+            path: PathBuf::from(format!("mods/{}.jar", id)),
+            file_name: format!("{}.jar", id),
+            id: Some(id.to_string()),
+            name: None,
+            version: Some(version.to_string()),
+            loaders,
+            provides: Vec::new(),
+            dependencies,
+            enabled: true,
+            size: 0,
+        }
+    }
+
+    fn requires(id: &str, range: Option<&str>) -> ModDependency {
+        ModDependency { id: id.to_string(), version_requirement: range.map(String::from), required: true }
+    }
+
+    #[test]
+    fn reads_mods_toml() {
+        let toml = r#"
+modLoader = "javafml"
+loaderVersion = "[47,)"
+
+[[mods]]
+modId = "examplemod"
+version = "${file.jarVersion}"
+displayName = "Example Mod" # trailing comment
+description = '''
+Spans
+several lines
+'''
+
+[[dependencies.examplemod]]
+modId = "forge"
+mandatory = true
+versionRange = "[47,)"
+
+[[dependencies.examplemod]]
+modId = "jei"
+mandatory = false
+versionRange = "[15.0,16.0)"
+"#;
+        let mut info = mod_info("placeholder", "", Vec::new(), Vec::new());
+        info.id = None;
+        assert!(!apply_forge_metadata(&mut info, toml, Some("1.2.3")));
+        assert_eq!(info.id.as_deref(), Some("examplemod"));
+        assert_eq!(info.name.as_deref(), Some("Example Mod"));
+        assert_eq!(info.version.as_deref(), Some("1.2.3"));
+        assert_eq!(info.dependencies.len(), 2);
+        assert!(info.dependencies[0].required);
+        assert!(!info.dependencies[1].required);
+        assert_eq!(info.dependencies[1].version_requirement.as_deref(), Some("[15.0,16.0)"));
+
+        let tables = parse_mods_toml(toml);
+        assert_eq!(tables[0].1.get("loaderVersion").map(String::as_str), Some("[47,)"));
+        assert!(tables[1].1.get("description").unwrap().contains("several lines"));
+    }
+
+    #[test]
+    fn malformed_mods_toml_is_skipped_without_panicking() {
+        let toml = "[[mods]\nmodId\n= \"broken\"\ndisplayName = \"unterminated\n[[dependencies.x]]\nversionRange = '''never closed";
+        let mut info = mod_info("placeholder", "", Vec::new(), Vec::new());
+        info.id = None;
+        apply_forge_metadata(&mut info, toml, None);
+        assert_eq!(info.id, None);
+        assert!(info.dependencies.is_empty());
+    }
+
+    #[test]
+    fn reports_missing_dependency() {
+        let mods = vec![mod_info("addon", "1.0", Vec::new(), vec![requires("library", None), requires("minecraft", None)])];
+        let issues = detect_mod_issues(&mods, None);
+        assert!(matches!(
+            issues.as_slice(),
+            [ModIssue::MissingDependency { mod_id, dependency, .. }] if mod_id == "addon" && dependency == "library"
+        ));
+    }
+
+    #[test]
+    fn reports_dependency_outside_version_range() {
+        let mods = vec![
+            mod_info("addon", "1.0", Vec::new(), vec![requires("library", Some("[2.0,3.0)"))]),
+            mod_info("other", "1.0", Vec::new(), vec![requires("library", Some(">=1.5 <2"))]),
+            mod_info("library", "1.9.1", Vec::new(), Vec::new()),
+        ];
+        let issues = detect_mod_issues(&mods, None);
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            &issues[0],
+            ModIssue::DependencyVersionMismatch { mod_id, installed_version, .. } if mod_id == "addon" && installed_version == "1.9.1"
+        ));
+    }
+
+    #[test]
+    fn version_requirements() {
+        assert_eq!(version_satisfies("47.2.0", "[47,)"), Some(true));
+        assert_eq!(version_satisfies("46.0.1", "[47,)"), Some(false));
+        assert_eq!(version_satisfies("1.20.1", "[1.20.1]"), Some(true));
+        assert_eq!(version_satisfies("2.0", "[1.0,2.0)"), Some(false));
+        assert_eq!(version_satisfies("3.5", "[1.0,2.0),[3.0,4.0)"), Some(true));
+        assert_eq!(version_satisfies("0.15.11+build.3", ">=0.15.0"), Some(true));
+        assert_eq!(version_satisfies("1.9.0", "^1.2.0"), Some(true));
+        assert_eq!(version_satisfies("2.0.0", "^1.2.0"), Some(false));
+        assert_eq!(version_satisfies("1.3.0", "~1.2.0"), Some(false));
+        assert_eq!(version_satisfies("1.20.4", "1.20.x"), Some(true));
+        assert_eq!(version_satisfies("1.19", "1.20.x || 1.19"), Some(true));
+        assert_eq!(version_satisfies("1.0", "!1.0"), None);
+    }
+
+    #[test]
+    fn reports_mods_for_another_loader() {
+        let mods = vec![
+            mod_info("fabric-mod", "1.0", vec![ModLoaderType::Fabric], Vec::new()),
+            mod_info("forge-mod", "1.0", vec![ModLoaderType::Forge], Vec::new()),
+        ];
+        let issues = detect_mod_issues(&mods, Some(&ModLoaderType::Quilt));
+        assert!(matches!(
+            issues.as_slice(),
+            [ModIssue::IncompatibleLoader { mod_id, .. }] if mod_id == "forge-mod"
+        ));
+        assert!(loader_runs_on(&ModLoaderType::Forge, &ModLoaderType::NeoForge));
+        assert!(!loader_runs_on(&ModLoaderType::NeoForge, &ModLoaderType::Forge));
+    }
+}
@@ -2,15 +2,50 @@
 
 use crate::error::{LauncherError, Result};
 use futures::StreamExt;
-use reqwest::Client;
-use sha1::{Digest, Sha1};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Number of `java -version` probes run at the same time when listing installations
+const JAVA_PROBE_CONCURRENCY: usize = 8;
+
+/// Where a Java installation was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JavaSource {
+    /// Installed on the system (package manager, vendor installer, PATH)
+    System,
+    /// Downloaded and managed by a launcher (this one or the official Minecraft launcher)
+    LauncherManaged,
+}
+
+/// A Java installation detected on the system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JavaInstallation {
+    /// Path to the java executable
+    pub path: PathBuf,
+    /// Major version (e.g. 17)
+    pub major_version: i32,
+    /// Full version string (e.g. "17.0.9")
+    pub version: String,
+    /// Vendor (e.g. "Eclipse Adoptium"), if reported
+    pub vendor: Option<String>,
+    /// Whether the JVM is 64-bit
+    pub is_64bit: bool,
+    /// CPU architecture reported by the JVM (e.g. "amd64", "aarch64")
+    pub architecture: Option<String>,
+    /// Where the installation was found
+    pub source: JavaSource,
+}
+
+/// `os.arch` of a Java executable, with the executable's modification time when it was probed
+type CachedArchitecture = (Option<std::time::SystemTime>, Option<String>);
 
 /// Java installation finder
 pub struct JavaFinder {
     java_cache: tokio::sync::RwLock<std::collections::HashMap<i32, PathBuf>>,
+    arch_cache: std::sync::Mutex<HashMap<PathBuf, CachedArchitecture>>,
+    managed_dirs: Vec<PathBuf>,
 }
 
 impl JavaFinder {
@@ -18,9 +53,17 @@ impl JavaFinder {
     pub fn new() -> Self {
         Self {
             java_cache: tokio::sync::RwLock::new(HashMap::new()),
+            arch_cache: std::sync::Mutex::new(HashMap::new()),
+            managed_dirs: Vec::new(),
         }
     }
 
+    /// Also search a directory of launcher-downloaded runtimes, reporting them as launcher-managed
+    pub fn with_managed_dir(mut self, dir: PathBuf) -> Self {
+        self.managed_dirs.push(dir);
+        self
+    }
+
     /// Find a Java installation for the specified major version
     pub async fn find_java(&self, major_version: i32) -> Result<PathBuf> {
         // Check cache first
@@ -68,7 +111,7 @@ impl JavaFinder {
 
     /// Get platform-specific Java search paths
     fn get_java_search_paths(&self) -> Vec<PathBuf> {
-        let mut paths = Vec::new();
+        let mut paths = self.managed_dirs.clone();
 
         #[cfg(target_os = "windows")]
         {
@@ -156,10 +199,8 @@ impl JavaFinder {
         if let Some(path_var) = std::env::var_os("PATH") {
             for path in std::env::split_paths(&path_var) {
                 let java_path = path.join(java_exe);
-                if java_path.exists() {
-                    if self.verify_java_version(&java_path, major_version).await.unwrap_or(false) {
-                        return Ok(java_path);
-                    }
+                if java_path.exists() && self.verify_java_version(&java_path, major_version).await.unwrap_or(false) {
+                    return Ok(java_path);
                 }
             }
         }
@@ -168,7 +209,7 @@ impl JavaFinder {
     }
 
     /// Find Java executable in a Java installation directory
-    async fn find_java_executable(&self, java_dir: &PathBuf) -> Result<PathBuf> {
+    async fn find_java_executable(&self, java_dir: &Path) -> Result<PathBuf> {
         let java_exe = if cfg!(windows) { "java.exe" } else { "java" };
         
         // Common locations within a Java installation
@@ -203,45 +244,15 @@ impl JavaFinder {
 
     /// Parse major version from java -version output
     fn parse_java_major_version(&self, version_output: &str) -> Result<i32> {
-        // Look for version patterns like:
-        // - java version "1.8.0_XXX" (Java 8)
-        // - java version "11.0.X" (Java 11)
-        // - openjdk version "17.0.X" (Java 17)
-        
-        for line in version_output.lines() {
-            if line.contains("version") {
-                // Extract version string between quotes
-                if let Some(start) = line.find('"') {
-                    if let Some(end) = line[start + 1..].find('"') {
-                        let version_str = &line[start + 1..start + 1 + end];
-                        
-                        // Parse version
-                        if version_str.starts_with("1.") {
-                            // Java 8 and below: "1.8.0_XXX" -> 8
-                            if let Some(major_str) = version_str.split('.').nth(1) {
-                                if let Ok(major) = major_str.parse::<i32>() {
-                                    return Ok(major);
-                                }
-                            }
-                        } else {
-                            // Java 9+: "11.0.X" -> 11
-                            if let Some(major_str) = version_str.split('.').next() {
-                                if let Ok(major) = major_str.parse::<i32>() {
-                                    return Ok(major);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        Err(LauncherError::config("Could not parse Java version"))
+        parse_java_version_string(version_output)
+            .and_then(|version| java_major_from_version(&version))
+            .ok_or_else(|| LauncherError::config("Could not parse Java version"))
     }
 
-    /// List all available Java installations
-    pub async fn list_java_installations(&self) -> Vec<(i32, PathBuf)> {
-        let mut installations = Vec::new();
+    /// List all available Java installations, each real installation once
+    pub async fn list_java_installations(&self) -> Vec<JavaInstallation> {
+        let mut candidates = Vec::new();
+        let mut seen = std::collections::HashSet::new();
         let search_paths = self.get_java_search_paths();
 
         for search_path in search_paths {
@@ -250,15 +261,10 @@ impl JavaFinder {
                     let path = entry.path();
                     if path.is_dir() {
                         if let Ok(java_exe) = self.find_java_executable(&path).await {
-                            if let Ok(output) = tokio::process::Command::new(&java_exe)
-                                .arg("-version")
-                                .output()
-                                .await
-                            {
-                                let version_output = String::from_utf8_lossy(&output.stderr);
-                                if let Ok(major_version) = self.parse_java_major_version(&version_output) {
-                                    installations.push((major_version, java_exe));
-                                }
+                            // Symlinked directories and overlapping search roots resolve to the same binary
+                            let real_path = tokio::fs::canonicalize(&java_exe).await.unwrap_or_else(|_| java_exe.clone());
+                            if seen.insert(real_path) {
+                                candidates.push((java_exe, self.java_source(&search_path)));
                             }
                         }
                     }
@@ -266,7 +272,89 @@ impl JavaFinder {
             }
         }
 
-        installations
+        futures::stream::iter(candidates)
+            .map(|(java_exe, source)| async move { self.probe_java(&java_exe, source).await })
+            .buffered(JAVA_PROBE_CONCURRENCY)
+            .filter_map(|installation| async move { installation })
+            .collect()
+            .await
+    }
+
+    /// Probe a single Java executable for its version and architecture
+    pub async fn inspect_java(&self, java_exe: &Path) -> Option<JavaInstallation> {
+        let source = if self.managed_dirs.iter().any(|dir| java_exe.starts_with(dir)) {
+            JavaSource::LauncherManaged
+        } else {
+            JavaSource::System
+        };
+        self.probe_java(java_exe, source).await
+    }
+
+    /// CPU architecture reported by a Java executable (`os.arch`), probed once per path and
+    /// cached until the executable changes
+    pub async fn java_architecture(&self, java_exe: &Path) -> Option<String> {
+        let modified = tokio::fs::metadata(java_exe).await.and_then(|m| m.modified()).ok();
+        if let Some((cached_modified, architecture)) = self.arch_cache.lock().unwrap().get(java_exe) {
+            if *cached_modified == modified {
+                return architecture.clone();
+            }
+        }
+
+        let architecture = self.inspect_java(java_exe).await.and_then(|java| java.architecture);
+        self.arch_cache
+            .lock()
+            .unwrap()
+            .insert(java_exe.to_path_buf(), (modified, architecture.clone()));
+        architecture
+    }
+
+    /// Classify a search root as launcher-managed or system
+    fn java_source(&self, search_path: &Path) -> JavaSource {
+        // The official launcher keeps its runtimes under `<minecraft dir>/runtime`
+        let official_runtime = search_path.file_name().is_some_and(|name| name == "runtime")
+            && search_path.parent().and_then(|p| p.file_name()).is_some_and(|name| {
+                name == ".minecraft" || name == "minecraft"
+            });
+
+        if official_runtime || self.managed_dirs.iter().any(|dir| dir == search_path) {
+            JavaSource::LauncherManaged
+        } else {
+            JavaSource::System
+        }
+    }
+
+    /// Run a Java executable and collect its version, vendor and architecture
+    async fn probe_java(&self, java_exe: &Path, source: JavaSource) -> Option<JavaInstallation> {
+        let output = tokio::process::Command::new(java_exe)
+            .arg("-XshowSettings:properties")
+            .arg("-version")
+            .output()
+            .await
+            .ok()?;
+
+        let output = String::from_utf8_lossy(&output.stderr);
+        let properties = parse_java_properties(&output);
+
+        let version = properties
+            .get("java.version")
+            .cloned()
+            .or_else(|| parse_java_version_string(&output))?;
+        let major_version = java_major_from_version(&version)?;
+        let architecture = properties.get("os.arch").cloned();
+        let is_64bit = match properties.get("sun.arch.data.model") {
+            Some(model) => model == "64",
+            None => output.contains("64-Bit") || architecture.as_deref().is_some_and(|arch| arch.contains("64")),
+        };
+
+        Some(JavaInstallation {
+            path: java_exe.to_path_buf(),
+            major_version,
+            version,
+            vendor: properties.get("java.vendor").cloned(),
+            is_64bit,
+            architecture,
+            source,
+        })
     }
 }
 
@@ -276,42 +364,718 @@ impl Default for JavaFinder {
     }
 }
 
-pub(crate) async fn download_file(
-    client: &Client,
-    url: &str,
-    path: &Path,
-    sha1: Option<&str>,
-) -> Result<()> {
-    if path.exists() {
-        if let Some(sha1) = sha1 {
-            let mut file = tokio::fs::File::open(path).await?;
-            let mut hasher = Sha1::new();
-            let mut buffer = [0; 1024];
-            loop {
-                let n = file.read(&mut buffer).await?;
-                if n == 0 {
-                    break;
+/// Extract the quoted version from a `java -version` banner, e.g. `openjdk version "17.0.9"`
+fn parse_java_version_string(version_output: &str) -> Option<String> {
+    version_output
+        .lines()
+        .filter(|line| line.contains("version"))
+        .find_map(|line| {
+            let start = line.find('"')? + 1;
+            let end = line[start..].find('"')? + start;
+            Some(line[start..end].to_string())
+        })
+}
+
+/// Major version from a Java version string: "1.8.0_381" -> 8, "17.0.9" -> 17, "21" -> 21
+fn java_major_from_version(version: &str) -> Option<i32> {
+    let mut parts = version.split(['.', '_', '-', '+']);
+    let first = parts.next()?.parse::<i32>().ok()?;
+    if first == 1 {
+        parts.next()?.parse().ok()
+    } else {
+        Some(first)
+    }
+}
+
+/// Parse the `key = value` lines printed by `java -XshowSettings:properties`
+fn parse_java_properties(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().split_once(" = "))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// Flags that each select a garbage collector; the JVM refuses to start with more than one
+const GC_SELECTORS: &[&str] = &[
+    "-XX:+UseSerialGC",
+    "-XX:+UseParallelGC",
+    "-XX:+UseConcMarkSweepGC",
+    "-XX:+UseG1GC",
+    "-XX:+UseZGC",
+    "-XX:+UseShenandoahGC",
+    "-XX:+UseEpsilonGC",
+];
+
+/// Find conflicting JVM arguments: heap sizes set more than once, a minimum heap larger than
+/// the maximum, several garbage collectors, or a second classpath. Returns one message per problem.
+pub fn find_jvm_arg_conflicts(args: &[String]) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let mut heap_sizes = |prefix: &str| -> Option<u64> {
+        let values: Vec<&String> = args.iter().filter(|arg| arg.starts_with(prefix)).collect();
+        let sizes: Vec<Option<u64>> = values.iter().map(|arg| parse_memory_size(&arg[prefix.len()..])).collect();
+        if sizes.iter().any(|size| size.is_none()) {
+            let invalid: Vec<&str> = values.iter().map(|arg| arg.as_str()).collect();
+            problems.push(format!("Invalid heap size in {}", invalid.join(", ")));
+        } else if sizes.windows(2).any(|pair| pair[0] != pair[1]) {
+            let conflicting: Vec<&str> = values.iter().map(|arg| arg.as_str()).collect();
+            problems.push(format!(
+                "{} is set more than once with different values ({}); only the last one applies",
+                prefix,
+                conflicting.join(", ")
+            ));
+        }
+        sizes.last().copied().flatten()
+    };
+    let initial_heap = heap_sizes("-Xms");
+    let max_heap = heap_sizes("-Xmx");
+    if let (Some(initial), Some(max)) = (initial_heap, max_heap) {
+        if initial > max {
+            problems.push(format!(
+                "Initial heap size ({} MB) is larger than the maximum heap size ({} MB)",
+                initial / (1024 * 1024),
+                max / (1024 * 1024)
+            ));
+        }
+    }
+
+    let mut collectors: Vec<&str> = GC_SELECTORS
+        .iter()
+        .copied()
+        .filter(|selector| args.iter().any(|arg| arg == selector))
+        .collect();
+    collectors.dedup();
+    if collectors.len() > 1 {
+        problems.push(format!("Multiple garbage collectors selected: {}", collectors.join(", ")));
+    }
+
+    if args.iter().any(|arg| arg == "-cp" || arg == "-classpath" || arg == "--class-path") {
+        problems.push("JVM arguments set a classpath, which conflicts with the launcher's".to_string());
+    }
+
+    problems
+}
+
+/// Parse a JVM memory size such as `4G`, `2048m`, `512k` or `1073741824` into bytes
+fn parse_memory_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.chars().last()? {
+        'k' | 'K' => (&value[..value.len() - 1], 1024),
+        'm' | 'M' => (&value[..value.len() - 1], 1024 * 1024),
+        'g' | 'G' => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        't' | 'T' => (&value[..value.len() - 1], 1024 * 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    digits.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// Normalize a JVM `os.arch` value to the names used by [`native_binary_architectures`]
+pub(crate) fn normalize_arch(os_arch: &str) -> Option<&'static str> {
+    match os_arch {
+        "amd64" | "x86_64" | "x64" => Some("x86_64"),
+        "x86" | "i386" | "i486" | "i586" | "i686" => Some("x86"),
+        "aarch64" | "arm64" => Some("aarch64"),
+        "arm" | "arm32" | "armv7" | "armv7l" => Some("arm"),
+        _ => None,
+    }
+}
+
+/// Architectures a native library was built for, read from its ELF, PE or Mach-O header
+///
+/// Universal Mach-O binaries report every architecture they contain.
+pub(crate) fn native_binary_architectures(bytes: &[u8]) -> Vec<&'static str> {
+    let u16_le = |at: usize| bytes.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+    let u32_le = |at: usize| bytes.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    let u32_be = |at: usize| bytes.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+
+    let mach_cpu = |cpu_type: u32| match cpu_type {
+        7 => Some("x86"),
+        0x0100_0007 => Some("x86_64"),
+        12 => Some("arm"),
+        0x0100_000c => Some("aarch64"),
+        _ => None,
+    };
+
+    if bytes.starts_with(b"\x7fELF") {
+        let machine = if bytes.get(5) == Some(&2) {
+            bytes.get(18..20).map(|b| u16::from_be_bytes([b[0], b[1]]))
+        } else {
+            u16_le(18)
+        };
+        return match machine {
+            Some(0x03) => vec!["x86"],
+            Some(0x3e) => vec!["x86_64"],
+            Some(0x28) => vec!["arm"],
+            Some(0xb7) => vec!["aarch64"],
+            _ => Vec::new(),
+        };
+    }
+
+    if bytes.starts_with(b"MZ") {
+        let Some(pe_offset) = u32_le(0x3c).map(|offset| offset as usize) else {
+            return Vec::new();
+        };
+        if bytes.get(pe_offset..pe_offset + 4) != Some(&b"PE\0\0"[..]) {
+            return Vec::new();
+        }
+        return match u16_le(pe_offset + 4) {
+            Some(0x014c) => vec!["x86"],
+            Some(0x8664) => vec!["x86_64"],
+            Some(0x01c0 | 0x01c4) => vec!["arm"],
+            Some(0xaa64) => vec!["aarch64"],
+            _ => Vec::new(),
+        };
+    }
+
+    match u32_le(0) {
+        Some(0xfeed_face | 0xfeed_facf) => return u32_le(4).and_then(mach_cpu).into_iter().collect(),
+        Some(0xcefa_edfe | 0xcffa_edfe) => return u32_be(4).and_then(mach_cpu).into_iter().collect(),
+        _ => {}
+    }
+
+    // Universal binary: big-endian header followed by one 20-byte entry per architecture
+    if u32_be(0) == Some(0xcafe_babe) {
+        let count = u32_be(4).unwrap_or(0) as usize;
+        return (0..count.min(16))
+            .filter_map(|i| u32_be(8 + i * 20).and_then(mach_cpu))
+            .collect();
+    }
+
+    Vec::new()
+}
+
+/// When the process with this PID started, in a platform-specific form that only needs to be
+/// compared for equality; `None` if no such process is running. A PID can be reused once its
+/// process exits, so this identifies a process where the PID alone can't.
+pub(crate) fn process_start_time(pid: u32) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        proc_stat_start_time(&stat)
+    }
+
+    #[cfg(windows)]
+    {
+        windows_process::times(pid).map(|times| times.created.to_string())
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        let output = std::process::Command::new("ps")
+            .args(["-o", "lstart=", "-p", &pid.to_string()])
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let start = stdout.trim();
+        (output.status.success() && !start.is_empty()).then(|| start.to_string())
+    }
+}
+
+/// `starttime` (clock ticks after boot) from the contents of `/proc/<pid>/stat`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn proc_stat_start_time(stat: &str) -> Option<String> {
+    // The command name is in parentheses and may itself contain spaces or parentheses
+    let (_, fields) = stat.rsplit_once(')')?;
+    // `starttime` is field 22; the fields after the name start at field 3
+    fields.split_whitespace().nth(19).map(str::to_string)
+}
+
+/// CPU time a process has used so far and its resident memory
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ProcessSample {
+    /// User plus system time, summed over all threads
+    pub cpu_time: std::time::Duration,
+    /// Resident set size (working set on Windows) in bytes
+    pub memory_bytes: u64,
+}
+
+/// Sample a process's CPU time and memory; `None` if the process is gone. Read from `/proc` on
+/// Linux and the process APIs on Windows, so polling doesn't spawn anything; other Unixes ask `ps`.
+pub(crate) fn process_sample(pid: u32) -> Option<ProcessSample> {
+    #[cfg(target_os = "linux")]
+    {
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        // SAFETY: sysconf only reads configuration values
+        let (ticks_per_second, page_size) = unsafe { (libc::sysconf(libc::_SC_CLK_TCK), libc::sysconf(libc::_SC_PAGESIZE)) };
+        parse_proc_stat_sample(&stat, u64::try_from(ticks_per_second).ok()?, u64::try_from(page_size).ok()?)
+    }
+
+    #[cfg(windows)]
+    {
+        let times = windows_process::times(pid)?;
+        Some(ProcessSample { cpu_time: times.cpu_time, memory_bytes: windows_process::working_set(pid)? })
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        let output = std::process::Command::new("ps")
+            .args(["-o", "cputime=,rss=", "-p", &pid.to_string()])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_ps_sample(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+/// `utime + stime` and `rss` from the contents of `/proc/<pid>/stat`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_stat_sample(stat: &str, ticks_per_second: u64, page_size: u64) -> Option<ProcessSample> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    let fields: Vec<&str> = fields.split_whitespace().collect();
+    // Fields 14, 15 and 24 of the file; the fields after the name start at field 3
+    let user_ticks: u64 = fields.get(11)?.parse().ok()?;
+    let system_ticks: u64 = fields.get(12)?.parse().ok()?;
+    let resident_pages: u64 = fields.get(21)?.parse().ok()?;
+    if ticks_per_second == 0 {
+        return None;
+    }
+    let ticks = user_ticks + system_ticks;
+    Some(ProcessSample {
+        cpu_time: std::time::Duration::from_secs(ticks / ticks_per_second)
+            + std::time::Duration::from_secs_f64((ticks % ticks_per_second) as f64 / ticks_per_second as f64),
+        memory_bytes: resident_pages * page_size,
+    })
+}
+
+/// `ps -o cputime=,rss=` output: `[dd-][hh:]mm:ss[.cc]` and KiB
+#[cfg_attr(any(target_os = "linux", windows), allow(dead_code))]
+fn parse_ps_sample(output: &str) -> Option<ProcessSample> {
+    let mut fields = output.split_whitespace();
+    let cpu_time = fields.next()?;
+    let resident_kib: u64 = fields.next()?.parse().ok()?;
+
+    let (days, clock) = match cpu_time.split_once('-') {
+        Some((days, clock)) => (days.parse::<f64>().ok()?, clock),
+        None => (0.0, cpu_time),
+    };
+    let mut seconds = 0.0;
+    for part in clock.split(':') {
+        seconds = seconds * 60.0 + part.replace(',', ".").parse::<f64>().ok()?;
+    }
+    seconds += days * 86_400.0;
+    Some(ProcessSample {
+        cpu_time: std::time::Duration::from_secs_f64(seconds),
+        memory_bytes: resident_kib * 1024,
+    })
+}
+
+/// Ask the process with this PID to terminate (forcefully on Windows)
+pub(crate) fn terminate_process(pid: u32) -> Result<()> {
+    #[cfg(windows)]
+    let status = std::process::Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .status();
+
+    #[cfg(not(windows))]
+    let status = std::process::Command::new("kill")
+        .args(["-TERM", &pid.to_string()])
+        .status();
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(LauncherError::process(format!("Failed to terminate process {}: {}", pid, status))),
+        Err(e) => Err(LauncherError::process(format!("Failed to terminate process {}: {}", pid, e))),
+    }
+}
+
+/// Run a user-supplied hook command through the platform shell and return its exit code
+pub(crate) async fn run_hook_command(command: &str, working_dir: &Path, env: &[(&str, String)]) -> Result<i32> {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    };
+
+    log::info!("Running hook command: {}", command);
+    let output = cmd
+        .current_dir(working_dir)
+        .envs(env.iter().map(|(key, value)| (*key, value)))
+        .stdin(std::process::Stdio::null())
+        .output()
+        .await
+        .map_err(|e| LauncherError::process(format!("Failed to run hook command '{}': {}", command, e)))?;
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        log::info!("[Hook STDOUT] {}", line);
+    }
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        log::warn!("[Hook STDERR] {}", line);
+    }
+
+    Ok(output.status.code().unwrap_or(-1))
+}
+
+/// Replace `path` with `contents` via a synced temp file and a rename, so a crash mid-write
+/// leaves either the old file or the new one, never a truncated mix
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    write_atomic_with_options(path, contents, std::fs::OpenOptions::new())
+}
+
+/// Like [`write_atomic`], but the file is only readable by the current user on Unix (mode 0600)
+/// from the moment it is created. Use it for files holding tokens.
+pub(crate) fn write_atomic_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    #[allow(unused_mut)]
+    let mut options = std::fs::OpenOptions::new();
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    write_atomic_with_options(path, contents, options)
+}
+
+fn write_atomic_with_options(path: &Path, contents: &[u8], mut options: std::fs::OpenOptions) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    // A temp file left by a crash keeps its old mode if opened again, so start from a new one
+    match std::fs::remove_file(&temp_path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let mut file = options.write(true).create_new(true).open(&temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+    std::fs::rename(&temp_path, path)
+}
+
+/// Move an unreadable file to `<name>.corrupt` so it can be rebuilt; the copy is kept for inspection
+pub(crate) fn quarantine_corrupt_file(path: &Path, reason: &str) {
+    let mut corrupt_name = path.file_name().unwrap_or_default().to_os_string();
+    corrupt_name.push(".corrupt");
+    let corrupt_path = path.with_file_name(corrupt_name);
+    match std::fs::rename(path, &corrupt_path) {
+        Ok(()) => log::warn!("{} is corrupt ({}), moved it to {}", path.display(), reason, corrupt_path.display()),
+        Err(e) => log::warn!("{} is corrupt ({}) and could not be moved aside: {}", path.display(), reason, e),
+    }
+}
+
+/// Leading bytes of every zip archive, and so of every jar
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// Check that `path` is a zip archive rather than, say, an HTML error page a captive portal
+/// or mirror served with a 200 status
+pub fn validate_jar(path: &Path) -> Result<()> {
+    match non_jar_content(path).map_err(|e| LauncherError::file(format!("Failed to read {}: {}", path.display(), e)))? {
+        Some(hint) => Err(LauncherError::validation(format!("{} is not a valid jar ({})", path.display(), hint))),
+        None => Ok(()),
+    }
+}
+
+/// What a file that should be a jar looks like instead, or `None` if it starts like a zip
+pub(crate) fn non_jar_content(path: &Path) -> std::io::Result<Option<&'static str>> {
+    use std::io::Read;
+
+    let mut head = Vec::with_capacity(512);
+    std::fs::File::open(path)?.take(512).read_to_end(&mut head)?;
+    if head.starts_with(ZIP_MAGIC) {
+        return Ok(None);
+    }
+
+    let text = String::from_utf8_lossy(&head);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    Ok(Some(if head.is_empty() {
+        "file is empty"
+    } else if text.starts_with('<') {
+        "got HTML?"
+    } else if text.starts_with('{') {
+        "got JSON?"
+    } else {
+        "no zip header"
+    }))
+}
+
+/// Time allowed for a single connectivity probe
+const CONNECTIVITY_PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Longest pause between connectivity probes
+const MAX_CONNECTIVITY_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(8);
+
+/// Wait until `url` can be reached, probing with backoff for up to `max_wait`
+///
+/// Any HTTP response counts as connected, so a lightweight endpoint works. Meant for the
+/// first auth or manifest request after startup, when a VPN or Wi-Fi may still be coming up.
+/// Returns the last connection or timeout error once `max_wait` has passed.
+pub async fn wait_for_connectivity(url: &str, max_wait: std::time::Duration) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(CONNECTIVITY_PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| LauncherError::network(format!("Failed to create HTTP client: {}", e)))?;
+
+    let deadline = tokio::time::Instant::now() + max_wait;
+    let mut delay = std::time::Duration::from_millis(500);
+    loop {
+        match client.head(url).send().await {
+            Ok(_) => return Ok(()),
+            Err(e) if e.is_connect() || e.is_timeout() => {
+                let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+                if remaining.is_zero() {
+                    return Err(LauncherError::from_request(format!("No connection to {}", url), e));
                 }
-                hasher.update(&buffer[..n]);
+                log::info!("Waiting for network ({}), retrying in {:?}", e, delay.min(remaining));
+                tokio::time::sleep(delay.min(remaining)).await;
+                delay = (delay * 2).min(MAX_CONNECTIVITY_RETRY_DELAY);
             }
-            let hash = format!("{:x}", hasher.finalize());
-            if hash == sha1 {
-                return Ok(());
+            Err(e) => return Err(LauncherError::from_request(format!("Connectivity check against {} failed", url), e)),
+        }
+    }
+}
+
+/// Process times and memory through the Win32 API, so polling doesn't start a process
+#[cfg(windows)]
+mod windows_process {
+    use std::ffi::c_void;
+
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    const PROCESS_VM_READ: u32 = 0x0010;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct FileTime {
+        low: u32,
+        high: u32,
+    }
+
+    impl FileTime {
+        fn ticks(&self) -> u64 {
+            (u64::from(self.high) << 32) | u64::from(self.low)
+        }
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)]
+    struct ProcessMemoryCounters {
+        cb: u32,
+        page_fault_count: u32,
+        peak_working_set_size: usize,
+        working_set_size: usize,
+        quota_peak_paged_pool_usage: usize,
+        quota_paged_pool_usage: usize,
+        quota_peak_non_paged_pool_usage: usize,
+        quota_non_paged_pool_usage: usize,
+        pagefile_usage: usize,
+        peak_pagefile_usage: usize,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(desired_access: u32, inherit_handle: i32, process_id: u32) -> *mut c_void;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+        fn GetProcessTimes(
+            process: *mut c_void,
+            creation: *mut FileTime,
+            exit: *mut FileTime,
+            kernel: *mut FileTime,
+            user: *mut FileTime,
+        ) -> i32;
+        fn GetExitCodeProcess(process: *mut c_void, exit_code: *mut u32) -> i32;
+        fn K32GetProcessMemoryInfo(process: *mut c_void, counters: *mut ProcessMemoryCounters, size: u32) -> i32;
+    }
+
+    /// `GetExitCodeProcess` reports this while the process runs
+    const STILL_ACTIVE: u32 = 259;
+
+    /// Open handle to a running process, closed on drop
+    struct Process(*mut c_void);
+
+    impl Process {
+        fn open(pid: u32) -> Option<Self> {
+            // SAFETY: plain Win32 call; a null handle means failure
+            let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ, 0, pid) };
+            if handle.is_null() {
+                return None;
             }
+            let process = Process(handle);
+            let mut exit_code = 0;
+            // SAFETY: the handle is valid until `process` is dropped
+            let running = unsafe { GetExitCodeProcess(process.0, &mut exit_code) } != 0 && exit_code == STILL_ACTIVE;
+            running.then_some(process)
+        }
+    }
+
+    impl Drop for Process {
+        fn drop(&mut self) {
+            // SAFETY: the handle came from OpenProcess and is closed once
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+
+    pub(super) struct Times {
+        /// Creation time in 100 ns units since 1601
+        pub created: u64,
+        pub cpu_time: std::time::Duration,
+    }
+
+    pub(super) fn times(pid: u32) -> Option<Times> {
+        let process = Process::open(pid)?;
+        let (mut creation, mut exit, mut kernel, mut user) = Default::default();
+        // SAFETY: all pointers are to live, correctly laid out FILETIMEs
+        if unsafe { GetProcessTimes(process.0, &mut creation, &mut exit, &mut kernel, &mut user) } == 0 {
+            return None;
+        }
+        Some(Times {
+            created: creation.ticks(),
+            cpu_time: std::time::Duration::from_nanos((kernel.ticks() + user.ticks()) * 100),
+        })
+    }
+
+    pub(super) fn working_set(pid: u32) -> Option<u64> {
+        let process = Process::open(pid)?;
+        let mut counters = ProcessMemoryCounters {
+            cb: std::mem::size_of::<ProcessMemoryCounters>() as u32,
+            ..Default::default()
+        };
+        // SAFETY: `counters` is a PROCESS_MEMORY_COUNTERS whose size is passed along
+        if unsafe { K32GetProcessMemoryInfo(process.0, &mut counters, counters.cb) } == 0 {
+            return None;
+        }
+        Some(counters.working_set_size as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proc_stat_samples() {
+        let stat = "4242 (java (main) x) S 1 4242 4242 0 -1 4194560 100 0 0 0 250 50 0 0 20 0 40 0 987654 1000 2048 18446744073709551615";
+        let sample = parse_proc_stat_sample(stat, 100, 4096).unwrap();
+        assert_eq!(sample.cpu_time, std::time::Duration::from_secs(3));
+        assert_eq!(sample.memory_bytes, 2048 * 4096);
+        assert_eq!(parse_proc_stat_sample("4242 (java) S 1 2", 100, 4096), None);
+    }
+
+    #[test]
+    fn parses_ps_samples() {
+        let sample = |output| parse_ps_sample(output).map(|sample| (sample.cpu_time.as_secs_f64(), sample.memory_bytes));
+        // macOS: minutes and seconds with hundredths
+        assert_eq!(sample("  1:02.50  204800\n"), Some((62.5, 204800 * 1024)));
+        // procps: hours, and days for long-running processes
+        assert_eq!(sample("01:00:01 10"), Some((3601.0, 10240)));
+        assert_eq!(sample("2-00:00:00 10"), Some((172_800.0, 10240)));
+        assert_eq!(sample(""), None);
+    }
+
+    #[test]
+    fn reads_start_time_from_proc_stat() {
+        let stat = "4242 (java (main) x) S 1 4242 4242 0 -1 4194560 100 0 0 0 50 10 0 0 20 0 40 0 987654 1000 200 18446744073709551615";
+        assert_eq!(proc_stat_start_time(stat).as_deref(), Some("987654"));
+        assert_eq!(proc_stat_start_time("4242 (java) S 1"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn start_time_identifies_running_processes() {
+        let own = process_start_time(std::process::id());
+        assert!(own.is_some());
+        assert_eq!(process_start_time(std::process::id()), own);
+        assert_eq!(process_start_time(u32::MAX / 2), None);
+        assert!(process_sample(std::process::id()).is_some_and(|sample| sample.memory_bytes > 0));
+        assert_eq!(process_sample(u32::MAX / 2), None);
+    }
+
+    /// `len` zero bytes with `patches` written over them
+    fn bytes(len: usize, patches: &[(usize, &[u8])]) -> Vec<u8> {
+        let mut bytes = vec![0u8; len];
+        for (at, patch) in patches {
+            bytes[*at..*at + patch.len()].copy_from_slice(patch);
         }
+        bytes
+    }
+
+    #[test]
+    fn reads_elf_machine() {
+        let little_endian_x64 = bytes(64, &[(0, b"\x7fELF\x02\x01"), (18, &[0x3e, 0x00])]);
+        assert_eq!(native_binary_architectures(&little_endian_x64), ["x86_64"]);
+        let little_endian_arm64 = bytes(64, &[(0, b"\x7fELF\x02\x01"), (18, &[0xb7, 0x00])]);
+        assert_eq!(native_binary_architectures(&little_endian_arm64), ["aarch64"]);
+        let big_endian_arm = bytes(64, &[(0, b"\x7fELF\x01\x02"), (18, &[0x00, 0x28])]);
+        assert_eq!(native_binary_architectures(&big_endian_arm), ["arm"]);
+    }
+
+    #[test]
+    fn reads_pe_machine() {
+        let x64 = bytes(0x80, &[(0, b"MZ"), (0x3c, &[0x40, 0, 0, 0]), (0x40, b"PE\0\0"), (0x44, &[0x64, 0x86])]);
+        assert_eq!(native_binary_architectures(&x64), ["x86_64"]);
+        let x86 = bytes(0x80, &[(0, b"MZ"), (0x3c, &[0x40, 0, 0, 0]), (0x40, b"PE\0\0"), (0x44, &[0x4c, 0x01])]);
+        assert_eq!(native_binary_architectures(&x86), ["x86"]);
+        let truncated = bytes(0x40, &[(0, b"MZ"), (0x3c, &[0x40, 0, 0, 0])]);
+        assert!(native_binary_architectures(&truncated).is_empty());
+    }
+
+    #[test]
+    fn reads_mach_o_cpu_types() {
+        let arm64 = bytes(32, &[(0, &[0xcf, 0xfa, 0xed, 0xfe]), (4, &[0x0c, 0x00, 0x00, 0x01])]);
+        assert_eq!(native_binary_architectures(&arm64), ["aarch64"]);
+        let x86_64 = bytes(32, &[(0, &[0xcf, 0xfa, 0xed, 0xfe]), (4, &[0x07, 0x00, 0x00, 0x01])]);
+        assert_eq!(native_binary_architectures(&x86_64), ["x86_64"]);
+        let universal = bytes(
+            48,
+            &[(0, &[0xca, 0xfe, 0xba, 0xbe]), (4, &[0, 0, 0, 2]), (8, &[0x01, 0, 0, 0x07]), (28, &[0x01, 0, 0, 0x0c])],
+        );
+        assert_eq!(native_binary_architectures(&universal), ["x86_64", "aarch64"]);
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_memory_sizes() {
+        assert_eq!(parse_memory_size("512k"), Some(512 * 1024));
+        assert_eq!(parse_memory_size("512K"), Some(512 * 1024));
+        assert_eq!(parse_memory_size("2048m"), Some(2048 * 1024 * 1024));
+        assert_eq!(parse_memory_size("2048M"), Some(2048 * 1024 * 1024));
+        assert_eq!(parse_memory_size("4g"), Some(4 * 1024 * 1024 * 1024));
+        assert_eq!(parse_memory_size("4G"), Some(4 * 1024 * 1024 * 1024));
+        assert_eq!(parse_memory_size("1073741824"), Some(1073741824));
+        assert_eq!(parse_memory_size(""), None);
+        assert_eq!(parse_memory_size("m"), None);
+        assert_eq!(parse_memory_size("4GB"), None);
+        assert_eq!(parse_memory_size("-1g"), None);
+        assert_eq!(parse_memory_size("lots"), None);
+        assert_eq!(parse_memory_size("99999999999T"), None);
     }
 
-    let temp_path = path.with_extension("tmp");
-    let response = client.get(url).send().await?;
-    let mut stream = response.bytes_stream();
-    let mut file = tokio::fs::File::create(&temp_path).await?;
+    #[test]
+    fn custom_heap_size_conflicts_with_max_memory() {
+        // The launcher appends -Xmx from `memory_max` after the user's JVM arguments
+        let problems = find_jvm_arg_conflicts(&args(&["-Xmx2G", "-Xmx4096m"]));
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("-Xmx is set more than once"), "{}", problems[0]);
+
+        assert!(find_jvm_arg_conflicts(&args(&["-Xmx4G", "-Xmx4096m"])).is_empty());
+
+        let problems = find_jvm_arg_conflicts(&args(&["-Xms8G", "-Xmx4096m"]));
+        assert_eq!(problems, ["Initial heap size (8192 MB) is larger than the maximum heap size (4096 MB)"]);
 
-    while let Some(item) = stream.next().await {
-        let chunk = item?;
-        file.write_all(&chunk).await?;
+        let problems = find_jvm_arg_conflicts(&args(&["-Xmxlots", "-Xmx4096m"]));
+        assert_eq!(problems, ["Invalid heap size in -Xmxlots, -Xmx4096m"]);
     }
 
-    tokio::fs::rename(&temp_path, path).await?;
+    #[test]
+    fn reports_duplicate_garbage_collectors_and_classpaths() {
+        let problems = find_jvm_arg_conflicts(&args(&["-XX:+UseG1GC", "-XX:+UseZGC"]));
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("Multiple garbage collectors selected"), "{}", problems[0]);
 
-    Ok(())
+        assert!(find_jvm_arg_conflicts(&args(&["-XX:+UseG1GC", "-XX:+UseG1GC"])).is_empty());
+        assert_eq!(find_jvm_arg_conflicts(&args(&["-cp", "mods.jar"])).len(), 1);
+    }
+
+    #[test]
+    fn unknown_binaries_have_no_architecture() {
+        assert!(native_binary_architectures(b"").is_empty());
+        assert!(native_binary_architectures(b"#!/bin/sh\necho hi\n").is_empty());
+    }
 }
@@ -3,8 +3,12 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use crate::config::MojangEndpoints;
 use crate::error::{LauncherError, Result};
 
+pub(crate) const VERSION_MANIFEST_V2_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+const VERSION_MANIFEST_V1_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest.json";
+
 /// Minecraft version manifest from Mojang
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionManifest {
@@ -38,9 +42,9 @@ pub struct VersionEntry {
     /// Release time (different format)
     #[serde(rename = "releaseTime")]
     pub release_time: DateTime<Utc>,
-    /// SHA1 hash of the version JSON
-    pub sha1: String,
-    /// Compliance level
+    /// SHA1 hash of the version JSON (absent in the v1 manifest)
+    pub sha1: Option<String>,
+    /// Compliance level (absent in the v1 manifest)
     #[serde(rename = "complianceLevel")]
     pub compliance_level: Option<i32>,
 }
@@ -88,6 +92,47 @@ pub struct VersionInfo {
     pub version_type: String,
 }
 
+/// First log4j-core release fixed against Log4Shell and its follow-up CVEs
+const LOG4J_FIXED_VERSION: &str = "2.17.0";
+
+/// First log4j-core release that reads the `formatMsgNoLookups` property
+const LOG4J_NO_LOOKUPS_SINCE: &str = "2.10.0";
+
+impl VersionInfo {
+    /// Whether Mojang marks this version as predating its security patches (`complianceLevel` 0)
+    pub fn lacks_security_patches(&self) -> bool {
+        self.compliance_level == Some(0)
+    }
+
+    /// Version of the log4j-core library the game loads, if any
+    pub fn log4j_core_version(&self) -> Option<&str> {
+        self.libraries.iter().find_map(|library| {
+            let mut parts = library.name.split(':');
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some("org.apache.logging.log4j"), Some("log4j-core"), Some(version)) => Some(version),
+                _ => None,
+            }
+        })
+    }
+
+    /// Whether the version loads a log4j-core older than 2.17.0 and is open to Log4Shell
+    pub fn is_log4shell_vulnerable(&self) -> bool {
+        self.log4j_core_version()
+            .is_some_and(|version| compare_maven_versions(version, LOG4J_FIXED_VERSION) == std::cmp::Ordering::Less)
+    }
+
+    /// Whether the loaded log4j-core honours `-Dlog4j2.formatMsgNoLookups`, added in 2.10.0
+    pub fn supports_log4j_no_lookups(&self) -> bool {
+        self.log4j_core_version()
+            .is_some_and(|version| compare_maven_versions(version, LOG4J_NO_LOOKUPS_SINCE) != std::cmp::Ordering::Less)
+    }
+
+    /// The client logging configuration Mojang serves for this version, if any
+    pub fn client_logging(&self) -> Option<&LoggingClient> {
+        self.logging.as_ref().and_then(|logging| logging.client.as_ref())
+    }
+}
+
 /// Game and JVM arguments
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Arguments {
@@ -146,6 +191,47 @@ pub struct AssetIndex {
     pub url: String,
 }
 
+/// Contents of an asset index file (`assets/indexes/<id>.json`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetIndexFile {
+    /// Assets by logical path, e.g. `minecraft/sounds/ambient/cave/cave1.ogg`
+    pub objects: HashMap<String, AssetObject>,
+    /// Legacy index (1.6 to 1.7.2) whose assets are also needed by name under `virtual/<id>`
+    #[serde(default, rename = "virtual")]
+    pub is_virtual: bool,
+    /// Pre-1.6 index whose assets are also needed by name in the game directory's `resources/`
+    #[serde(default)]
+    pub map_to_resources: bool,
+}
+
+impl AssetIndexFile {
+    /// Combined size of all assets in bytes
+    pub fn total_size(&self) -> u64 {
+        self.objects.values().map(|object| object.size).sum()
+    }
+}
+
+/// A single asset in an [`AssetIndexFile`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetObject {
+    /// SHA1 of the file, which is also its storage name
+    pub hash: String,
+    /// File size in bytes
+    pub size: u64,
+}
+
+impl AssetObject {
+    /// Path under `assets/objects/` and on the resources server: `<first two hash chars>/<hash>`
+    pub fn object_path(&self) -> String {
+        format!("{}/{}", self.hash.get(..2).unwrap_or_default(), self.hash)
+    }
+
+    /// Download URL on Mojang's resources server, moved to a mirror by [`MojangEndpoints::rewrite`]
+    pub fn url(&self) -> String {
+        format!("{}/{}", crate::config::DEFAULT_RESOURCES_HOST, self.object_path())
+    }
+}
+
 /// Download information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Downloads {
@@ -195,6 +281,42 @@ pub struct Library {
     pub natives: Option<HashMap<String, String>>,
     /// Extract information
     pub extract: Option<ExtractInfo>,
+    /// Maven repository for libraries without a `downloads` block (mod loader profiles)
+    pub url: Option<String>,
+    /// SHA1 of the artifact, when declared next to the Maven coordinate
+    pub sha1: Option<String>,
+}
+
+/// Maven repository used when a coordinate-only library declares no `url`
+pub const DEFAULT_LIBRARY_REPOSITORY: &str = "https://libraries.minecraft.net/";
+
+impl Library {
+    /// Download URL derived from the Maven coordinate and the declared (or default) repository
+    pub fn maven_url(&self) -> Option<String> {
+        let repository = self.url.as_deref().unwrap_or(DEFAULT_LIBRARY_REPOSITORY);
+        let path = maven_path(&self.name)?;
+        Some(format!("{}/{}", repository.trim_end_matches('/'), path))
+    }
+}
+
+/// Convert a Maven coordinate (`group:artifact:version[:classifier][@extension]`) into
+/// its repository-relative path
+pub fn maven_path(coordinate: &str) -> Option<String> {
+    let (coordinate, extension) = coordinate.split_once('@').unwrap_or((coordinate, "jar"));
+    let parts: Vec<&str> = coordinate.split(':').collect();
+    if parts.len() < 3 {
+        return None;
+    }
+
+    let group = parts[0].replace('.', "/");
+    let artifact = parts[1];
+    let version = parts[2];
+    let classifier = parts.get(3).map(|c| format!("-{}", c)).unwrap_or_default();
+
+    Some(format!(
+        "{}/{}/{}/{}-{}{}.{}",
+        group, artifact, version, artifact, version, classifier, extension
+    ))
 }
 
 /// Library download information
@@ -259,6 +381,224 @@ impl std::fmt::Display for ModLoaderType {
     }
 }
 
+impl ModLoaderType {
+    /// Rough check whether this loader exists for a Minecraft version. Releases, pre-releases
+    /// (`1.21-pre1`) and weekly snapshots (`24w14a`) are compared by the release they belong to;
+    /// unrecognized versions are only assumed to work with Forge.
+    pub fn supports_version(&self, minecraft_version: &str) -> bool {
+        let Some(series) = release_series(minecraft_version) else {
+            return *self == ModLoaderType::Forge;
+        };
+
+        match self {
+            // Forge supports most versions from 1.2.5 onwards
+            ModLoaderType::Forge => true,
+            ModLoaderType::Fabric => series >= (1, 14, 0),
+            ModLoaderType::Quilt => series >= (1, 17, 0),
+            ModLoaderType::NeoForge => series >= (1, 20, 1),
+            ModLoaderType::LegacyFabric => series < (1, 14, 0),
+        }
+    }
+}
+
+/// A release as `(major, minor, patch)`
+pub type ReleaseSeries = (u32, u32, u32);
+
+/// First weekly snapshot (`(year, week)`) leading up to each release, in order
+const SNAPSHOT_SERIES: &[((u32, u32), ReleaseSeries)] = &[
+    ((12, 1), (1, 2, 0)),
+    ((12, 15), (1, 3, 0)),
+    ((12, 32), (1, 4, 0)),
+    ((13, 1), (1, 5, 0)),
+    ((13, 16), (1, 6, 0)),
+    ((13, 36), (1, 7, 0)),
+    ((13, 47), (1, 7, 4)),
+    ((14, 2), (1, 8, 0)),
+    ((15, 14), (1, 9, 0)),
+    ((16, 20), (1, 10, 0)),
+    ((16, 32), (1, 11, 0)),
+    ((17, 6), (1, 12, 0)),
+    ((17, 43), (1, 13, 0)),
+    ((18, 30), (1, 13, 1)),
+    ((18, 43), (1, 14, 0)),
+    ((19, 34), (1, 15, 0)),
+    ((20, 6), (1, 16, 0)),
+    ((20, 27), (1, 16, 2)),
+    ((20, 45), (1, 17, 0)),
+    ((21, 37), (1, 18, 0)),
+    ((22, 3), (1, 18, 2)),
+    ((22, 11), (1, 19, 0)),
+    ((22, 24), (1, 19, 1)),
+    ((22, 42), (1, 19, 3)),
+    ((23, 3), (1, 19, 4)),
+    ((23, 12), (1, 20, 0)),
+    ((23, 31), (1, 20, 2)),
+    ((23, 40), (1, 20, 3)),
+    ((24, 3), (1, 20, 5)),
+    ((24, 18), (1, 21, 0)),
+    ((24, 33), (1, 21, 2)),
+    ((24, 44), (1, 21, 4)),
+    ((25, 2), (1, 21, 5)),
+    ((25, 15), (1, 21, 6)),
+    ((25, 31), (1, 21, 9)),
+    ((25, 41), (1, 21, 11)),
+];
+
+/// The release a version id belongs to, as `(major, minor, patch)`: `1.20.1` is `(1, 20, 1)`,
+/// `1.21-pre1` and `1.21 Pre-Release 1` are `(1, 21, 0)`, and weekly snapshots such as
+/// `24w14a` map to the release they were leading up to. Returns `None` for other ids.
+pub fn release_series(version_id: &str) -> Option<ReleaseSeries> {
+    if let Some(series) = snapshot_series(version_id) {
+        return Some(series);
+    }
+
+    let release = version_id.split(['-', ' ']).next()?;
+    let mut parts = release.split('.').map(|part| part.parse::<u32>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next()?.ok()?;
+    let patch = match parts.next() {
+        Some(patch) => patch.ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+/// Map a weekly snapshot id (`YYwWWx`) to its release through [`SNAPSHOT_SERIES`]
+fn snapshot_series(version_id: &str) -> Option<ReleaseSeries> {
+    let (year, rest) = version_id.split_once('w')?;
+    if year.len() != 2 || rest.len() < 3 {
+        return None;
+    }
+    let year = year.parse::<u32>().ok()?;
+    let week = rest.get(..2)?.parse::<u32>().ok()?;
+    if !rest[2..].chars().all(|c| c.is_ascii_alphabetic() || c == '_' || c == '~') {
+        return None;
+    }
+
+    SNAPSHOT_SERIES
+        .iter()
+        .rev()
+        .find(|(start, _)| *start <= (year, week))
+        .map(|(_, series)| *series)
+}
+
+/// Compare two Maven-style versions (`1.20.1`, `1.20.1-rc1`, `0.16.10+build.3`).
+///
+/// Versions are split into numeric and alphabetic segments at `.`, `-`, `_`, `+` and at digit/letter
+/// boundaries. Numbers compare numerically, missing trailing segments count as `0`, and known
+/// qualifiers rank `alpha < beta < milestone < pre < rc < snapshot < release < sp`, so
+/// `1.20.1-rc1 < 1.20.1 < 1.20.2 < 1.20.10`. Unknown qualifiers sort after `sp`, alphabetically.
+pub fn compare_maven_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let a = version_segments(a);
+    let b = version_segments(b);
+
+    for i in 0..a.len().max(b.len()) {
+        let ordering = match (a.get(i), b.get(i)) {
+            (Some(left), Some(right)) => left.cmp(right),
+            (Some(left), None) => left.cmp_to_padding(),
+            (None, Some(right)) => right.cmp_to_padding().reverse(),
+            (None, None) => std::cmp::Ordering::Equal,
+        };
+        if ordering != std::cmp::Ordering::Equal {
+            return ordering;
+        }
+    }
+    std::cmp::Ordering::Equal
+}
+
+/// One segment of a version for [`compare_maven_versions`]. Equality follows `Ord`, so aliases
+/// such as `a` and `alpha` are equal.
+#[derive(Debug)]
+enum VersionSegment {
+    /// Digits with leading zeros removed
+    Number(String),
+    /// Rank of a known qualifier, or the lowercased text of an unknown one
+    Qualifier(u8, String),
+}
+
+/// Rank of a release (and of a missing qualifier)
+const RELEASE_QUALIFIER_RANK: u8 = 6;
+
+impl VersionSegment {
+    fn qualifier(text: &str) -> Self {
+        let text = text.to_lowercase();
+        let rank = match text.as_str() {
+            "alpha" | "a" => 0,
+            "beta" | "b" => 1,
+            "milestone" | "m" => 2,
+            "pre" | "preview" => 3,
+            "rc" | "cr" => 4,
+            "snapshot" => 5,
+            "" | "ga" | "final" | "release" => RELEASE_QUALIFIER_RANK,
+            "sp" => 7,
+            _ => 8,
+        };
+        VersionSegment::Qualifier(rank, text)
+    }
+
+    /// Compare against the segment a shorter version is padded with (`0` or a release)
+    fn cmp_to_padding(&self) -> std::cmp::Ordering {
+        match self {
+            VersionSegment::Number(digits) if digits.is_empty() => std::cmp::Ordering::Equal,
+            VersionSegment::Number(_) => std::cmp::Ordering::Greater,
+            VersionSegment::Qualifier(rank, _) => rank.cmp(&RELEASE_QUALIFIER_RANK),
+        }
+    }
+}
+
+impl Ord for VersionSegment {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self, other) {
+            (VersionSegment::Number(a), VersionSegment::Number(b)) => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+            // `1.0.1` is newer than `1.0-beta`
+            (VersionSegment::Number(_), VersionSegment::Qualifier(..)) => std::cmp::Ordering::Greater,
+            (VersionSegment::Qualifier(..), VersionSegment::Number(_)) => std::cmp::Ordering::Less,
+            (VersionSegment::Qualifier(rank_a, a), VersionSegment::Qualifier(rank_b, b)) => {
+                rank_a.cmp(rank_b).then_with(|| if *rank_a == 8 { a.cmp(b) } else { std::cmp::Ordering::Equal })
+            }
+        }
+    }
+}
+
+impl PartialEq for VersionSegment {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for VersionSegment {}
+
+impl PartialOrd for VersionSegment {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Split a version into numeric and alphabetic segments
+fn version_segments(version: &str) -> Vec<VersionSegment> {
+    let mut segments = Vec::new();
+    for part in version.split(['.', '-', '_', '+']).filter(|part| !part.is_empty()) {
+        let mut rest = part;
+        while let Some(first) = rest.chars().next() {
+            let is_digit = first.is_ascii_digit();
+            let end = rest
+                .find(|c: char| c.is_ascii_digit() != is_digit)
+                .unwrap_or(rest.len());
+            let (segment, remainder) = rest.split_at(end);
+            segments.push(if is_digit {
+                VersionSegment::Number(segment.trim_start_matches('0').to_string())
+            } else {
+                VersionSegment::qualifier(segment)
+            });
+            rest = remainder;
+        }
+    }
+    segments
+}
+
 /// Mod loader information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModLoader {
@@ -287,11 +627,78 @@ pub struct ModLoaderBuildInfo {
     pub libraries: Vec<Library>,
 }
 
+/// A file a mod loader install would download
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedDownload {
+    /// Maven coordinate or name of the file
+    pub name: String,
+    /// Download URL
+    pub url: String,
+    /// Destination inside the instance directory
+    pub path: std::path::PathBuf,
+    /// Size in bytes, if the metadata or the server reported one
+    pub size: Option<u64>,
+    /// SHA1 hash, if known
+    pub sha1: Option<String>,
+    /// Whether the file is already present (and matches its hash, when one is known)
+    pub present: bool,
+}
+
+/// The files installing a mod loader into an instance would fetch, see `Launcher::plan_mod_loader_download`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModLoaderDownloadPlan {
+    /// The resolved loader build
+    pub loader: ModLoader,
+    /// Loader jar and libraries, in download order
+    pub files: Vec<PlannedDownload>,
+}
+
+impl ModLoaderDownloadPlan {
+    /// Files that still need downloading
+    pub fn pending(&self) -> impl Iterator<Item = &PlannedDownload> {
+        self.files.iter().filter(|file| !file.present)
+    }
+
+    /// Total known size of the files that still need downloading
+    pub fn pending_bytes(&self) -> u64 {
+        self.pending().filter_map(|file| file.size).sum()
+    }
+}
+
+/// Where a version JSON comes from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum VersionSource {
+    /// Look the version up in Mojang's manifest by ID
+    ManifestId(String),
+    /// Fetch the version JSON from a URL (custom distributions)
+    Url(String),
+    /// Read the version JSON from a local file (patched or hand-written profiles)
+    LocalFile(std::path::PathBuf),
+}
+
+/// Longest `inheritsFrom` chain `resolve_version_info` follows before giving up
+const MAX_INHERITANCE_DEPTH: usize = 8;
+
+/// Number of version JSONs `fetch_version_infos` downloads at the same time
+const VERSION_FETCH_CONCURRENCY: usize = 8;
+
 /// Version manager for fetching and caching version information
 pub struct VersionManager {
     client: reqwest::Client,
-    #[allow(dead_code)]
     cache_dir: std::path::PathBuf,
+    endpoints: MojangEndpoints,
+}
+
+/// Check `data` against a hex SHA1 digest
+fn verify_sha1(data: &[u8], expected: &str) -> std::result::Result<(), String> {
+    use sha1::{Digest, Sha1};
+
+    let actual = format!("{:x}", Sha1::digest(data));
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(format!("hash mismatch: expected {}, got {}", expected, actual))
+    }
 }
 
 impl VersionManager {
@@ -303,16 +710,36 @@ impl VersionManager {
             .build()
             .map_err(|e| LauncherError::network(format!("Failed to create HTTP client: {}", e)))?;
 
-        Ok(Self { client, cache_dir })
+        Ok(Self { client, cache_dir, endpoints: MojangEndpoints::default() })
+    }
+
+    /// Fetch manifests and version JSONs from other base URLs than Mojang's
+    pub fn with_endpoints(mut self, endpoints: MojangEndpoints) -> Self {
+        self.endpoints = endpoints;
+        self
     }
 
-    /// Fetch the version manifest from Mojang
+    /// Fetch the version manifest from Mojang, falling back to the v1 manifest if v2 can't be
+    /// reached, times out or answers with 5xx. Other errors (4xx, unparsable JSON) are returned.
     pub async fn fetch_version_manifest(&self) -> Result<VersionManifest> {
+        match self.fetch_manifest_from(VERSION_MANIFEST_V2_URL).await {
+            Ok(manifest) => Ok(manifest),
+            Err(e) if e.is_transient() => {
+                log::warn!("Version manifest v2 unavailable ({}), falling back to v1", e);
+                self.fetch_manifest_from(VERSION_MANIFEST_V1_URL).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn fetch_manifest_from(&self, url: &str) -> Result<VersionManifest> {
+        let url = &self.endpoints.rewrite(url);
         let response = self.client
-            .get("https://piston-meta.mojang.com/mc/game/version_manifest_v2.json")
+            .get(url)
             .send()
             .await
-            .map_err(|e| LauncherError::network(format!("Failed to fetch version manifest: {}", e)))?;
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| LauncherError::from_request("Failed to fetch version manifest", e))?;
 
         let manifest: VersionManifest = response
             .json()
@@ -324,21 +751,176 @@ impl VersionManager {
 
     /// Fetch detailed version information for a specific version
     pub async fn fetch_version_info(&self, version_entry: &VersionEntry) -> Result<VersionInfo> {
-        let response = self.client
-            .get(&version_entry.url)
-            .send()
+        let body = self.fetch_verified_version_json(version_entry).await?;
+        let version_info: VersionInfo = serde_json::from_slice(&body)
+            .map_err(|e| LauncherError::json(format!("Failed to parse version info: {}", e)))?;
+
+        Ok(version_info)
+    }
+
+    /// Fetch the version JSONs of several versions, several at a time, in the order of `ids`
+    ///
+    /// The manifest is fetched once; failing to get it is the outer error. Each version then
+    /// succeeds or fails on its own.
+    pub async fn fetch_version_infos(&self, ids: &[&str]) -> Result<Vec<Result<VersionInfo>>> {
+        use futures::StreamExt;
+
+        let manifest = self.fetch_version_manifest().await?;
+        let results = futures::stream::iter(ids)
+            .map(|id| {
+                let entry = manifest.versions.iter().find(|entry| entry.id == *id);
+                async move {
+                    let entry = entry.ok_or_else(|| LauncherError::version_not_found(*id))?;
+                    self.fetch_version_info(entry).await
+                }
+            })
+            .buffered(VERSION_FETCH_CONCURRENCY)
+            .collect()
+            .await;
+        Ok(results)
+    }
+
+    /// Where a version JSON with a known SHA1 is cached; `None` for IDs unfit for a file name
+    fn cached_version_json_path(&self, version_entry: &VersionEntry) -> Option<std::path::PathBuf> {
+        let id = &version_entry.id;
+        let usable = !id.is_empty() && id != "." && id != ".." && !id.contains(['/', '\\']);
+        usable.then(|| self.cache_dir.join("versions").join(format!("{}.json", id)))
+    }
+
+    /// A manifest version's JSON, checked against the manifest's SHA1. Verified JSONs are
+    /// cached, as a given SHA1 always names the same content.
+    async fn fetch_verified_version_json(&self, version_entry: &VersionEntry) -> Result<Vec<u8>> {
+        let cache_path = version_entry.sha1.as_ref().and(self.cached_version_json_path(version_entry));
+        if let (Some(path), Some(expected)) = (&cache_path, &version_entry.sha1) {
+            if let Ok(cached) = tokio::fs::read(path).await {
+                if verify_sha1(&cached, expected).is_ok() {
+                    log::debug!("Using cached version JSON for {}", version_entry.id);
+                    return Ok(cached);
+                }
+            }
+        }
+
+        let body = self.fetch_version_json(&version_entry.url).await?;
+
+        // v1 and custom manifests carry no hash; only verify when one was given
+        match &version_entry.sha1 {
+            Some(expected) => verify_sha1(&body, expected)
+                .map_err(|e| LauncherError::validation(format!("Version {}: {}", version_entry.id, e)))?,
+            None => log::debug!("No SHA1 for version {}, skipping verification", version_entry.id),
+        }
+
+        if let Some(path) = &cache_path {
+            let written = match path.parent() {
+                Some(parent) => tokio::fs::create_dir_all(parent).await.is_ok() && tokio::fs::write(path, &body).await.is_ok(),
+                None => false,
+            };
+            if !written {
+                log::debug!("Could not cache version JSON at {}", path.display());
+            }
+        }
+
+        Ok(body)
+    }
+
+    /// Fetch version information from a version JSON at an arbitrary URL
+    pub async fn fetch_version_info_from_url(&self, url: &str) -> Result<VersionInfo> {
+        let body = self.fetch_version_json(url).await?;
+        serde_json::from_slice(&body)
+            .map_err(|e| LauncherError::json(format!("Failed to parse version info from {}: {}", url, e)))
+    }
+
+    /// Load version information from a local version JSON file
+    pub async fn load_version_info_from_file(&self, path: &std::path::Path) -> Result<VersionInfo> {
+        let content = tokio::fs::read(path)
             .await
-            .map_err(|e| LauncherError::network(format!("Failed to fetch version info: {}", e)))?;
+            .map_err(|e| LauncherError::file(format!("Failed to read version file {}: {}", path.display(), e)))?;
+        serde_json::from_slice(&content)
+            .map_err(|e| LauncherError::json(format!("Failed to parse version file {}: {}", path.display(), e)))
+    }
 
-        let version_info: VersionInfo = response
-            .json()
+    /// Resolve version information from any supported source
+    ///
+    /// Profiles that declare `inheritsFrom` are merged onto their parents, which may inherit
+    /// in turn. A parent is read from `versions/<id>/<id>.json` next to a local profile when
+    /// that file exists, and looked up in the manifest otherwise. Cycles and chains deeper
+    /// than eight parents are rejected.
+    pub async fn resolve_version_info(&self, source: &VersionSource) -> Result<VersionInfo> {
+        let mut profile = self.load_version_profile(source).await?;
+        let mut chain = vec![profile_id(&profile)];
+        let mut current_source = source.clone();
+
+        while let Some(parent_id) = profile.get("inheritsFrom").and_then(|v| v.as_str()).map(str::to_string) {
+            if chain.contains(&parent_id) {
+                chain.push(parent_id);
+                return Err(LauncherError::validation(format!(
+                    "Version inheritance cycle: {}",
+                    chain.join(" -> ")
+                )));
+            }
+            if chain.len() > MAX_INHERITANCE_DEPTH {
+                return Err(LauncherError::validation(format!(
+                    "Version {} inherits through more than {} parents",
+                    chain[0], MAX_INHERITANCE_DEPTH
+                )));
+            }
+
+            current_source = parent_source(&current_source, &parent_id);
+            let parent = self.load_version_profile(&current_source).await?;
+            log::debug!("Version {} inherits from {}", chain[chain.len() - 1], parent_id);
+            chain.push(parent_id);
+            profile = merge_version_profiles(parent, profile);
+        }
+
+        serde_json::from_value(profile)
+            .map_err(|e| LauncherError::json(format!("Failed to parse version {}: {}", chain[0], e)))
+    }
+
+    /// Raw version JSON from a source, without resolving `inheritsFrom`
+    async fn load_version_profile(&self, source: &VersionSource) -> Result<serde_json::Value> {
+        let (body, origin) = match source {
+            VersionSource::ManifestId(id) => {
+                let version_entry = self.find_version(id).await?;
+                (self.fetch_verified_version_json(&version_entry).await?, id.clone())
+            }
+            VersionSource::Url(url) => (self.fetch_version_json(url).await?, url.clone()),
+            VersionSource::LocalFile(path) => {
+                let content = tokio::fs::read(path)
+                    .await
+                    .map_err(|e| LauncherError::file(format!("Failed to read version file {}: {}", path.display(), e)))?;
+                (content, path.display().to_string())
+            }
+        };
+
+        serde_json::from_slice(&body)
+            .map_err(|e| LauncherError::json(format!("Failed to parse version info from {}: {}", origin, e)))
+    }
+
+    async fn fetch_version_json(&self, url: &str) -> Result<Vec<u8>> {
+        let url = &self.endpoints.rewrite(url);
+        let body = self.client
+            .get(url)
+            .send()
             .await
-            .map_err(|e| LauncherError::json(format!("Failed to parse version info: {}", e)))?;
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| LauncherError::from_request("Failed to fetch version info", e))?
+            .bytes()
+            .await
+            .map_err(|e| LauncherError::from_request("Failed to fetch version info", e))?;
+        Ok(body.to_vec())
+    }
 
-        Ok(version_info)
+    /// Fetch and parse the asset index a version uses, verifying its SHA1
+    pub async fn fetch_asset_index(&self, version_info: &VersionInfo) -> Result<AssetIndexFile> {
+        let index = &version_info.asset_index;
+        let body = self.fetch_version_json(&index.url).await?;
+        verify_sha1(&body, &index.sha1)
+            .map_err(|e| LauncherError::validation(format!("Asset index {}: {}", index.id, e)))?;
+        serde_json::from_slice(&body)
+            .map_err(|e| LauncherError::json(format!("Failed to parse asset index {}: {}", index.id, e)))
     }
 
-    /// Get available mod loader versions for a Minecraft version
+    /// Get available mod loader versions for a Minecraft version from the loader's meta server.
+    /// Forge and NeoForge are not supported yet and return an error.
     pub async fn get_mod_loader_versions(
         &self,
         loader_type: ModLoaderType,
@@ -377,30 +959,509 @@ impl VersionManager {
 
     // Private methods for specific mod loader APIs
 
+    // Forge and NeoForge only publish installers, whose libraries are listed inside the jar
     async fn get_forge_versions(&self, _minecraft_version: &str) -> Result<Vec<ModLoader>> {
-        // Implement Forge API integration
-        // This would fetch from https://files.minecraftforge.net/net/minecraftforge/forge/
-        Ok(Vec::new()) // Placeholder
+        Err(LauncherError::mod_loader("Listing Forge versions is not supported yet"))
     }
 
-    async fn get_fabric_versions(&self, _minecraft_version: &str) -> Result<Vec<ModLoader>> {
-        // Implement Fabric API integration
-        // This would fetch from https://meta.fabricmc.net/v2/versions/loader/{minecraft_version}
-        Ok(Vec::new()) // Placeholder
+    async fn get_fabric_versions(&self, minecraft_version: &str) -> Result<Vec<ModLoader>> {
+        self.fetch_meta_loaders(ModLoaderType::Fabric, FABRIC_META_URL, minecraft_version, FABRIC_MAVEN, FABRIC_MAVEN)
+            .await
     }
 
-    async fn get_quilt_versions(&self, _minecraft_version: &str) -> Result<Vec<ModLoader>> {
-        // Implement Quilt API integration
-        Ok(Vec::new()) // Placeholder
+    async fn get_quilt_versions(&self, minecraft_version: &str) -> Result<Vec<ModLoader>> {
+        // Quilt runs on Fabric's intermediary mappings
+        self.fetch_meta_loaders(ModLoaderType::Quilt, QUILT_META_URL, minecraft_version, QUILT_MAVEN, FABRIC_MAVEN)
+            .await
     }
 
     async fn get_neoforge_versions(&self, _minecraft_version: &str) -> Result<Vec<ModLoader>> {
-        // Implement NeoForge API integration
-        Ok(Vec::new()) // Placeholder
+        Err(LauncherError::mod_loader("Listing NeoForge versions is not supported yet"))
+    }
+
+    async fn get_legacy_fabric_versions(&self, minecraft_version: &str) -> Result<Vec<ModLoader>> {
+        // Legacy Fabric ships the regular Fabric loader with its own intermediary
+        self.fetch_meta_loaders(
+            ModLoaderType::LegacyFabric,
+            LEGACY_FABRIC_META_URL,
+            minecraft_version,
+            FABRIC_MAVEN,
+            LEGACY_FABRIC_MAVEN,
+        )
+        .await
+    }
+
+    async fn fetch_meta_loaders(
+        &self,
+        loader_type: ModLoaderType,
+        meta_url: &str,
+        minecraft_version: &str,
+        loader_maven: &str,
+        intermediary_maven: &str,
+    ) -> Result<Vec<ModLoader>> {
+        let url = format!("{}{}", meta_url, urlencoding::encode(minecraft_version));
+        let context = format!("Failed to fetch {} versions", loader_type);
+        let body = self.client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| LauncherError::from_request(context.as_str(), e))?
+            .bytes()
+            .await
+            .map_err(|e| LauncherError::from_request(context.as_str(), e))?;
+        parse_meta_loaders(&body, loader_type, minecraft_version, loader_maven, intermediary_maven)
+    }
+}
+
+/// Loader lists of the Fabric-style meta servers, followed by the Minecraft version
+const FABRIC_META_URL: &str = "https://meta.fabricmc.net/v2/versions/loader/";
+const QUILT_META_URL: &str = "https://meta.quiltmc.org/v3/versions/loader/";
+const LEGACY_FABRIC_META_URL: &str = "https://meta.legacyfabric.net/v2/versions/loader/";
+
+const FABRIC_MAVEN: &str = "https://maven.fabricmc.net/";
+const QUILT_MAVEN: &str = "https://maven.quiltmc.org/repository/release/";
+const LEGACY_FABRIC_MAVEN: &str = "https://maven.legacyfabric.net/";
+
+/// One entry of a Fabric-style meta server's loader list
+#[derive(Deserialize)]
+struct MetaLoaderEntry {
+    loader: MetaArtifact,
+    intermediary: Option<MetaArtifact>,
+    #[serde(rename = "launcherMeta")]
+    launcher_meta: MetaLauncherMeta,
+}
+
+#[derive(Deserialize)]
+struct MetaArtifact {
+    maven: String,
+    version: String,
+    build: Option<u64>,
+    /// Quilt doesn't mark releases; its betas carry a `-beta.N` suffix instead
+    stable: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct MetaLauncherMeta {
+    libraries: MetaLibraries,
+}
+
+#[derive(Deserialize)]
+struct MetaLibraries {
+    #[serde(default)]
+    common: Vec<MetaLibrary>,
+    #[serde(default)]
+    client: Vec<MetaLibrary>,
+}
+
+#[derive(Deserialize)]
+struct MetaLibrary {
+    name: String,
+    url: Option<String>,
+    sha1: Option<String>,
+    size: Option<u64>,
+}
+
+fn maven_library(name: String, repository: Option<String>, sha1: Option<String>, size: Option<u64>) -> Library {
+    let mut library = Library {
+        downloads: None,
+        name,
+        rules: None,
+        natives: None,
+        extract: None,
+        url: repository,
+        sha1,
+    };
+    if let (Some(sha1), Some(size), Some(url)) = (&library.sha1, size, library.maven_url()) {
+        library.downloads = Some(LibraryDownloads {
+            artifact: Some(DownloadInfo { sha1: sha1.clone(), size, url }),
+            classifiers: None,
+        });
     }
+    library
+}
+
+/// Turn a Fabric-style meta server's loader list into `ModLoader`s. The loader jar itself is
+/// `build_info.maven`/`url`; `libraries` holds the intermediary mappings and the client libraries.
+fn parse_meta_loaders(
+    body: &[u8],
+    loader_type: ModLoaderType,
+    minecraft_version: &str,
+    loader_maven: &str,
+    intermediary_maven: &str,
+) -> Result<Vec<ModLoader>> {
+    let entries: Vec<MetaLoaderEntry> = serde_json::from_slice(body)
+        .map_err(|e| LauncherError::json(format!("Failed to parse {} versions: {}", loader_type, e)))?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let loader = entry.loader;
+            let mut libraries = Vec::new();
+            if let Some(intermediary) = entry.intermediary {
+                libraries.push(maven_library(intermediary.maven, Some(intermediary_maven.to_string()), None, None));
+            }
+            let meta_libraries = entry.launcher_meta.libraries;
+            libraries.extend(
+                meta_libraries
+                    .common
+                    .into_iter()
+                    .chain(meta_libraries.client)
+                    .map(|library| maven_library(library.name, library.url, library.sha1, library.size)),
+            );
+
+            let url = maven_library(loader.maven.clone(), Some(loader_maven.to_string()), None, None).maven_url();
+            ModLoader {
+                loader_type: loader_type.clone(),
+                stable: loader.stable.unwrap_or_else(|| !loader.version.contains('-')),
+                minecraft_version: minecraft_version.to_string(),
+                build_info: ModLoaderBuildInfo {
+                    build: loader.build.map(|build| build.to_string()).unwrap_or_else(|| loader.version.clone()),
+                    url,
+                    maven: Some(loader.maven),
+                    libraries,
+                },
+                version: loader.version,
+            }
+        })
+        .collect())
+}
+
+/// `id` of a raw version JSON, or an empty string if it has none
+fn profile_id(profile: &serde_json::Value) -> String {
+    profile.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string()
+}
+
+/// Where to load the parent `parent_id` of a profile loaded from `child`
+fn parent_source(child: &VersionSource, parent_id: &str) -> VersionSource {
+    if let VersionSource::LocalFile(path) = child {
+        let versions_dir = path.parent().and_then(|dir| dir.parent());
+        if let Some(versions_dir) = versions_dir {
+            let candidate = versions_dir.join(parent_id).join(format!("{}.json", parent_id));
+            if candidate.is_file() {
+                return VersionSource::LocalFile(candidate);
+            }
+        }
+    }
+    VersionSource::ManifestId(parent_id.to_string())
+}
+
+/// Library coordinates without the version, so a child's library replaces the parent's copy
+fn library_key(library: &serde_json::Value) -> Option<String> {
+    let name = library.get("name")?.as_str()?;
+    let parts: Vec<&str> = name.split(':').collect();
+    let mut key = parts.iter().take(2).copied().collect::<Vec<_>>();
+    key.extend(parts.iter().skip(3));
+    Some(key.join(":"))
+}
+
+/// Merge a child profile onto its parent the way the vanilla launcher does
+///
+/// Child values replace the parent's, libraries are child first (dropping parent libraries the
+/// child overrides) and templated arguments are appended to the parent's. The result inherits
+/// from whatever the parent inherits from.
+fn merge_version_profiles(parent: serde_json::Value, child: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    let (Value::Object(mut merged), Value::Object(child)) = (parent, child) else {
+        return Value::Null;
+    };
+
+    for (key, value) in child {
+        match key.as_str() {
+            "inheritsFrom" => {}
+            "libraries" => {
+                let child_libraries = value.as_array().cloned().unwrap_or_default();
+                let overridden: Vec<Option<String>> = child_libraries.iter().map(library_key).collect();
+                let parent_libraries = merged
+                    .remove("libraries")
+                    .and_then(|v| v.as_array().cloned())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|library| {
+                        let key = library_key(library);
+                        key.is_none() || !overridden.contains(&key)
+                    });
+                let libraries: Vec<Value> = child_libraries.into_iter().chain(parent_libraries).collect();
+                merged.insert(key, Value::Array(libraries));
+            }
+            "arguments" => {
+                let mut arguments = merged.remove("arguments").unwrap_or_else(|| serde_json::json!({}));
+                for kind in ["game", "jvm"] {
+                    let extra = value.get(kind).and_then(|v| v.as_array()).cloned().unwrap_or_default();
+                    match arguments.get_mut(kind).and_then(|v| v.as_array_mut()) {
+                        Some(existing) => existing.extend(extra),
+                        None => arguments[kind] = Value::Array(extra),
+                    }
+                }
+                merged.insert(key, arguments);
+            }
+            _ => {
+                merged.insert(key, value);
+            }
+        }
+    }
+
+    Value::Object(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V2_ENTRY: &str = r#"{
+        "id": "1.21.4",
+        "type": "release",
+        "url": "https://piston-meta.mojang.com/v1/packages/abc/1.21.4.json",
+        "time": "2024-12-03T10:24:48+00:00",
+        "releaseTime": "2024-12-03T10:12:57+00:00",
+        "sha1": "a3bcba436caa849622fd7e1e5b89489ed6c9ac63",
+        "complianceLevel": 1
+    }"#;
+
+    const V1_ENTRY: &str = r#"{
+        "id": "1.21.4",
+        "type": "release",
+        "url": "https://piston-meta.mojang.com/v1/packages/abc/1.21.4.json",
+        "time": "2024-12-03T10:24:48+00:00",
+        "releaseTime": "2024-12-03T10:12:57+00:00"
+    }"#;
+
+    #[test]
+    fn parses_v2_entry_with_hash() {
+        let entry: VersionEntry = serde_json::from_str(V2_ENTRY).unwrap();
+        assert_eq!(entry.sha1.as_deref(), Some("a3bcba436caa849622fd7e1e5b89489ed6c9ac63"));
+        assert_eq!(entry.compliance_level, Some(1));
+    }
+
+    #[test]
+    fn parses_v1_entry_without_hash() {
+        let entry: VersionEntry = serde_json::from_str(V1_ENTRY).unwrap();
+        assert_eq!(entry.id, "1.21.4");
+        assert!(entry.sha1.is_none());
+        assert!(entry.compliance_level.is_none());
+    }
+
+    #[test]
+    fn parses_custom_manifest_without_hashes() {
+        let manifest = format!(
+            r#"{{"latest": {{"release": "1.21.4", "snapshot": "1.21.4"}}, "versions": [{}, {}]}}"#,
+            V1_ENTRY, V2_ENTRY
+        );
+        let manifest: VersionManifest = serde_json::from_str(&manifest).unwrap();
+        assert_eq!(manifest.versions.len(), 2);
+        assert!(manifest.versions[0].sha1.is_none());
+        assert!(manifest.versions[1].sha1.is_some());
+    }
+
+    #[test]
+    fn entry_without_hash_round_trips() {
+        let entry: VersionEntry = serde_json::from_str(V1_ENTRY).unwrap();
+        let json = serde_json::to_string(&entry).unwrap();
+        let entry: VersionEntry = serde_json::from_str(&json).unwrap();
+        assert!(entry.sha1.is_none());
+    }
+
+    #[test]
+    fn detects_vulnerable_log4j() {
+        let version_with = |log4j: &str| -> VersionInfo {
+            serde_json::from_value(serde_json::json!({
+                "id": "test",
+                "assetIndex": { "id": "1.12", "sha1": "", "size": 0, "url": "" },
+                "assets": "1.12",
+                "downloads": { "client": { "sha1": "", "size": 0, "url": "" } },
+                "libraries": [{ "name": format!("org.apache.logging.log4j:log4j-core:{}", log4j) }],
+                "mainClass": "net.minecraft.client.main.Main",
+                "releaseTime": "2017-09-18T08:39:46+00:00",
+                "time": "2017-09-18T08:39:46+00:00",
+                "type": "release"
+            }))
+            .unwrap()
+        };
+        assert!(version_with("2.0-beta9").is_log4shell_vulnerable());
+        assert!(version_with("2.14.1").is_log4shell_vulnerable());
+        assert!(!version_with("2.17.0").is_log4shell_vulnerable());
+        assert!(!version_with("2.19.0").is_log4shell_vulnerable());
+    }
+
+    #[test]
+    fn release_series_handles_snapshots_and_pre_releases() {
+        assert_eq!(release_series("1.20.1"), Some((1, 20, 1)));
+        assert_eq!(release_series("1.21"), Some((1, 21, 0)));
+        assert_eq!(release_series("1.21-pre1"), Some((1, 21, 0)));
+        assert_eq!(release_series("1.14 Pre-Release 2"), Some((1, 14, 0)));
+        assert_eq!(release_series("24w14a"), Some((1, 20, 5)));
+        assert_eq!(release_series("24w33a"), Some((1, 21, 2)));
+        assert_eq!(release_series("b1.7.3"), None);
+        assert!(ModLoaderType::NeoForge.supports_version("24w14a"));
+        assert!(!ModLoaderType::Fabric.supports_version("18w10a"));
+    }
+
+    #[test]
+    fn maven_versions_compare_numerically() {
+        use std::cmp::Ordering;
+
+        assert_eq!(compare_maven_versions("1.20.10", "1.20.2"), Ordering::Greater);
+        assert_eq!(compare_maven_versions("1.20", "1.20.0"), Ordering::Equal);
+        assert_eq!(compare_maven_versions("1.20.01", "1.20.1"), Ordering::Equal);
+        assert_eq!(compare_maven_versions("1.20.1", "1.20.1.1"), Ordering::Less);
+        assert_eq!(compare_maven_versions("10.0", "9.99"), Ordering::Greater);
+    }
+
+    #[test]
+    fn maven_versions_rank_qualifiers() {
+        use std::cmp::Ordering;
+
+        assert_eq!(compare_maven_versions("1.20.1-rc1", "1.20.1"), Ordering::Less);
+        assert_eq!(compare_maven_versions("1.20.1-rc2", "1.20.1-rc10"), Ordering::Less);
+        assert_eq!(compare_maven_versions("1.0-alpha", "1.0-beta"), Ordering::Less);
+        assert_eq!(compare_maven_versions("1.0-beta2", "1.0-rc1"), Ordering::Less);
+        assert_eq!(compare_maven_versions("1.21-pre1", "1.21-rc1"), Ordering::Less);
+        assert_eq!(compare_maven_versions("1.0-SNAPSHOT", "1.0"), Ordering::Less);
+        assert_eq!(compare_maven_versions("1.0-final", "1.0"), Ordering::Equal);
+        assert_eq!(compare_maven_versions("1.0-sp1", "1.0"), Ordering::Greater);
+        assert_eq!(compare_maven_versions("1.0.1", "1.0-beta"), Ordering::Greater);
+        assert_eq!(compare_maven_versions("0.16.10+build.3", "0.16.9"), Ordering::Greater);
+    }
+
+    #[test]
+    fn version_segment_equality_matches_ordering() {
+        assert_eq!(VersionSegment::qualifier("a"), VersionSegment::qualifier("alpha"));
+        assert_eq!(VersionSegment::qualifier("GA"), VersionSegment::qualifier("release"));
+        assert_ne!(VersionSegment::qualifier("foo"), VersionSegment::qualifier("bar"));
+        assert_eq!(version_segments("1.0-a1"), version_segments("1.0-alpha1"));
+        assert_eq!(compare_maven_versions("1.0-a1", "1.0-alpha1"), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn verify_sha1_matches_digest() {
+        // SHA1 of "hello"
+        let hash = "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d";
+        assert!(verify_sha1(b"hello", hash).is_ok());
+        assert!(verify_sha1(b"hello", &hash.to_uppercase()).is_ok());
+        assert!(verify_sha1(b"hello!", hash).is_err());
+    }
+
+    #[test]
+    fn merge_prefers_child_libraries_and_appends_arguments() {
+        let parent = serde_json::json!({
+            "id": "1.20.1",
+            "mainClass": "net.minecraft.client.main.Main",
+            "libraries": [{ "name": "org.ow2.asm:asm:9.3" }, { "name": "com.mojang:brigadier:1.1.8" }],
+            "arguments": { "game": ["--username", "${auth_player_name}"], "jvm": ["-cp", "${classpath}"] }
+        });
+        let child = serde_json::json!({
+            "id": "fabric-loader-0.15.0-1.20.1",
+            "inheritsFrom": "1.20.1",
+            "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+            "libraries": [{ "name": "org.ow2.asm:asm:9.6" }],
+            "arguments": { "jvm": ["-DFabricMcEmu=net.minecraft.client.main.Main"] }
+        });
+
+        let merged = merge_version_profiles(parent, child);
+        assert_eq!(merged["id"], "fabric-loader-0.15.0-1.20.1");
+        assert_eq!(merged["mainClass"], "net.fabricmc.loader.impl.launch.knot.KnotClient");
+        assert!(merged.get("inheritsFrom").is_none());
+        assert_eq!(
+            merged["libraries"],
+            serde_json::json!([{ "name": "org.ow2.asm:asm:9.6" }, { "name": "com.mojang:brigadier:1.1.8" }])
+        );
+        assert_eq!(merged["arguments"]["game"], serde_json::json!(["--username", "${auth_player_name}"]));
+        assert_eq!(
+            merged["arguments"]["jvm"],
+            serde_json::json!(["-cp", "${classpath}", "-DFabricMcEmu=net.minecraft.client.main.Main"])
+        );
+    }
+
+    #[tokio::test]
+    async fn inheritance_cycle_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let versions_dir = dir.path().join("versions");
+        for (id, parent) in [("a", "b"), ("b", "c"), ("c", "a")] {
+            std::fs::create_dir_all(versions_dir.join(id)).unwrap();
+            let profile = serde_json::json!({ "id": id, "inheritsFrom": parent });
+            std::fs::write(versions_dir.join(id).join(format!("{}.json", id)), profile.to_string()).unwrap();
+        }
+
+        let manager = VersionManager::new(dir.path().join("cache")).unwrap();
+        let source = VersionSource::LocalFile(versions_dir.join("a").join("a.json"));
+        let error = manager.resolve_version_info(&source).await.unwrap_err();
+        assert!(error.to_string().contains("a -> b -> c -> a"), "{}", error);
+    }
+
+    #[test]
+    fn endpoints_rewrite_only_mojang_hosts() {
+        let endpoints = MojangEndpoints {
+            meta: "http://127.0.0.1:8080/meta/".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(endpoints.rewrite(VERSION_MANIFEST_V2_URL), "http://127.0.0.1:8080/meta/mc/game/version_manifest_v2.json");
+        assert_eq!(
+            endpoints.rewrite("https://launchermeta.mojang.com/v1/packages/abc/1.12.2.json"),
+            "http://127.0.0.1:8080/meta/v1/packages/abc/1.12.2.json"
+        );
+        assert_eq!(
+            endpoints.rewrite("https://libraries.minecraft.net/a/b/1/b-1.jar"),
+            "https://libraries.minecraft.net/a/b/1/b-1.jar"
+        );
+        assert_eq!(endpoints.rewrite("https://piston-meta.mojang.com.evil/x"), "https://piston-meta.mojang.com.evil/x");
+
+        let endpoints = MojangEndpoints {
+            data: "http://127.0.0.1:8080/data".to_string(),
+            resources: "http://127.0.0.1:8080/resources".to_string(),
+            ..Default::default()
+        };
+        for host in ["https://piston-data.mojang.com", "https://launcher.mojang.com"] {
+            assert_eq!(
+                endpoints.rewrite(&format!("{}/v1/objects/abc/client.jar", host)),
+                "http://127.0.0.1:8080/data/v1/objects/abc/client.jar"
+            );
+        }
+        let object = AssetObject { hash: "abcdef".to_string(), size: 1 };
+        assert_eq!(endpoints.rewrite(&object.url()), "http://127.0.0.1:8080/resources/ab/abcdef");
+    }
+
+    #[test]
+    fn parses_fabric_meta_loader_list() {
+        let body = r#"[{
+            "loader": {"separator": ".", "build": 16, "maven": "net.fabricmc:fabric-loader:0.16.9", "version": "0.16.9", "stable": true},
+            "intermediary": {"maven": "net.fabricmc:intermediary:1.21.1", "version": "1.21.1", "stable": true},
+            "launcherMeta": {"version": 2, "libraries": {
+                "client": [],
+                "common": [{"name": "org.ow2.asm:asm:9.7.1", "url": "https://maven.fabricmc.net/", "sha1": "f0ed132a49244b042cd0e15702ab9f2ce3cc8436", "size": 126093}],
+                "server": [{"name": "ignored:server:1.0"}]
+            }, "mainClass": {"client": "net.fabricmc.loader.impl.launch.knot.KnotClient"}}
+        }]"#;
+
+        let loaders = parse_meta_loaders(body.as_bytes(), ModLoaderType::Fabric, "1.21.1", FABRIC_MAVEN, FABRIC_MAVEN).unwrap();
+        assert_eq!(loaders.len(), 1);
+        let loader = &loaders[0];
+        assert_eq!(loader.version, "0.16.9");
+        assert!(loader.stable);
+        assert_eq!(loader.build_info.build, "16");
+        assert_eq!(
+            loader.build_info.url.as_deref(),
+            Some("https://maven.fabricmc.net/net/fabricmc/fabric-loader/0.16.9/fabric-loader-0.16.9.jar")
+        );
+
+        let names: Vec<&str> = loader.build_info.libraries.iter().map(|library| library.name.as_str()).collect();
+        assert_eq!(names, ["net.fabricmc:intermediary:1.21.1", "org.ow2.asm:asm:9.7.1"]);
+        let asm = loader.build_info.libraries[1].downloads.as_ref().and_then(|d| d.artifact.as_ref()).unwrap();
+        assert_eq!(asm.size, 126093);
+        assert_eq!(asm.url, "https://maven.fabricmc.net/org/ow2/asm/asm/9.7.1/asm-9.7.1.jar");
+    }
+
+    #[test]
+    fn quilt_betas_are_unstable() {
+        let body = r#"[
+            {"loader": {"separator": ".", "build": 1, "maven": "org.quiltmc:quilt-loader:0.28.0-beta.1", "version": "0.28.0-beta.1"},
+             "launcherMeta": {"version": 1, "libraries": {"client": [], "common": []}}},
+            {"loader": {"separator": ".", "build": 0, "maven": "org.quiltmc:quilt-loader:0.27.1", "version": "0.27.1"},
+             "launcherMeta": {"version": 1, "libraries": {"client": [], "common": []}}}
+        ]"#;
 
-    async fn get_legacy_fabric_versions(&self, _minecraft_version: &str) -> Result<Vec<ModLoader>> {
-        // Implement Legacy Fabric API integration
-        Ok(Vec::new()) // Placeholder
+        let loaders = parse_meta_loaders(body.as_bytes(), ModLoaderType::Quilt, "1.21.1", QUILT_MAVEN, FABRIC_MAVEN).unwrap();
+        let stable: Vec<bool> = loaders.iter().map(|loader| loader.stable).collect();
+        assert_eq!(stable, [false, true]);
     }
 }
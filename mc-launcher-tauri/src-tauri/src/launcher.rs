@@ -239,9 +239,9 @@ pub async fn start_device_code_flow(
     
     if let Some(authenticator) = auth_guard.as_ref() {
         match authenticator.start_device_code_flow().await {
-            Ok(device_response) => {
+            Ok(flow) => {
                 log::info!("Device code flow started successfully");
-                Ok(LauncherResponse::success(device_response))
+                Ok(LauncherResponse::success(flow.response().clone()))
             }
             Err(e) => {
                 log::error!("Device code flow failed: {}", e);
@@ -368,7 +368,10 @@ pub async fn get_java_runtime(
 
     if let Some(launcher) = launcher_guard.as_ref() {
         let minecraft_dir = launcher.get_config().minecraft_dir.clone();
-        let java_manager = JavaManager::new(minecraft_dir.join("runtime"));
+        let java_manager = match JavaManager::new(minecraft_dir.join("runtime")) {
+            Ok(java_manager) => java_manager,
+            Err(e) => return Ok(LauncherResponse::error(format!("Failed to set up Java manager: {}", e))),
+        };
 
         match java_manager.get_java_runtime(&version).await {
             Ok(Some(java_path)) => {